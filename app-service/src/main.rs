@@ -1,17 +1,18 @@
-use std::env;
+use std::{env, time::Duration};
 
 use askama::Template;
+use auth_middleware::{verifier::RemoteVerifier, AuthLayer, AuthUser, Authenticator};
 use axum::{
-    http::StatusCode,
     response::{Html, IntoResponse},
     routing::get,
     Json, Router,
 };
-use axum_extra::extract::CookieJar;
 use serde::Serialize;
 use tokio::signal;
 use tower_http::services::ServeDir;
 
+const VERIFY_TOKEN_TIMEOUT: Duration = Duration::from_secs(5);
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
@@ -38,10 +39,28 @@ async fn shutdown_signal() {
 
 #[tokio::main]
 async fn main() {
+    let http_client = reqwest::Client::builder()
+        .timeout(VERIFY_TOKEN_TIMEOUT)
+        .build()
+        .expect("Failed to build HTTP client");
+    let mut verifier = RemoteVerifier::new(
+        load_or_default("AUTH_SERVICE_CONTAINER_ADDRESS", "http://localhost:3000"),
+        http_client,
+    );
+    // The auth service only verifies tokens for known service accounts
+    if let (Ok(client_id), Ok(client_secret)) = (
+        env::var("APP_SERVICE_CLIENT_ID"),
+        env::var("APP_SERVICE_CLIENT_SECRET"),
+    ) {
+        verifier = verifier.with_client_credentials(&client_id, &client_secret);
+    }
+    let authenticator = Authenticator::new(verifier);
+
     let app = Router::new()
+        .route("/protected", get(protected))
+        .route_layer(AuthLayer::new(authenticator))
         .nest_service("/assets", ServeDir::new("assets"))
-        .route("/", get(root))
-        .route("/protected", get(protected));
+        .route("/", get(root));
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:8000").await.unwrap();
 
@@ -60,20 +79,11 @@ struct IndexTemplate {
 }
 
 async fn root() -> impl IntoResponse {
-    let default_address = String::from("http://localhost:3000");
-    let auth_service_external_address = match env::var("AUTH_SERVICE_EXTERNAL_ADDRESS") {
-        Ok(address) => {
-            if address.is_empty() {
-                default_address
-            } else {
-                address
-            }
-        }
-        Err(_) => default_address,
-    };
+    let auth_service_external_address =
+        load_or_default("AUTH_SERVICE_EXTERNAL_ADDRESS", "http://localhost:3000");
 
-    let login_link = format!("{}", auth_service_external_address);
     let logout_link = format!("{}/logout", auth_service_external_address);
+    let login_link = auth_service_external_address;
 
     let template = IndexTemplate {
         login_link,
@@ -82,50 +92,16 @@ async fn root() -> impl IntoResponse {
     Html(template.render().unwrap())
 }
 
-async fn protected(jar: CookieJar) -> impl IntoResponse {
-    let jwt_cookie = match jar.get("jwt") {
-        Some(cookie) => cookie,
-        None => {
-            return StatusCode::UNAUTHORIZED.into_response();
-        }
-    };
-
-    let api_client = reqwest::Client::builder().build().unwrap();
-
-    let verify_token_body = serde_json::json!({
-        "token": &jwt_cookie.value(),
-    });
-
-    let default_address = String::from("http://localhost:3000");
-    let auth_service_container_address = match env::var("AUTH_SERVICE_CONTAINER_ADDRESS") {
-        Ok(address) => {
-            if address.is_empty() {
-                default_address
-            } else {
-                address
-            }
-        }
-        Err(_) => default_address,
-    };
-
-    let url = format!("{}/verify-token", auth_service_container_address);
-
-    let response = match api_client.post(&url).json(&verify_token_body).send().await {
-        Ok(response) => response,
-        Err(_) => {
-            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
-        }
-    };
+async fn protected(AuthUser(_claims): AuthUser) -> impl IntoResponse {
+    Json(ProtectedRouteResponse {
+        img_url: "https://i.ibb.co/YP90j68/Light-Live-Bootcamp-Certificate.png".to_owned(),
+    })
+}
 
-    match response.status() {
-        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::BAD_REQUEST => {
-            StatusCode::UNAUTHORIZED.into_response()
-        }
-        reqwest::StatusCode::OK => Json(ProtectedRouteResponse {
-            img_url: "https://i.ibb.co/YP90j68/Light-Live-Bootcamp-Certificate.png".to_owned(),
-        })
-        .into_response(),
-        _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+fn load_or_default(variable_name: &str, default_value: &str) -> String {
+    match env::var(variable_name) {
+        Ok(value) if !value.is_empty() => value,
+        _ => String::from(default_value),
     }
 }
 
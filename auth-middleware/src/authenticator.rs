@@ -0,0 +1,125 @@
+use axum::http::{header::AUTHORIZATION, HeaderMap};
+use axum_extra::extract::CookieJar;
+use std::sync::Arc;
+
+use crate::{verifier::TokenVerifier, AuthError, Claims};
+
+pub const DEFAULT_COOKIE_NAME: &str = "jwt";
+
+// Finds the token on a request and hands it to the configured verifier.
+// Cheap to clone, so it can be shared between the layer and the extractor.
+#[derive(Clone)]
+pub struct Authenticator {
+    verifier: Arc<dyn TokenVerifier + Send + Sync>,
+    cookie_name: String,
+}
+
+impl Authenticator {
+    pub fn new(verifier: impl TokenVerifier + Send + Sync + 'static) -> Self {
+        Self {
+            verifier: Arc::new(verifier),
+            cookie_name: DEFAULT_COOKIE_NAME.to_owned(),
+        }
+    }
+
+    pub fn with_cookie_name(mut self, cookie_name: &str) -> Self {
+        self.cookie_name = cookie_name.to_owned();
+        self
+    }
+
+    #[tracing::instrument(name = "Authenticating request", skip_all)]
+    pub async fn authenticate(
+        &self,
+        headers: &HeaderMap,
+    ) -> Result<Claims, AuthError> {
+        let token =
+            self.extract_token(headers).ok_or(AuthError::MissingToken)?;
+        self.verifier.verify(&token).await
+    }
+
+    // The auth cookie takes precedence over an `Authorization: Bearer` header
+    fn extract_token(&self, headers: &HeaderMap) -> Option<String> {
+        if let Some(cookie) =
+            CookieJar::from_headers(headers).get(&self.cookie_name)
+        {
+            return Some(cookie.value().to_owned());
+        }
+
+        headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| token.trim().to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{header::COOKIE, HeaderValue};
+
+    struct AcceptAll;
+
+    #[async_trait::async_trait]
+    impl TokenVerifier for AcceptAll {
+        async fn verify(&self, token: &str) -> Result<Claims, AuthError> {
+            Ok(Claims {
+                sub: token.to_owned(),
+                exp: 0,
+                extra: Default::default(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_is_read_from_cookie() {
+        let mut headers = HeaderMap::new();
+        headers.insert(COOKIE, HeaderValue::from_static("jwt=from_cookie"));
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_static("Bearer from_header"),
+        );
+
+        let claims = Authenticator::new(AcceptAll)
+            .authenticate(&headers)
+            .await
+            .unwrap();
+        assert_eq!(claims.sub, "from_cookie");
+    }
+
+    #[tokio::test]
+    async fn test_token_is_read_from_bearer_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_static("Bearer from_header"),
+        );
+
+        let claims = Authenticator::new(AcceptAll)
+            .authenticate(&headers)
+            .await
+            .unwrap();
+        assert_eq!(claims.sub, "from_header");
+    }
+
+    #[tokio::test]
+    async fn test_custom_cookie_name() {
+        let mut headers = HeaderMap::new();
+        headers.insert(COOKIE, HeaderValue::from_static("jwt=a; session=b"));
+
+        let claims = Authenticator::new(AcceptAll)
+            .with_cookie_name("session")
+            .authenticate(&headers)
+            .await
+            .unwrap();
+        assert_eq!(claims.sub, "b");
+    }
+
+    #[tokio::test]
+    async fn test_missing_token() {
+        let result = Authenticator::new(AcceptAll)
+            .authenticate(&HeaderMap::new())
+            .await;
+        assert!(matches!(result, Err(AuthError::MissingToken)));
+    }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Claims {
+    pub sub: String,
+    pub exp: usize,
+    // Any other claims carried by the token, e.g. `aud` or `scope`
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
@@ -0,0 +1,31 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use thiserror::Error;
+
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("Missing token")]
+    MissingToken,
+    #[error("Invalid token")]
+    InvalidToken,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] BoxError),
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        match self {
+            AuthError::MissingToken | AuthError::InvalidToken => {
+                StatusCode::UNAUTHORIZED.into_response()
+            }
+            AuthError::UnexpectedError(e) => {
+                tracing::error!("Failed to authenticate request: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
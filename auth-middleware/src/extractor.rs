@@ -0,0 +1,104 @@
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+
+use crate::{AuthError, Authenticator, Claims};
+
+// Extracts the verified claims of the caller.
+// Behind `AuthLayer` the claims are already in the request extensions.
+// Without the layer, an `Authenticator` must be provided as an `Extension`.
+#[derive(Clone, Debug)]
+pub struct AuthUser(pub Claims);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        if let Some(claims) = parts.extensions.get::<Claims>() {
+            return Ok(AuthUser(claims.clone()));
+        }
+
+        let authenticator = parts
+            .extensions
+            .get::<Authenticator>()
+            .cloned()
+            .ok_or_else(|| {
+                AuthError::UnexpectedError("no Authenticator configured".into())
+            })?;
+        let claims = authenticator.authenticate(&parts.headers).await?;
+        parts.extensions.insert(claims.clone());
+
+        Ok(AuthUser(claims))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::TokenVerifier;
+    use axum::{
+        body::Body,
+        http::{header::COOKIE, Request, StatusCode},
+        routing::get,
+        Extension, Router,
+    };
+    use tower::ServiceExt;
+
+    struct AcceptAll;
+
+    #[async_trait::async_trait]
+    impl TokenVerifier for AcceptAll {
+        async fn verify(&self, token: &str) -> Result<Claims, AuthError> {
+            Ok(Claims {
+                sub: token.to_owned(),
+                exp: 0,
+                extra: Default::default(),
+            })
+        }
+    }
+
+    async fn handler(AuthUser(claims): AuthUser) -> String {
+        claims.sub
+    }
+
+    #[tokio::test]
+    async fn test_extractor_uses_authenticator_extension() {
+        let app = Router::new()
+            .route("/", get(handler))
+            .layer(Extension(Authenticator::new(AcceptAll)));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header(COOKIE, "jwt=token")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_extractor_without_authenticator_fails() {
+        let app = Router::new().route("/", get(handler));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header(COOKIE, "jwt=token")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
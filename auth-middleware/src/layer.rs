@@ -0,0 +1,152 @@
+use axum::{
+    body::Body,
+    http::Request,
+    response::{IntoResponse, Response},
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::Authenticator;
+
+// Rejects requests without a valid token. Accepted requests carry their
+// `Claims` in the request extensions, where `AuthUser` picks them up.
+#[derive(Clone)]
+pub struct AuthLayer {
+    authenticator: Authenticator,
+}
+
+impl AuthLayer {
+    pub fn new(authenticator: Authenticator) -> Self {
+        Self { authenticator }
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = AuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService {
+            inner,
+            authenticator: self.authenticator.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthService<S> {
+    inner: S,
+    authenticator: Authenticator,
+}
+
+type ResponseFuture<E> =
+    Pin<Box<dyn Future<Output = Result<Response, E>> + Send + 'static>>;
+
+impl<S> Service<Request<Body>> for AuthService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Error>;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        // Keep the service that was polled ready, leaving a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let authenticator = self.authenticator.clone();
+
+        Box::pin(async move {
+            match authenticator.authenticate(request.headers()).await {
+                Ok(claims) => {
+                    request.extensions_mut().insert(claims);
+                    inner.call(request).await
+                }
+                Err(err) => Ok(err.into_response()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{verifier::TokenVerifier, AuthError, AuthUser, Claims};
+    use axum::{
+        http::{header::COOKIE, StatusCode},
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    struct SingleTokenVerifier;
+
+    #[async_trait::async_trait]
+    impl TokenVerifier for SingleTokenVerifier {
+        async fn verify(&self, token: &str) -> Result<Claims, AuthError> {
+            match token {
+                "valid" => Ok(Claims {
+                    sub: "test@example.com".to_owned(),
+                    exp: 0,
+                    extra: Default::default(),
+                }),
+                _ => Err(AuthError::InvalidToken),
+            }
+        }
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/protected",
+                get(|AuthUser(claims): AuthUser| async move { claims.sub }),
+            )
+            .route_layer(AuthLayer::new(Authenticator::new(
+                SingleTokenVerifier,
+            )))
+    }
+
+    fn request(cookie: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri("/protected");
+        if let Some(cookie) = cookie {
+            builder = builder.header(COOKIE, cookie);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_valid_token_reaches_handler() {
+        let response = app().oneshot(request(Some("jwt=valid"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, "test@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_token_is_rejected() {
+        let response =
+            app().oneshot(request(Some("jwt=invalid"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_missing_token_is_rejected() {
+        let response = app().oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
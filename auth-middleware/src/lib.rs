@@ -0,0 +1,12 @@
+mod authenticator;
+mod claims;
+mod error;
+mod extractor;
+mod layer;
+pub mod verifier;
+
+pub use authenticator::*;
+pub use claims::*;
+pub use error::*;
+pub use extractor::*;
+pub use layer::*;
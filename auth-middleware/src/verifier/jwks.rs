@@ -0,0 +1,201 @@
+use jsonwebtoken::{
+    decode, decode_header, jwk::JwkSet, DecodingKey, Validation,
+};
+use reqwest::Client;
+
+use super::TokenVerifier;
+use crate::{AuthError, Claims};
+
+// Verifies tokens locally against a JSON Web Key Set, without calling the
+// auth service. Revoked tokens are not detected in this mode. The set only
+// holds ID token keys, so the audience is required: ID tokens issued to other
+// clients must not pass as credentials here.
+pub struct JwksVerifier {
+    jwks: JwkSet,
+    validation: Validation,
+}
+
+impl JwksVerifier {
+    pub fn new(jwks: JwkSet, audience: &str) -> Self {
+        let mut validation = Validation::default();
+        validation.set_audience(&[audience]);
+        validation.set_required_spec_claims(&["exp", "aud"]);
+        Self { jwks, validation }
+    }
+
+    #[tracing::instrument(name = "Fetching JWKS", skip_all)]
+    pub async fn fetch(
+        jwks_url: &str,
+        audience: &str,
+        http_client: &Client,
+    ) -> Result<Self, AuthError> {
+        let jwks = http_client
+            .get(jwks_url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| AuthError::UnexpectedError(e.into()))?
+            .json::<JwkSet>()
+            .await
+            .map_err(|e| AuthError::UnexpectedError(e.into()))?;
+
+        Ok(Self::new(jwks, audience))
+    }
+
+    pub fn with_issuer(mut self, issuer: &str) -> Self {
+        self.validation.set_issuer(&[issuer]);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenVerifier for JwksVerifier {
+    #[tracing::instrument(name = "Verifying token against JWKS", skip_all)]
+    async fn verify(&self, token: &str) -> Result<Claims, AuthError> {
+        let header =
+            decode_header(token).map_err(|_| AuthError::InvalidToken)?;
+
+        // Without a `kid`, only an unambiguous single-key set can be used
+        let jwk = match &header.kid {
+            Some(kid) => self.jwks.find(kid),
+            None if self.jwks.keys.len() == 1 => self.jwks.keys.first(),
+            None => None,
+        }
+        .ok_or(AuthError::InvalidToken)?;
+
+        let key =
+            DecodingKey::from_jwk(jwk).map_err(|_| AuthError::InvalidToken)?;
+
+        // `decode` rejects algorithms that don't match the key's family
+        let mut validation = self.validation.clone();
+        validation.algorithms = vec![header.alg];
+
+        decode::<Claims>(token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|_| AuthError::InvalidToken)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SECRET: &[u8] = b"secret";
+    // `SECRET` base64url-encoded, as JWKs expect
+    const SECRET_B64: &str = "c2VjcmV0";
+    const AUDIENCE: &str = "client";
+
+    fn jwks() -> JwkSet {
+        serde_json::from_value(serde_json::json!({
+            "keys": [{ "kty": "oct", "kid": "key-1", "k": SECRET_B64 }]
+        }))
+        .unwrap()
+    }
+
+    fn token(
+        kid: Option<&str>,
+        secret: &[u8],
+        claims: serde_json::Value,
+    ) -> String {
+        let header = Header {
+            kid: kid.map(str::to_owned),
+            ..Default::default()
+        };
+        encode(&header, &claims, &EncodingKey::from_secret(secret)).unwrap()
+    }
+
+    fn claims() -> serde_json::Value {
+        let exp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 600;
+        serde_json::json!({
+            "sub": "test@example.com",
+            "exp": exp,
+            "iss": "auth-service",
+            "aud": AUDIENCE,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_valid_token() {
+        let token = token(Some("key-1"), SECRET, claims());
+        let claims = JwksVerifier::new(jwks(), AUDIENCE)
+            .verify(&token)
+            .await
+            .unwrap();
+        assert_eq!(claims.sub, "test@example.com");
+        assert_eq!(claims.extra["iss"], "auth-service");
+    }
+
+    #[tokio::test]
+    async fn test_single_key_is_used_without_kid() {
+        let token = token(None, SECRET, claims());
+        assert!(JwksVerifier::new(jwks(), AUDIENCE)
+            .verify(&token)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kid_is_invalid() {
+        let token = token(Some("key-2"), SECRET, claims());
+        let result = JwksVerifier::new(jwks(), AUDIENCE).verify(&token).await;
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn test_wrong_signature_is_invalid() {
+        let token = token(Some("key-1"), b"not the secret", claims());
+        let result = JwksVerifier::new(jwks(), AUDIENCE).verify(&token).await;
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn test_issuer_is_enforced() {
+        let token = token(Some("key-1"), SECRET, claims());
+        let verifier =
+            JwksVerifier::new(jwks(), AUDIENCE).with_issuer("someone-else");
+        let result = verifier.verify(&token).await;
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn test_audience_is_enforced() {
+        let verifier = JwksVerifier::new(jwks(), "someone-else");
+        let result = verifier
+            .verify(&token(Some("key-1"), SECRET, claims()))
+            .await;
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+
+        let mut claims = claims();
+        claims.as_object_mut().unwrap().remove("aud");
+        let result = JwksVerifier::new(jwks(), AUDIENCE)
+            .verify(&token(Some("key-1"), SECRET, claims))
+            .await;
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_jwks() {
+        let mock_server = MockServer::start().await;
+        Mock::given(path("/.well-known/jwks.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(jwks()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let url = format!("{}/.well-known/jwks.json", mock_server.uri());
+        let verifier = JwksVerifier::fetch(&url, AUDIENCE, &Client::new())
+            .await
+            .unwrap();
+
+        let token = token(Some("key-1"), SECRET, claims());
+        assert!(verifier.verify(&token).await.is_ok());
+    }
+}
@@ -0,0 +1,12 @@
+mod jwks;
+mod remote;
+
+pub use jwks::*;
+pub use remote::*;
+
+use crate::{AuthError, Claims};
+
+#[async_trait::async_trait]
+pub trait TokenVerifier {
+    async fn verify(&self, token: &str) -> Result<Claims, AuthError>;
+}
@@ -0,0 +1,385 @@
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::Mutex;
+
+use super::TokenVerifier;
+use crate::{AuthError, Claims};
+
+// Verifies tokens by asking the auth service's `/verify-token` route.
+// The HTTP client is reused across requests, and accepted tokens are cached
+// briefly so that repeat requests don't each cost a round trip.
+pub struct RemoteVerifier {
+    http_client: Client,
+    base_url: String,
+    cache_ttl: Duration,
+    cache: Mutex<HashMap<String, CachedClaims>>,
+    credentials: Option<ClientCredentials>,
+    service_token: Mutex<Option<ServiceToken>>,
+}
+
+struct ClientCredentials {
+    client_id: String,
+    client_secret: String,
+}
+
+struct ServiceToken {
+    token: String,
+    renew_at: SystemTime,
+}
+
+struct CachedClaims {
+    claims: Claims,
+    cached_until: SystemTime,
+}
+
+// A logged-out token can still be accepted until its cache entry expires,
+// so keep this short
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(30);
+
+// Service tokens are renewed this long before they expire
+const SERVICE_TOKEN_LEEWAY: Duration = Duration::from_secs(30);
+
+// The scope the auth service requires of callers to `/verify-token`
+pub const VERIFY_TOKEN_SCOPE: &str = "tokens:verify";
+
+impl RemoteVerifier {
+    pub fn new(base_url: String, http_client: Client) -> Self {
+        Self {
+            http_client,
+            base_url,
+            cache_ttl: DEFAULT_CACHE_TTL,
+            cache: Mutex::new(HashMap::new()),
+            credentials: None,
+            service_token: Mutex::new(None),
+        }
+    }
+
+    // Authenticate to the auth service as a service account, using tokens
+    // from the client_credentials grant
+    pub fn with_client_credentials(
+        mut self,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Self {
+        self.credentials = Some(ClientCredentials {
+            client_id: client_id.to_owned(),
+            client_secret: client_secret.to_owned(),
+        });
+        self
+    }
+
+    // A TTL of zero disables caching
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    async fn get_cached(&self, token: &str) -> Option<Claims> {
+        let cache = self.cache.lock().await;
+        cache
+            .get(token)
+            .filter(|entry| entry.cached_until > SystemTime::now())
+            .map(|entry| entry.claims.clone())
+    }
+
+    async fn cache_claims(&self, token: &str, claims: &Claims) {
+        if self.cache_ttl.is_zero() {
+            return;
+        }
+
+        let now = SystemTime::now();
+        let expires_at = UNIX_EPOCH + Duration::from_secs(claims.exp as u64);
+        let cached_until = (now + self.cache_ttl).min(expires_at);
+
+        let mut cache = self.cache.lock().await;
+        cache.retain(|_, entry| entry.cached_until > now);
+        cache.insert(
+            token.to_owned(),
+            CachedClaims {
+                claims: claims.clone(),
+                cached_until,
+            },
+        );
+    }
+
+    #[tracing::instrument(name = "Fetching service token", skip_all)]
+    async fn get_service_token(&self) -> Result<Option<String>, AuthError> {
+        let Some(credentials) = &self.credentials else {
+            return Ok(None);
+        };
+
+        // Held across the request, so concurrent callers share one token
+        let mut service_token = self.service_token.lock().await;
+        let now = SystemTime::now();
+        if let Some(cached) = service_token.as_ref() {
+            if cached.renew_at > now {
+                return Ok(Some(cached.token.clone()));
+            }
+        }
+
+        let url = format!("{}/oauth/token", self.base_url);
+        let response = self
+            .http_client
+            .post(&url)
+            .basic_auth(
+                &credentials.client_id,
+                Some(&credentials.client_secret),
+            )
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("scope", VERIFY_TOKEN_SCOPE),
+            ])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| AuthError::UnexpectedError(e.into()))?
+            .json::<TokenResponse>()
+            .await
+            .map_err(|e| AuthError::UnexpectedError(e.into()))?;
+
+        let lifetime = Duration::from_secs(response.expires_in)
+            .saturating_sub(SERVICE_TOKEN_LEEWAY);
+        *service_token = Some(ServiceToken {
+            token: response.access_token.clone(),
+            renew_at: now + lifetime,
+        });
+
+        Ok(Some(response.access_token))
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenVerifier for RemoteVerifier {
+    #[tracing::instrument(name = "Verifying token with auth service", skip_all)]
+    async fn verify(&self, token: &str) -> Result<Claims, AuthError> {
+        if let Some(claims) = self.get_cached(token).await {
+            return Ok(claims);
+        }
+
+        let url = format!("{}/verify-token", self.base_url);
+        let mut request = self
+            .http_client
+            .post(&url)
+            .json(&VerifyTokenRequest { token });
+        if let Some(service_token) = self.get_service_token().await? {
+            request = request.bearer_auth(service_token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AuthError::UnexpectedError(e.into()))?;
+
+        match response.status() {
+            StatusCode::OK => (),
+            StatusCode::UNAUTHORIZED | StatusCode::BAD_REQUEST => {
+                return Err(AuthError::InvalidToken)
+            }
+            status => {
+                return Err(AuthError::UnexpectedError(
+                    format!("unexpected status from auth service: {}", status)
+                        .into(),
+                ))
+            }
+        }
+
+        let claims = read_claims(token)?;
+        self.cache_claims(token, &claims).await;
+
+        Ok(claims)
+    }
+}
+
+#[derive(Serialize)]
+struct VerifyTokenRequest<'a> {
+    token: &'a str,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+// The auth service has already checked the signature, so only the payload
+// needs decoding here
+fn read_claims(token: &str) -> Result<Claims, AuthError> {
+    let mut validation = Validation::default();
+    validation.insecure_disable_signature_validation();
+    validation.validate_aud = false;
+
+    decode::<Claims>(token, &DecodingKey::from_secret(&[]), &validation)
+        .map(|data| data.claims)
+        .map_err(|_| AuthError::InvalidToken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use wiremock::matchers::{
+        basic_auth, body_json, body_string_contains, header, method, path,
+    };
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn token(sub: &str) -> String {
+        let exp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as usize
+            + 600;
+        let claims = serde_json::json!({ "sub": sub, "exp": exp });
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap()
+    }
+
+    fn verifier(base_url: String) -> RemoteVerifier {
+        RemoteVerifier::new(base_url, Client::new())
+    }
+
+    #[tokio::test]
+    async fn test_accepted_token_returns_claims() {
+        let mock_server = MockServer::start().await;
+        let token = token("test@example.com");
+
+        Mock::given(method("POST"))
+            .and(path("/verify-token"))
+            .and(body_json(serde_json::json!({ "token": token })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let claims = verifier(mock_server.uri()).verify(&token).await.unwrap();
+        assert_eq!(claims.sub, "test@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_accepted_token_is_cached() {
+        let mock_server = MockServer::start().await;
+        let token = token("test@example.com");
+
+        Mock::given(path("/verify-token"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let verifier = verifier(mock_server.uri());
+        assert!(verifier.verify(&token).await.is_ok());
+        assert!(verifier.verify(&token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cache_can_be_disabled() {
+        let mock_server = MockServer::start().await;
+        let token = token("test@example.com");
+
+        Mock::given(path("/verify-token"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let verifier =
+            verifier(mock_server.uri()).with_cache_ttl(Duration::ZERO);
+        assert!(verifier.verify(&token).await.is_ok());
+        assert!(verifier.verify(&token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_token_is_invalid() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(path("/verify-token"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let verifier = verifier(mock_server.uri());
+        let token = token("test@example.com");
+        for _ in 0..2 {
+            let result = verifier.verify(&token).await;
+            assert!(matches!(result, Err(AuthError::InvalidToken)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_error_is_unexpected() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(path("/verify-token"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let result = verifier(mock_server.uri())
+            .verify(&token("test@example.com"))
+            .await;
+        assert!(matches!(result, Err(AuthError::UnexpectedError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_service_token_is_sent_and_reused() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/oauth/token"))
+            .and(basic_auth("app-service", "secret"))
+            .and(body_string_contains("grant_type=client_credentials"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({
+                    "access_token": "service-token",
+                    "token_type": "Bearer",
+                    "expires_in": 300
+                }),
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(path("/verify-token"))
+            .and(header("authorization", "Bearer service-token"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let verifier = verifier(mock_server.uri())
+            .with_cache_ttl(Duration::ZERO)
+            .with_client_credentials("app-service", "secret");
+        let token = token("test@example.com");
+        assert!(verifier.verify(&token).await.is_ok());
+        assert!(verifier.verify(&token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_client_credentials_are_unexpected() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(path("/oauth/token"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+        Mock::given(path("/verify-token"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let result = verifier(mock_server.uri())
+            .with_client_credentials("app-service", "wrong")
+            .verify(&token("test@example.com"))
+            .await;
+        assert!(matches!(result, Err(AuthError::UnexpectedError(_))));
+    }
+}
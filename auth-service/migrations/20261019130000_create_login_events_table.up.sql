@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS login_events (
+    id BIGSERIAL PRIMARY KEY,
+    email TEXT NOT NULL REFERENCES users (email) ON DELETE CASCADE,
+    created_at BIGINT NOT NULL,
+    ip TEXT,
+    user_agent TEXT,
+    method TEXT NOT NULL,
+    success BOOLEAN NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS login_events_email_created_at_idx
+    ON login_events (email, created_at);
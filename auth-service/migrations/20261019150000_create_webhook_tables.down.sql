@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS webhook_dead_letters;
+DROP TABLE IF EXISTS webhook_deliveries;
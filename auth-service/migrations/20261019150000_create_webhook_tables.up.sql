@@ -0,0 +1,22 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    delivery_id TEXT NOT NULL PRIMARY KEY,
+    endpoint TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    next_attempt_at BIGINT NOT NULL,
+    last_error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_next_attempt_at_idx
+    ON webhook_deliveries (next_attempt_at);
+
+-- Deliveries that ran out of retries, kept to be inspected or replayed
+CREATE TABLE IF NOT EXISTS webhook_dead_letters (
+    delivery_id TEXT NOT NULL PRIMARY KEY,
+    endpoint TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    last_error TEXT,
+    failed_at BIGINT NOT NULL
+);
@@ -0,0 +1,16 @@
+-- Add up migration script here
+-- Emails waiting to be sent. Rows are deleted once sent, and kept with
+-- failed_at set once they run out of retries
+CREATE TABLE IF NOT EXISTS email_outbox (
+    id TEXT NOT NULL PRIMARY KEY,
+    recipient TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    content TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    next_attempt_at BIGINT NOT NULL,
+    last_error TEXT,
+    failed_at BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS email_outbox_pending_idx
+    ON email_outbox (next_attempt_at) WHERE failed_at IS NULL;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE email_outbox DROP COLUMN html_body;
+ALTER TABLE email_outbox RENAME COLUMN text_body TO content;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE users
+    DROP COLUMN email_undeliverable_reason,
+    DROP COLUMN email_undeliverable_at;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- Set from the email provider's bounce and spam complaint webhooks
+ALTER TABLE users
+    ADD COLUMN email_undeliverable_reason TEXT,
+    ADD COLUMN email_undeliverable_at BIGINT;
@@ -0,0 +1,16 @@
+-- Add up migration script here
+-- Hashes of each user's most recent passwords, including the current one, so
+-- they can't be used again
+CREATE TABLE IF NOT EXISTS password_history (
+    id BIGSERIAL PRIMARY KEY,
+    email TEXT NOT NULL REFERENCES users (email) ON DELETE CASCADE,
+    password_hash TEXT NOT NULL,
+    created_at BIGINT NOT NULL DEFAULT EXTRACT(EPOCH FROM now())::BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS password_history_email_id_idx
+    ON password_history (email, id);
+
+-- Existing users' current passwords start their history
+INSERT INTO password_history (email, password_hash)
+SELECT email, password_hash FROM users;
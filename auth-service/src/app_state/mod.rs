@@ -1,11 +1,46 @@
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::domain::{BannedTokenStore, EmailClient, TwoFACodeStore, UserStore};
+use crate::domain::{
+    AuditSink, AuthorizationCodeStore, BannedTokenStore,
+    BreachedPasswordChecker, ClaimsEnricher, DeviceCodeStore, EmailClient,
+    EmailDomainPolicy, EmailOutboxStore, LoginHistoryStore, OAuthClientStore,
+    PasswordPolicy, RefreshTokenStore, ServiceAccountStore, TrustedDeviceStore,
+    TwoFACodeStore, UserStore, WebhookPublisher, WebhookStore,
+};
+use crate::services::{
+    failover_email_client::FailoverEmailClient,
+    file_email_client::FileEmailClient,
+};
+use crate::utils::client_info::TrustedProxies;
 pub type UserStoreType = Arc<RwLock<dyn UserStore + Send + Sync>>;
 pub type BannedTokenStoreType = Arc<RwLock<dyn BannedTokenStore + Send + Sync>>;
 pub type TwoFACodeStoreType = Arc<RwLock<dyn TwoFACodeStore + Send + Sync>>;
 pub type EmailClientType = Arc<dyn EmailClient + Send + Sync>;
+pub type EmailOutboxStoreType = Arc<RwLock<dyn EmailOutboxStore + Send + Sync>>;
+pub type OAuthClientStoreType = Arc<RwLock<dyn OAuthClientStore + Send + Sync>>;
+pub type AuthorizationCodeStoreType =
+    Arc<RwLock<dyn AuthorizationCodeStore + Send + Sync>>;
+pub type RefreshTokenStoreType =
+    Arc<RwLock<dyn RefreshTokenStore + Send + Sync>>;
+pub type ServiceAccountStoreType =
+    Arc<RwLock<dyn ServiceAccountStore + Send + Sync>>;
+pub type DeviceCodeStoreType = Arc<RwLock<dyn DeviceCodeStore + Send + Sync>>;
+pub type TrustedDeviceStoreType =
+    Arc<RwLock<dyn TrustedDeviceStore + Send + Sync>>;
+pub type LoginHistoryStoreType =
+    Arc<RwLock<dyn LoginHistoryStore + Send + Sync>>;
+pub type ClaimsEnricherType = Arc<dyn ClaimsEnricher + Send + Sync>;
+pub type AuditSinkType = Arc<dyn AuditSink + Send + Sync>;
+pub type WebhookStoreType = Arc<RwLock<dyn WebhookStore + Send + Sync>>;
+pub type WebhookPublisherType = Arc<dyn WebhookPublisher + Send + Sync>;
+pub type BreachedPasswordCheckerType =
+    Arc<dyn BreachedPasswordChecker + Send + Sync>;
+pub type PasswordPolicyType = Arc<PasswordPolicy>;
+pub type EmailDomainPolicyType = Arc<EmailDomainPolicy>;
+pub type MailboxType = Arc<FileEmailClient>;
+pub type EmailFailoverType = Arc<FailoverEmailClient>;
+pub type TrustedProxiesType = Arc<TrustedProxies>;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -13,20 +48,72 @@ pub struct AppState {
     pub banned_token_store: BannedTokenStoreType,
     pub two_fa_code_store: TwoFACodeStoreType,
     pub email_client: EmailClientType,
+    pub oauth_client_store: OAuthClientStoreType,
+    pub authorization_code_store: AuthorizationCodeStoreType,
+    pub refresh_token_store: RefreshTokenStoreType,
+    pub service_account_store: ServiceAccountStoreType,
+    pub device_code_store: DeviceCodeStoreType,
+    pub claims_enricher: ClaimsEnricherType,
+    pub trusted_device_store: TrustedDeviceStoreType,
+    pub login_history_store: LoginHistoryStoreType,
+    pub audit_sink: AuditSinkType,
+    pub webhook_publisher: WebhookPublisherType,
+    pub breached_password_checker: BreachedPasswordCheckerType,
+    pub password_policy: PasswordPolicyType,
+    pub email_domain_policy: EmailDomainPolicyType,
+    pub trusted_proxies: TrustedProxiesType,
+    // Set when emails are written to files, to serve them at `/dev/mailbox`
+    pub mailbox: Option<MailboxType>,
+    // Set when emails fail over between several providers, to serve their
+    // metrics at `/admin/email-providers`
+    pub email_failover: Option<EmailFailoverType>,
 }
 
 impl AppState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         user_store: UserStoreType,
         banned_token_store: BannedTokenStoreType,
         two_fa_code_store: TwoFACodeStoreType,
         email_client: EmailClientType,
+        oauth_client_store: OAuthClientStoreType,
+        authorization_code_store: AuthorizationCodeStoreType,
+        refresh_token_store: RefreshTokenStoreType,
+        service_account_store: ServiceAccountStoreType,
+        device_code_store: DeviceCodeStoreType,
+        claims_enricher: ClaimsEnricherType,
+        trusted_device_store: TrustedDeviceStoreType,
+        login_history_store: LoginHistoryStoreType,
+        audit_sink: AuditSinkType,
+        webhook_publisher: WebhookPublisherType,
+        breached_password_checker: BreachedPasswordCheckerType,
+        password_policy: PasswordPolicyType,
+        email_domain_policy: EmailDomainPolicyType,
+        trusted_proxies: TrustedProxiesType,
+        mailbox: Option<MailboxType>,
+        email_failover: Option<EmailFailoverType>,
     ) -> Self {
         Self {
             user_store,
             banned_token_store,
             two_fa_code_store,
             email_client,
+            oauth_client_store,
+            authorization_code_store,
+            refresh_token_store,
+            service_account_store,
+            device_code_store,
+            claims_enricher,
+            trusted_device_store,
+            login_history_store,
+            audit_sink,
+            webhook_publisher,
+            breached_password_checker,
+            password_policy,
+            email_domain_policy,
+            trusted_proxies,
+            mailbox,
+            email_failover,
         }
     }
 }
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Signup,
+    Login,
+    #[serde(rename = "verify_2fa")]
+    Verify2FA,
+    #[serde(rename = "update_2fa")]
+    Update2FA,
+    Logout,
+    DeleteUser,
+    CreateServiceAccount,
+    DeleteServiceAccount,
+    MarkEmailUndeliverable,
+    ClearEmailUndeliverable,
+    ImportUser,
+    ChangePassword,
+}
+
+impl AsRef<str> for AuditAction {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Signup => "signup",
+            Self::Login => "login",
+            Self::Verify2FA => "verify_2fa",
+            Self::Update2FA => "update_2fa",
+            Self::Logout => "logout",
+            Self::DeleteUser => "delete_user",
+            Self::CreateServiceAccount => "create_service_account",
+            Self::DeleteServiceAccount => "delete_service_account",
+            Self::MarkEmailUndeliverable => "mark_email_undeliverable",
+            Self::ClearEmailUndeliverable => "clear_email_undeliverable",
+            Self::ImportUser => "import_user",
+            Self::ChangePassword => "change_password",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+impl AsRef<str> for AuditOutcome {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+        }
+    }
+}
+
+// A security-relevant action, recording who did it to whom. The actor is
+// the signed-in user or service account, if known, and the target is the
+// account acted on
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AuditEvent {
+    pub timestamp: usize,
+    pub action: AuditAction,
+    pub outcome: AuditOutcome,
+    pub actor: Option<String>,
+    pub target: Option<String>,
+    pub ip: Option<String>,
+    pub request_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_serializes_as_its_name() {
+        for action in [
+            AuditAction::Signup,
+            AuditAction::Login,
+            AuditAction::Verify2FA,
+            AuditAction::Update2FA,
+            AuditAction::Logout,
+            AuditAction::DeleteUser,
+            AuditAction::CreateServiceAccount,
+            AuditAction::DeleteServiceAccount,
+            AuditAction::MarkEmailUndeliverable,
+            AuditAction::ClearEmailUndeliverable,
+            AuditAction::ImportUser,
+            AuditAction::ChangePassword,
+        ] {
+            assert_eq!(
+                serde_json::to_value(action).unwrap(),
+                serde_json::Value::from(action.as_ref())
+            );
+        }
+    }
+}
@@ -0,0 +1,8 @@
+use super::AuditEvent;
+use color_eyre::eyre::Result;
+
+// Somewhere to keep the audit log. Events are only ever appended
+#[async_trait::async_trait]
+pub trait AuditSink {
+    async fn record(&self, event: AuditEvent) -> Result<()>;
+}
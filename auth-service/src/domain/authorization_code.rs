@@ -0,0 +1,158 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use color_eyre::eyre::{eyre, Result};
+use secrecy::{ExposeSecret, Secret};
+use sha2::{Digest, Sha256};
+
+use super::{Email, Scopes};
+
+#[derive(Debug, Clone)]
+pub struct AuthorizationCode(Secret<String>);
+
+impl PartialEq for AuthorizationCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl AuthorizationCode {
+    pub fn parse(code: Secret<String>) -> Result<Self> {
+        if is_unreserved(code.expose_secret()) {
+            Ok(Self(code))
+        } else {
+            Err(eyre!("Authorization code is invalid"))
+        }
+    }
+}
+
+impl Default for AuthorizationCode {
+    fn default() -> Self {
+        let bytes: [u8; 32] = rand::random();
+        AuthorizationCode(Secret::new(URL_SAFE_NO_PAD.encode(bytes)))
+    }
+}
+
+impl AsRef<Secret<String>> for AuthorizationCode {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+// PKCE code challenge, using the S256 method from RFC 7636
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeChallenge(String);
+
+impl CodeChallenge {
+    pub fn parse(challenge: &str) -> Result<Self> {
+        // A base64url-encoded SHA-256 digest is always 43 characters
+        if challenge.len() == 43 && is_unreserved(challenge) {
+            Ok(Self(challenge.to_owned()))
+        } else {
+            Err(eyre!("Code challenge is invalid"))
+        }
+    }
+
+    pub fn verify(&self, code_verifier: &Secret<String>) -> bool {
+        let code_verifier = code_verifier.expose_secret();
+        if !(43..=128).contains(&code_verifier.len())
+            || !is_unreserved(code_verifier)
+        {
+            return false;
+        }
+
+        let digest = Sha256::digest(code_verifier.as_bytes());
+        URL_SAFE_NO_PAD.encode(digest) == self.0
+    }
+}
+
+impl AsRef<str> for CodeChallenge {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+// What the user consented to, redeemed by the client with the matching code
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorizationGrant {
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub email: Email,
+    pub scopes: Scopes,
+    pub code_challenge: CodeChallenge,
+    // OpenID Connect: echoed back in the ID token
+    pub nonce: Option<String>,
+    pub auth_time: usize,
+}
+
+// Unreserved characters, see RFC 3986 section 2.3
+fn is_unreserved(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars().all(|c| {
+            c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example values from RFC 7636 appendix B
+    const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    const CODE_CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+
+    #[test]
+    fn test_code_challenge_verifies_matching_verifier() {
+        let challenge = CodeChallenge::parse(CODE_CHALLENGE).unwrap();
+        assert!(challenge.verify(&Secret::new(CODE_VERIFIER.to_owned())));
+    }
+
+    #[test]
+    fn test_code_challenge_rejects_other_verifiers() {
+        let challenge = CodeChallenge::parse(CODE_CHALLENGE).unwrap();
+        let invalid_verifiers = [
+            "",
+            "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXj",
+            "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjX",
+            CODE_CHALLENGE,
+        ];
+        for invalid_verifier in invalid_verifiers.iter() {
+            assert!(
+                !challenge.verify(&Secret::new(invalid_verifier.to_string())),
+                "{}",
+                invalid_verifier
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_code_challenges() {
+        let invalid_challenges = [
+            "",
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-c",
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM=",
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw+cM",
+        ];
+        for invalid_challenge in invalid_challenges.iter() {
+            let result = CodeChallenge::parse(invalid_challenge);
+            let error = result.expect_err(invalid_challenge);
+            assert_eq!(error.to_string(), "Code challenge is invalid");
+        }
+    }
+
+    #[test]
+    fn test_invalid_codes() {
+        let invalid_codes = ["", "abc def", "abc/def", "abc+def"];
+        for invalid_code in invalid_codes.iter() {
+            let result =
+                AuthorizationCode::parse(Secret::new(invalid_code.to_string()));
+            let error = result.expect_err(invalid_code);
+            assert_eq!(error.to_string(), "Authorization code is invalid");
+        }
+    }
+
+    #[test]
+    fn test_generated_codes_are_valid() {
+        let code = AuthorizationCode::default();
+        assert!(AuthorizationCode::parse(code.as_ref().clone()).is_ok());
+        assert_ne!(code, AuthorizationCode::default());
+    }
+}
@@ -0,0 +1,9 @@
+use super::Password;
+use color_eyre::eyre::Result;
+
+// Tells whether a password has appeared in a known data breach, so it can be
+// refused even though it is otherwise valid
+#[async_trait::async_trait]
+pub trait BreachedPasswordChecker {
+    async fn is_breached(&self, password: &Password) -> Result<bool>;
+}
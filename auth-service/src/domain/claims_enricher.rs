@@ -0,0 +1,14 @@
+use super::Email;
+use color_eyre::eyre::Result;
+use serde_json::{Map, Value};
+
+// Adds deployment-specific claims, such as roles or a tenant, to the session
+// token issued when a user signs in
+#[async_trait::async_trait]
+pub trait ClaimsEnricher {
+    async fn enrich(
+        &self,
+        email: &Email,
+        two_fa_verified: bool,
+    ) -> Result<Map<String, Value>>;
+}
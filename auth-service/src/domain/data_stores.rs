@@ -1,4 +1,10 @@
-use super::{Email, LoginAttemptId, Password, TwoFACode, User};
+use super::{
+    AuthorizationCode, AuthorizationGrant, ClientSecret, DeviceCode,
+    DeviceGrant, Email, ImportedUser, LoginAttemptId, LoginEvent, OAuthClient,
+    OutboxEmail, Password, RefreshGrant, RefreshToken, ServiceAccount,
+    TrustedDevice, TwoFACode, UndeliverableEmail, User, UserCode,
+    WebhookDelivery,
+};
 use color_eyre::eyre::{Report, Result};
 use secrecy::Secret;
 use thiserror::Error;
@@ -6,6 +12,12 @@ use thiserror::Error;
 #[async_trait::async_trait]
 pub trait UserStore {
     async fn add_user(&mut self, user: User) -> Result<(), UserStoreError>;
+    // Keeps the user's existing password hash, which must be in a format the
+    // store can verify
+    async fn import_user(
+        &mut self,
+        user: ImportedUser,
+    ) -> Result<(), UserStoreError>;
     async fn get_user(&self, email: &Email) -> Result<User, UserStoreError>;
     async fn validate_user(
         &self,
@@ -16,6 +28,27 @@ pub trait UserStore {
         &mut self,
         email: &Email,
     ) -> Result<(), UserStoreError>;
+    async fn set_requires_2fa(
+        &mut self,
+        email: &Email,
+        requires_2fa: bool,
+    ) -> Result<(), UserStoreError>;
+    // Fails with `PasswordReused` if the password is one of the user's last
+    // few, including the current one
+    async fn update_password(
+        &mut self,
+        email: &Email,
+        password: Password,
+    ) -> Result<(), UserStoreError>;
+    // None clears it, once the address can receive mail again
+    async fn set_email_undeliverable(
+        &mut self,
+        email: &Email,
+        undeliverable: Option<UndeliverableEmail>,
+    ) -> Result<(), UserStoreError>;
+    async fn get_undeliverable_users(
+        &self,
+    ) -> Result<Vec<User>, UserStoreError>;
 }
 
 #[derive(Debug, Error)]
@@ -26,6 +59,10 @@ pub enum UserStoreError {
     UserNotFound,
     #[error("Invalid credentials")]
     InvalidCredentials,
+    #[error("Unrecognised password hash format")]
+    UnrecognisedPasswordHash,
+    #[error("Password was used recently")]
+    PasswordReused,
     #[error("Unexpected error")]
     UnexpectedError(#[source] Report),
 }
@@ -37,6 +74,11 @@ impl PartialEq for UserStoreError {
             (Self::UserAlreadyExists, Self::UserAlreadyExists)
                 | (Self::UserNotFound, Self::UserNotFound)
                 | (Self::InvalidCredentials, Self::InvalidCredentials)
+                | (
+                    Self::UnrecognisedPasswordHash,
+                    Self::UnrecognisedPasswordHash
+                )
+                | (Self::PasswordReused, Self::PasswordReused)
                 | (Self::UnexpectedError(_), Self::UnexpectedError(_))
         )
     }
@@ -105,3 +147,396 @@ impl PartialEq for TwoFACodeStoreError {
         )
     }
 }
+
+#[async_trait::async_trait]
+pub trait OAuthClientStore {
+    async fn add_client(
+        &mut self,
+        client: OAuthClient,
+    ) -> Result<(), OAuthClientStoreError>;
+    async fn get_client(
+        &self,
+        client_id: &str,
+    ) -> Result<OAuthClient, OAuthClientStoreError>;
+    async fn validate_client(
+        &self,
+        client_id: &str,
+        client_secret: Option<&ClientSecret>,
+    ) -> Result<OAuthClient, OAuthClientStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum OAuthClientStoreError {
+    #[error("Client already exists")]
+    ClientAlreadyExists,
+    #[error("Client not found")]
+    ClientNotFound,
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for OAuthClientStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::ClientAlreadyExists, Self::ClientAlreadyExists)
+                | (Self::ClientNotFound, Self::ClientNotFound)
+                | (Self::InvalidCredentials, Self::InvalidCredentials)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait AuthorizationCodeStore {
+    async fn add_code(
+        &mut self,
+        code: AuthorizationCode,
+        grant: AuthorizationGrant,
+    ) -> Result<(), AuthorizationCodeStoreError>;
+
+    // Codes are single use, so taking one also removes it from the store
+    async fn take_code(
+        &mut self,
+        code: &AuthorizationCode,
+    ) -> Result<AuthorizationGrant, AuthorizationCodeStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum AuthorizationCodeStoreError {
+    #[error("Authorization code not found")]
+    CodeNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for AuthorizationCodeStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::CodeNotFound, Self::CodeNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait RefreshTokenStore {
+    async fn add_token(
+        &mut self,
+        token: RefreshToken,
+        grant: RefreshGrant,
+    ) -> Result<(), RefreshTokenStoreError>;
+
+    // Refresh tokens are rotated on use, so taking one also removes it
+    async fn take_token(
+        &mut self,
+        token: &RefreshToken,
+    ) -> Result<RefreshGrant, RefreshTokenStoreError>;
+
+    // Look a token up without using it, e.g. to check who it belongs to
+    async fn get_token(
+        &self,
+        token: &RefreshToken,
+    ) -> Result<RefreshGrant, RefreshTokenStoreError>;
+
+    async fn remove_token(
+        &mut self,
+        token: &RefreshToken,
+    ) -> Result<(), RefreshTokenStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum RefreshTokenStoreError {
+    #[error("Refresh token not found")]
+    TokenNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for RefreshTokenStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::TokenNotFound, Self::TokenNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait ServiceAccountStore {
+    async fn add_account(
+        &mut self,
+        account: ServiceAccount,
+    ) -> Result<(), ServiceAccountStoreError>;
+    async fn get_accounts(
+        &self,
+    ) -> Result<Vec<ServiceAccount>, ServiceAccountStoreError>;
+    async fn validate_account(
+        &self,
+        client_id: &str,
+        client_secret: &ClientSecret,
+    ) -> Result<ServiceAccount, ServiceAccountStoreError>;
+    async fn delete_account(
+        &mut self,
+        client_id: &str,
+    ) -> Result<(), ServiceAccountStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum ServiceAccountStoreError {
+    #[error("Service account already exists")]
+    AccountAlreadyExists,
+    #[error("Service account not found")]
+    AccountNotFound,
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for ServiceAccountStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::AccountAlreadyExists, Self::AccountAlreadyExists)
+                | (Self::AccountNotFound, Self::AccountNotFound)
+                | (Self::InvalidCredentials, Self::InvalidCredentials)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait DeviceCodeStore {
+    async fn add_code(
+        &mut self,
+        code: DeviceCode,
+        grant: DeviceGrant,
+    ) -> Result<(), DeviceCodeStoreError>;
+    async fn get_code(
+        &self,
+        code: &DeviceCode,
+    ) -> Result<DeviceGrant, DeviceCodeStoreError>;
+    async fn update_code(
+        &mut self,
+        code: &DeviceCode,
+        grant: DeviceGrant,
+    ) -> Result<(), DeviceCodeStoreError>;
+    async fn remove_code(
+        &mut self,
+        code: &DeviceCode,
+    ) -> Result<(), DeviceCodeStoreError>;
+    // The verification page only knows the code the user typed in
+    async fn find_user_code(
+        &self,
+        user_code: &UserCode,
+    ) -> Result<DeviceCode, DeviceCodeStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum DeviceCodeStoreError {
+    #[error("Device code not found")]
+    CodeNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for DeviceCodeStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::CodeNotFound, Self::CodeNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait TrustedDeviceStore {
+    async fn add_device(
+        &mut self,
+        device: TrustedDevice,
+    ) -> Result<(), TrustedDeviceStoreError>;
+    async fn get_device(
+        &self,
+        email: &Email,
+        device_id: &str,
+    ) -> Result<TrustedDevice, TrustedDeviceStoreError>;
+    async fn get_devices(
+        &self,
+        email: &Email,
+    ) -> Result<Vec<TrustedDevice>, TrustedDeviceStoreError>;
+    async fn delete_device(
+        &mut self,
+        email: &Email,
+        device_id: &str,
+    ) -> Result<(), TrustedDeviceStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum TrustedDeviceStoreError {
+    #[error("Trusted device not found")]
+    DeviceNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for TrustedDeviceStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::DeviceNotFound, Self::DeviceNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait LoginHistoryStore {
+    // A notice about the login is queued in the email outbox along with the
+    // event, so neither is kept without the other
+    async fn add_event(
+        &mut self,
+        event: LoginEvent,
+        notice: Option<OutboxEmail>,
+    ) -> Result<(), LoginHistoryStoreError>;
+    // Newest first
+    async fn get_events(
+        &self,
+        email: &Email,
+        limit: usize,
+    ) -> Result<Vec<LoginEvent>, LoginHistoryStoreError>;
+    // Whether the user has signed in successfully before, but never from
+    // this IP or never with this user agent
+    async fn is_new_device(
+        &self,
+        email: &Email,
+        ip: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<bool, LoginHistoryStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum LoginHistoryStoreError {
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for LoginHistoryStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait WebhookStore {
+    async fn add_delivery(
+        &mut self,
+        delivery: WebhookDelivery,
+    ) -> Result<(), WebhookStoreError>;
+    // Deliveries that are due, oldest first. They aren't due again until
+    // claimed_until, so other workers don't send them at the same time
+    async fn claim_due_deliveries(
+        &mut self,
+        now: u64,
+        claimed_until: u64,
+        limit: usize,
+    ) -> Result<Vec<WebhookDelivery>, WebhookStoreError>;
+    // Saves the attempts, next attempt time and error of a failed delivery
+    async fn update_delivery(
+        &mut self,
+        delivery: &WebhookDelivery,
+    ) -> Result<(), WebhookStoreError>;
+    async fn remove_delivery(
+        &mut self,
+        delivery_id: &str,
+    ) -> Result<(), WebhookStoreError>;
+    // Gives up on a delivery, moving it to the dead letters. These are
+    // returned with next_attempt_at set to when they failed
+    async fn dead_letter(
+        &mut self,
+        delivery: &WebhookDelivery,
+        failed_at: u64,
+    ) -> Result<(), WebhookStoreError>;
+    async fn get_dead_letters(
+        &self,
+    ) -> Result<Vec<WebhookDelivery>, WebhookStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum WebhookStoreError {
+    #[error("Delivery not found")]
+    DeliveryNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for WebhookStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::DeliveryNotFound, Self::DeliveryNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait EmailOutboxStore {
+    async fn add_email(
+        &mut self,
+        email: OutboxEmail,
+    ) -> Result<(), EmailOutboxStoreError>;
+    // Pending emails that are due, oldest first. They aren't due again until
+    // claimed_until, so other workers don't send them at the same time
+    async fn claim_due_emails(
+        &mut self,
+        now: u64,
+        claimed_until: u64,
+        limit: usize,
+    ) -> Result<Vec<OutboxEmail>, EmailOutboxStoreError>;
+    // Saves the attempts, next attempt time and error of a failed send
+    async fn update_email(
+        &mut self,
+        email: &OutboxEmail,
+    ) -> Result<(), EmailOutboxStoreError>;
+    async fn remove_email(
+        &mut self,
+        id: &str,
+    ) -> Result<(), EmailOutboxStoreError>;
+    // Gives up on an email, keeping it for inspection. Failed emails are
+    // returned with next_attempt_at set to when they failed
+    async fn mark_failed(
+        &mut self,
+        email: &OutboxEmail,
+        failed_at: u64,
+    ) -> Result<(), EmailOutboxStoreError>;
+    async fn get_failed_emails(
+        &self,
+    ) -> Result<Vec<OutboxEmail>, EmailOutboxStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum EmailOutboxStoreError {
+    #[error("Email not found")]
+    EmailNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for EmailOutboxStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::EmailNotFound, Self::EmailNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
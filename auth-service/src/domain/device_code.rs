@@ -0,0 +1,205 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use color_eyre::eyre::{eyre, Result};
+use rand::Rng;
+use secrecy::{ExposeSecret, Secret};
+
+use super::{Email, Scopes};
+
+// How long the user has to approve a device, and how often it may poll
+pub const DEVICE_CODE_TTL_SECONDS: usize = 600;
+pub const DEVICE_POLL_INTERVAL_SECONDS: usize = 5;
+
+// Polled by the device, so it must be as hard to guess as an authorization code
+#[derive(Debug, Clone)]
+pub struct DeviceCode(Secret<String>);
+
+impl PartialEq for DeviceCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl DeviceCode {
+    pub fn parse(code: Secret<String>) -> Result<Self> {
+        let code_str = code.expose_secret();
+        if !code_str.is_empty()
+            && code_str
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            Ok(Self(code))
+        } else {
+            Err(eyre!("Device code is invalid"))
+        }
+    }
+}
+
+impl Default for DeviceCode {
+    fn default() -> Self {
+        let bytes: [u8; 32] = rand::random();
+        DeviceCode(Secret::new(URL_SAFE_NO_PAD.encode(bytes)))
+    }
+}
+
+impl AsRef<Secret<String>> for DeviceCode {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+// Typed in by the user, so it avoids vowels and look-alike characters.
+// 20^8 codes is enough given they expire in minutes, see RFC 8628 section 6.1
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserCode(String);
+
+const USER_CODE_CHARSET: &[u8] = b"BCDFGHJKLMNPQRSTVWXZ";
+const USER_CODE_LENGTH: usize = 8;
+
+impl UserCode {
+    // Case, spaces and dashes are ignored, as users may type them either way
+    pub fn parse(code: &str) -> Result<Self> {
+        let code: String = code
+            .chars()
+            .filter(|c| !matches!(c, '-' | ' '))
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+
+        if code.len() == USER_CODE_LENGTH
+            && code.bytes().all(|c| USER_CODE_CHARSET.contains(&c))
+        {
+            Ok(Self(code))
+        } else {
+            Err(eyre!("User code is invalid"))
+        }
+    }
+
+    // Shown to the user as two groups of four, e.g. WDJB-MJHT
+    pub fn formatted(&self) -> String {
+        let (first, second) = self.0.split_at(USER_CODE_LENGTH / 2);
+        format!("{}-{}", first, second)
+    }
+}
+
+impl Default for UserCode {
+    fn default() -> Self {
+        let mut rng = rand::thread_rng();
+        let code = (0..USER_CODE_LENGTH)
+            .map(|_| {
+                USER_CODE_CHARSET[rng.gen_range(0..USER_CODE_CHARSET.len())]
+                    as char
+            })
+            .collect();
+        UserCode(code)
+    }
+}
+
+impl AsRef<str> for UserCode {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+// A device waiting for the user to approve it in their browser
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceGrant {
+    pub client_id: String,
+    pub scopes: Scopes,
+    pub user_code: UserCode,
+    pub status: DeviceGrantStatus,
+    pub expires_at: usize,
+    pub interval: usize,
+    pub last_polled_at: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceGrantStatus {
+    Pending,
+    Approved { email: Email, auth_time: usize },
+    Denied,
+}
+
+impl DeviceGrant {
+    pub fn new(client_id: String, scopes: Scopes, now: usize) -> Self {
+        Self {
+            client_id,
+            scopes,
+            user_code: UserCode::default(),
+            status: DeviceGrantStatus::Pending,
+            expires_at: now + DEVICE_CODE_TTL_SECONDS,
+            interval: DEVICE_POLL_INTERVAL_SECONDS,
+            last_polled_at: None,
+        }
+    }
+
+    pub fn is_expired(&self, now: usize) -> bool {
+        now >= self.expires_at
+    }
+
+    // Record a poll by the device, returning whether it came too soon. Devices
+    // that poll too fast must wait 5 seconds longer from then on (RFC 8628
+    // section 3.5).
+    pub fn poll(&mut self, now: usize) -> bool {
+        let too_soon = self
+            .last_polled_at
+            .is_some_and(|last_polled_at| now < last_polled_at + self.interval);
+        if too_soon {
+            self.interval += DEVICE_POLL_INTERVAL_SECONDS;
+        }
+        self.last_polled_at = Some(now);
+        too_soon
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_code_is_normalised() {
+        let code = UserCode::parse("wdjb-mjht").unwrap();
+        assert_eq!(code.as_ref(), "WDJBMJHT");
+        assert_eq!(code.formatted(), "WDJB-MJHT");
+        assert_eq!(UserCode::parse("WDJB MJHT").unwrap(), code);
+    }
+
+    #[test]
+    fn test_invalid_user_codes() {
+        let invalid_codes =
+            ["", "WDJB-MJH", "WDJB-MJHTX", "WDJB-MJH1", "ABCD-EFGH"];
+        for invalid_code in invalid_codes.iter() {
+            let result = UserCode::parse(invalid_code);
+            let error = result.expect_err(invalid_code);
+            assert_eq!(error.to_string(), "User code is invalid");
+        }
+    }
+
+    #[test]
+    fn test_generated_user_codes_are_valid() {
+        let code = UserCode::default();
+        assert_eq!(UserCode::parse(&code.formatted()).unwrap(), code);
+    }
+
+    #[test]
+    fn test_invalid_device_codes() {
+        let invalid_codes = ["", "abc def", "abc/def", "abc+def"];
+        for invalid_code in invalid_codes.iter() {
+            let result =
+                DeviceCode::parse(Secret::new(invalid_code.to_string()));
+            let error = result.expect_err(invalid_code);
+            assert_eq!(error.to_string(), "Device code is invalid");
+        }
+    }
+
+    #[test]
+    fn test_polling_too_fast_slows_down() {
+        let mut grant =
+            DeviceGrant::new("client".to_owned(), Scopes::default(), 1000);
+
+        assert!(!grant.poll(1000));
+        assert!(grant.poll(1004));
+        assert_eq!(grant.interval, 10);
+        assert!(!grant.poll(1014));
+        assert!(!grant.is_expired(1599));
+        assert!(grant.is_expired(1600));
+    }
+}
@@ -1,12 +1,31 @@
 use super::Email;
 use color_eyre::eyre::Result;
+use serde::{Deserialize, Serialize};
 
 #[async_trait::async_trait]
 pub trait EmailClient {
+    // The idempotency key is the same for every attempt at one email, and is
+    // passed on to the provider so a resend can be recognised
     async fn send_email(
         &self,
+        idempotency_key: &str,
         recipient: &Email,
         subject: &str,
-        content: &str,
+        html_body: &str,
+        text_body: &str,
     ) -> Result<()>;
 }
+
+// Per-provider counts, from a client that fails over between several
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EmailProviderMetrics {
+    pub provider: String,
+    // Emails this provider sent
+    pub sent: u64,
+    // Sends that errored or timed out, and were passed to the next provider
+    pub failed: u64,
+    // Emails passed straight to the next provider as the circuit was open
+    pub skipped: u64,
+    // `closed`, `open` or `half_open`
+    pub circuit: String,
+}
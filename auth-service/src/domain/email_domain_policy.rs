@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use super::Email;
+
+// Which email domains can sign up. Each listed domain also covers its
+// subdomains, so `example.com` matches `mail.example.com`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmailDomainPolicy {
+    // When not empty, only these domains can sign up, e.g. for invite-only or
+    // corporate deployments. They are never treated as disposable
+    pub allowed_domains: Vec<String>,
+    pub denied_domains: Vec<String>,
+    // Throwaway email providers, usually loaded from a published list
+    pub disposable_domains: HashSet<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailDomainRejection {
+    NotAllowed,
+    Denied,
+    Disposable,
+}
+
+impl EmailDomainPolicy {
+    pub fn check(&self, email: &Email) -> Result<(), EmailDomainRejection> {
+        let domain = domain(email);
+
+        if matches_any(&domain, &self.denied_domains) {
+            return Err(EmailDomainRejection::Denied);
+        }
+        if !self.allowed_domains.is_empty() {
+            return match matches_any(&domain, &self.allowed_domains) {
+                true => Ok(()),
+                false => Err(EmailDomainRejection::NotAllowed),
+            };
+        }
+        // The domain and each parent, e.g. `a.b.com` then `b.com` and `com`
+        let is_disposable = std::iter::successors(Some(domain.as_str()), |d| {
+            d.split_once('.').map(|(_, parent)| parent)
+        })
+        .any(|d| self.disposable_domains.contains(d));
+        if is_disposable {
+            return Err(EmailDomainRejection::Disposable);
+        }
+        Ok(())
+    }
+
+    // One domain a line, as in the disposable-email-domains lists. Blank lines
+    // and `#` comments are skipped
+    pub fn parse_domain_list(list: &str) -> HashSet<String> {
+        list.lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .map(normalise)
+            .filter(|domain| !domain.is_empty())
+            .collect()
+    }
+}
+
+fn domain(email: &Email) -> String {
+    let email = email.as_ref().expose_secret();
+    normalise(email.rsplit_once('@').map_or("", |(_, domain)| domain))
+}
+
+fn normalise(domain: &str) -> String {
+    domain.trim().trim_end_matches('.').to_lowercase()
+}
+
+fn matches_any(domain: &str, domains: &[String]) -> bool {
+    domains.iter().map(|d| normalise(d)).any(|listed| {
+        domain == listed
+            || domain
+                .strip_suffix(listed.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+
+    fn check(
+        policy: &EmailDomainPolicy,
+        email: &str,
+    ) -> Result<(), EmailDomainRejection> {
+        policy.check(&Email::parse(Secret::new(email.to_owned())).unwrap())
+    }
+
+    fn domains(list: &[&str]) -> Vec<String> {
+        list.iter().map(|d| d.to_string()).collect()
+    }
+
+    #[test]
+    fn test_default_policy_allows_any_domain() {
+        assert_eq!(
+            check(&EmailDomainPolicy::default(), "a@example.com"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_allowlist_only_allows_listed_domains_and_subdomains() {
+        let policy = EmailDomainPolicy {
+            allowed_domains: domains(&["Example.com"]),
+            ..EmailDomainPolicy::default()
+        };
+
+        assert_eq!(check(&policy, "a@example.com"), Ok(()));
+        assert_eq!(check(&policy, "a@EXAMPLE.COM"), Ok(()));
+        assert_eq!(check(&policy, "a@mail.example.com"), Ok(()));
+        for email in ["a@example.org", "a@notexample.com"] {
+            assert_eq!(
+                check(&policy, email),
+                Err(EmailDomainRejection::NotAllowed),
+                "{}",
+                email
+            );
+        }
+    }
+
+    #[test]
+    fn test_denylist_wins_over_allowlist() {
+        let policy = EmailDomainPolicy {
+            allowed_domains: domains(&["example.com"]),
+            denied_domains: domains(&["contractors.example.com"]),
+            ..EmailDomainPolicy::default()
+        };
+
+        assert_eq!(check(&policy, "a@example.com"), Ok(()));
+        assert_eq!(
+            check(&policy, "a@contractors.example.com"),
+            Err(EmailDomainRejection::Denied)
+        );
+    }
+
+    #[test]
+    fn test_blocks_disposable_domains_unless_allowed() {
+        let disposable_domains = EmailDomainPolicy::parse_domain_list(
+            "# Disposable domains\nmailinator.com\n\n  Trashmail.com  # common\n",
+        );
+        assert_eq!(disposable_domains.len(), 2);
+        let policy = EmailDomainPolicy {
+            disposable_domains,
+            ..EmailDomainPolicy::default()
+        };
+
+        assert_eq!(
+            check(&policy, "a@mailinator.com"),
+            Err(EmailDomainRejection::Disposable)
+        );
+        assert_eq!(
+            check(&policy, "a@x.trashmail.com"),
+            Err(EmailDomainRejection::Disposable)
+        );
+        assert_eq!(check(&policy, "a@example.com"), Ok(()));
+
+        let allowed = EmailDomainPolicy {
+            allowed_domains: domains(&["mailinator.com"]),
+            ..policy
+        };
+        assert_eq!(check(&allowed, "a@mailinator.com"), Ok(()));
+    }
+}
@@ -1,14 +1,36 @@
 use color_eyre::eyre::Report;
 use thiserror::Error;
 
+use super::{EmailDomainRejection, PasswordViolation};
+
 #[derive(Debug, Error)]
 pub enum AuthAPIError {
+    #[error("Breached password")]
+    BreachedPassword,
+    #[error("Breached password check failed")]
+    BreachCheckFailed(#[source] Report),
+    #[error("Email domain rejected")]
+    EmailDomainRejected(EmailDomainRejection),
+    #[error("Email undeliverable")]
+    EmailUndeliverable,
+    #[error("Password policy violation")]
+    PasswordPolicyViolation(Vec<PasswordViolation>),
+    #[error("Password reused")]
+    PasswordReused,
+    #[error("Message not found")]
+    MessageNotFound,
     #[error("Missing token")]
     MissingToken,
     #[error("Invalid credentials")]
     IncorrectCredentials,
     #[error("Invalid token")]
     InvalidToken,
+    #[error("Insufficient scope")]
+    InsufficientScope,
+    #[error("Service account not found")]
+    ServiceAccountNotFound,
+    #[error("Trusted device not found")]
+    TrustedDeviceNotFound,
     #[error("Unexpected error")]
     UnexpectedError(#[source] Report),
     #[error("User already exists")]
@@ -18,3 +40,61 @@ pub enum AuthAPIError {
     #[error("Validation error")]
     ValidationError,
 }
+
+// Errors from the OAuth endpoints, reported in the format of RFC 6749 section 5.2
+// and, for the device grant, RFC 8628 section 3.5
+#[derive(Debug, Error)]
+pub enum OAuthError {
+    #[error("Access denied")]
+    AccessDenied,
+    #[error("Authorization pending")]
+    AuthorizationPending,
+    #[error("Expired token")]
+    ExpiredToken,
+    #[error("Invalid client")]
+    InvalidClient,
+    #[error("Invalid grant")]
+    InvalidGrant,
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("Invalid scope")]
+    InvalidScope,
+    #[error("Slow down")]
+    SlowDown,
+    #[error("Unauthorized client")]
+    UnauthorizedClient,
+    #[error("Unsupported grant type")]
+    UnsupportedGrantType,
+    #[error("Unsupported response type")]
+    UnsupportedResponseType,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl OAuthError {
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            OAuthError::AccessDenied => "access_denied",
+            OAuthError::AuthorizationPending => "authorization_pending",
+            OAuthError::ExpiredToken => "expired_token",
+            OAuthError::InvalidClient => "invalid_client",
+            OAuthError::InvalidGrant => "invalid_grant",
+            OAuthError::InvalidRequest(_) => "invalid_request",
+            OAuthError::InvalidScope => "invalid_scope",
+            OAuthError::SlowDown => "slow_down",
+            OAuthError::UnauthorizedClient => "unauthorized_client",
+            OAuthError::UnsupportedGrantType => "unsupported_grant_type",
+            OAuthError::UnsupportedResponseType => "unsupported_response_type",
+            OAuthError::UnexpectedError(_) => "server_error",
+        }
+    }
+
+    pub fn description(&self) -> Option<String> {
+        match self {
+            OAuthError::InvalidRequest(description) => {
+                Some(description.to_owned())
+            }
+            _ => None,
+        }
+    }
+}
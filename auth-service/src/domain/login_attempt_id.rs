@@ -12,7 +12,7 @@ impl PartialEq for LoginAttemptId {
 
 impl LoginAttemptId {
     pub fn parse(id: Secret<String>) -> Result<Self> {
-        let parsed = uuid::Uuid::try_parse(&id.expose_secret())
+        let parsed = uuid::Uuid::try_parse(id.expose_secret())
             .wrap_err("Invalid login attempt ID")?;
         Ok(Self(Secret::new(parsed.to_string())))
     }
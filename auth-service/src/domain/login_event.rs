@@ -0,0 +1,80 @@
+use color_eyre::eyre::{eyre, Result};
+
+use super::Email;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoginMethod {
+    Password,
+    TwoFA,
+    TrustedDevice,
+}
+
+impl LoginMethod {
+    pub fn parse(method: &str) -> Result<Self> {
+        match method {
+            "password" => Ok(Self::Password),
+            "2fa" => Ok(Self::TwoFA),
+            "trusted_device" => Ok(Self::TrustedDevice),
+            _ => Err(eyre!("Unknown login method {}", method)),
+        }
+    }
+}
+
+impl AsRef<str> for LoginMethod {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Password => "password",
+            Self::TwoFA => "2fa",
+            Self::TrustedDevice => "trusted_device",
+        }
+    }
+}
+
+// A successful or failed attempt to sign in, kept so users can review
+// activity on their account
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginEvent {
+    pub email: Email,
+    pub timestamp: usize,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub method: LoginMethod,
+    pub success: bool,
+}
+
+impl LoginEvent {
+    pub fn new(
+        email: Email,
+        timestamp: usize,
+        ip: Option<String>,
+        user_agent: Option<String>,
+        method: LoginMethod,
+        success: bool,
+    ) -> Self {
+        Self {
+            email,
+            timestamp,
+            ip,
+            user_agent,
+            method,
+            success,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_round_trips() {
+        for method in [
+            LoginMethod::Password,
+            LoginMethod::TwoFA,
+            LoginMethod::TrustedDevice,
+        ] {
+            assert_eq!(LoginMethod::parse(method.as_ref()).unwrap(), method);
+        }
+        assert!(LoginMethod::parse("totp").is_err());
+    }
+}
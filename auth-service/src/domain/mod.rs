@@ -1,17 +1,55 @@
+mod audit_event;
+mod audit_sink;
+mod authorization_code;
+mod breached_password_checker;
+mod claims_enricher;
 mod data_stores;
+mod device_code;
 mod email;
 mod email_client;
+mod email_domain_policy;
 mod error;
 mod login_attempt_id;
+mod login_event;
+mod oauth_client;
+mod outbox_email;
 mod password;
+mod password_hasher;
+mod password_policy;
+mod password_strength;
+mod refresh_token;
+mod scopes;
+mod service_account;
+mod trusted_device;
 mod two_fa_code;
 mod user;
+mod webhook;
+mod webhook_publisher;
 
+pub use audit_event::*;
+pub use audit_sink::*;
+pub use authorization_code::*;
+pub use breached_password_checker::*;
+pub use claims_enricher::*;
 pub use data_stores::*;
+pub use device_code::*;
 pub use email::*;
 pub use email_client::*;
+pub use email_domain_policy::*;
 pub use error::*;
 pub use login_attempt_id::*;
+pub use login_event::*;
+pub use oauth_client::*;
+pub use outbox_email::*;
 pub use password::*;
+pub use password_hasher::*;
+pub use password_policy::*;
+pub use password_strength::*;
+pub use refresh_token::*;
+pub use scopes::*;
+pub use service_account::*;
+pub use trusted_device::*;
 pub use two_fa_code::*;
 pub use user::*;
+pub use webhook::*;
+pub use webhook_publisher::*;
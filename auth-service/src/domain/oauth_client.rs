@@ -0,0 +1,87 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use color_eyre::eyre::{eyre, Result};
+use secrecy::{ExposeSecret, Secret};
+
+use super::Scopes;
+
+#[derive(Debug, Clone)]
+pub struct OAuthClient {
+    pub client_id: String,
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub scopes: Scopes,
+    // Public clients (e.g. native apps) have no secret and rely on PKCE
+    pub client_secret: Option<ClientSecret>,
+}
+
+impl OAuthClient {
+    pub fn new(
+        name: String,
+        redirect_uris: Vec<String>,
+        scopes: Scopes,
+        client_secret: Option<ClientSecret>,
+    ) -> Self {
+        Self {
+            client_id: uuid::Uuid::new_v4().to_string(),
+            name,
+            redirect_uris,
+            scopes,
+            client_secret,
+        }
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.client_secret.is_none()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientSecret(Secret<String>);
+
+impl PartialEq for ClientSecret {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl ClientSecret {
+    pub fn parse(secret: Secret<String>) -> Result<Self> {
+        if secret.expose_secret().is_empty() {
+            return Err(eyre!("Client secret is invalid"));
+        }
+        Ok(Self(secret))
+    }
+}
+
+impl Default for ClientSecret {
+    fn default() -> Self {
+        let bytes: [u8; 32] = rand::random();
+        ClientSecret(Secret::new(URL_SAFE_NO_PAD.encode(bytes)))
+    }
+}
+
+impl AsRef<Secret<String>> for ClientSecret {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_secrets_are_unique() {
+        let first = ClientSecret::default();
+        let second = ClientSecret::default();
+        assert_eq!(first.as_ref().expose_secret().len(), 43);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_empty_secret_is_invalid() {
+        let result = ClientSecret::parse(Secret::new(String::new()));
+        let error = result.expect_err("empty secret");
+        assert_eq!(error.to_string(), "Client secret is invalid");
+    }
+}
@@ -0,0 +1,38 @@
+use super::Email;
+
+// An email waiting in the outbox. Its id doubles as the idempotency key, sent
+// to the provider with every attempt: the email is only removed once the
+// provider has accepted it, and every retry is of the same record. Times are
+// in milliseconds
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxEmail {
+    pub id: String,
+    pub recipient: Email,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: String,
+    pub attempts: u32,
+    pub next_attempt_at: u64,
+    pub last_error: Option<String>,
+}
+
+impl OutboxEmail {
+    pub fn new(
+        recipient: Email,
+        subject: String,
+        html_body: String,
+        text_body: String,
+        next_attempt_at: u64,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            recipient,
+            subject,
+            html_body,
+            text_body,
+            attempts: 0,
+            next_attempt_at,
+            last_error: None,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use color_eyre::eyre::{Result, WrapErr};
 use secrecy::{ExposeSecret, Secret};
+use unicode_normalization::UnicodeNormalization;
 use validator::ValidationError;
 
 #[derive(Debug, Clone)]
@@ -12,38 +13,23 @@ impl PartialEq for Password {
 }
 
 impl Password {
+    // Only rejects an empty password. What a new password must look like is
+    // up to the `PasswordPolicy`, which doesn't apply to existing ones
     pub fn parse(s: Secret<String>) -> Result<Password> {
-        match validate_password(&s) {
-            Ok(()) => Ok(Self(s)),
-            Err(message) => {
-                let mut error = ValidationError::new("Invalid password");
-                error.message = Some(message.into());
-                Err(error).wrap_err("failed to parse password")
-            }
+        if s.expose_secret().is_empty() {
+            let mut error = ValidationError::new("Invalid password");
+            error.message = Some("Empty password.".into());
+            return Err(error).wrap_err("failed to parse password");
         }
-    }
-}
-
-fn validate_password(s: &Secret<String>) -> Result<(), String> {
-    let min_characters = 8;
-    let max_characters = 128;
-    let char_count = s.expose_secret().chars().count();
-
-    if char_count < min_characters {
-        return Err(format!(
-            "Too short. Should be {} to {} characters.",
-            min_characters, max_characters
-        ));
+        Ok(Self(s))
     }
 
-    if char_count > max_characters {
-        return Err(format!(
-            "Too long. Should be {} to {} characters.",
-            min_characters, max_characters,
-        ));
+    // NFKC normalised, so passwords that look the same, such as a composed
+    // and a decomposed "é", are the same. New passwords are set like this,
+    // but older ones may have been hashed as they were typed
+    pub fn normalised(&self) -> Password {
+        Self(Secret::new(self.0.expose_secret().nfkc().collect()))
     }
-
-    Ok(())
 }
 
 impl AsRef<Secret<String>> for Password {
@@ -81,51 +67,30 @@ mod tests {
     }
 
     #[test]
-    fn test_short_passwords() {
-        let short_passwords = ["", "1234567", "😀😁😂😃😄😅😆"];
-        for short_password in short_passwords.iter() {
-            let secret_password = Secret::new(short_password.to_string());
-            let result = Password::parse(secret_password);
-
-            let error = result.expect_err(short_password);
+    fn test_empty_password() {
+        let error = Password::parse(Secret::new(String::new())).unwrap_err();
 
-            // Downcast to get the original ValidationError
-            let validation_error = error
-                .downcast_ref::<ValidationError>()
-                .expect("Expected ValidationError");
-
-            assert_eq!(validation_error.code, "Invalid password");
-            assert!(validation_error
-                .message
-                .as_ref()
-                .unwrap()
-                .starts_with("Too short"));
-        }
+        let validation_error = error
+            .downcast_ref::<ValidationError>()
+            .expect("Expected ValidationError");
+        assert_eq!(validation_error.code, "Invalid password");
     }
 
     #[test]
-    fn test_long_passwords() {
-        let long_passwords = [
-            "abcdefghijklmnopqrstuvwxyz1234567890ABCDEFGHIJKLMNOPQRSTUVWXYZ12abcdefghijklmnopqrstuvwxyz1234567890ABCDEFGHIJKLMNOPQRSTUVWXYZ123",
-            "☀☁☂☃☄★☆☇☈☉☊☋☌☍☎☏☐☑☒☓☔☕ħĨ☘☙☚☛☜☝☞☟☠☡☢☣ĩ☥☦☧☨☩☪☫☬☭☮☯☰☱☲☳☴☵☶☷☸☹☺☻☼☽☾☿☀☁☂☃☄★☆☇☈☉☊☋☌☍☎☏☐☑☒☓☔☕ħĨ☘☙☚☛☜☝☞☟☠☡☢☣ĩ☥☦☧☨☩☪☫☬☭☮☯☰☱☲☳☴☵☶☷☸☹☺☻☼☽☾☿♀",
-        ];
-        for long_password in long_passwords.iter() {
-            let secret_password = Secret::new(long_password.to_string());
-            let result = Password::parse(secret_password);
-            let error = result.expect_err(long_password);
-
-            // Downcast to get the original ValidationError
-            let validation_error = error
-                .downcast_ref::<ValidationError>()
-                .expect("Expected ValidationError");
-
-            assert_eq!(validation_error.code, "Invalid password");
-            assert!(validation_error
-                .message
-                .as_ref()
-                .unwrap()
-                .starts_with("Too long"));
-        }
+    fn test_passwords_are_nfkc_normalised() {
+        let composed =
+            Password::parse(Secret::new("caf\u{e9}1234".to_owned())).unwrap();
+        let decomposed =
+            Password::parse(Secret::new("cafe\u{301}1234".to_owned())).unwrap();
+        let full_width =
+            Password::parse(Secret::new("\u{ff41}bcd1234".to_owned())).unwrap();
+
+        assert_ne!(composed, decomposed);
+        assert_eq!(composed.normalised(), decomposed.normalised());
+        assert_eq!(
+            full_width.normalised().as_ref().expose_secret(),
+            "abcd1234"
+        );
     }
 
     #[derive(Debug, Clone)]
@@ -0,0 +1,15 @@
+use color_eyre::eyre::Result;
+use secrecy::Secret;
+
+// Checks passwords against hashes in one family of formats, so hashes made by
+// other systems can be imported and still be signed in with
+pub trait PasswordHasher: Send + Sync {
+    // Whether the hash is in a format this hasher reads, judged by its prefix
+    fn recognises(&self, password_hash: &str) -> bool;
+    // Fails if the password doesn't match, or the hash is malformed
+    fn verify(
+        &self,
+        password_hash: &Secret<String>,
+        password: &Secret<String>,
+    ) -> Result<()>;
+}
@@ -0,0 +1,235 @@
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use super::{estimate_strength, password_strength::unleet, Email, Password};
+
+// The rules new passwords must follow. Checked when a password is chosen, not
+// when signing in, so tightening it doesn't lock anyone out
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    // Lowest score from `estimate_strength` accepted, from 0 to 4
+    pub min_strength: u8,
+    // Words a password mustn't contain, ignoring case and l33t speak. The local
+    // part of the user's email is always one
+    pub banned_words: Vec<String>,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 128,
+            min_strength: 0,
+            banned_words: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasswordRule {
+    MinLength,
+    MaxLength,
+    Strength,
+    BannedWord,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+pub struct PasswordViolation {
+    pub rule: PasswordRule,
+    pub message: String,
+}
+
+impl PasswordViolation {
+    fn new(rule: PasswordRule, message: String) -> Self {
+        Self { rule, message }
+    }
+}
+
+// Words shorter than this would ban too many passwords
+const MIN_BANNED_WORD_LENGTH: usize = 3;
+
+impl PasswordPolicy {
+    // Every rule the password breaks, not just the first
+    pub fn validate(
+        &self,
+        password: &Password,
+        email: &Email,
+    ) -> Result<(), Vec<PasswordViolation>> {
+        let password = password.as_ref().expose_secret();
+        let char_count = password.chars().count();
+        let mut violations = Vec::new();
+
+        if char_count < self.min_length {
+            violations.push(PasswordViolation::new(
+                PasswordRule::MinLength,
+                format!(
+                    "Too short. Should be {} to {} characters.",
+                    self.min_length, self.max_length
+                ),
+            ));
+        }
+        if char_count > self.max_length {
+            violations.push(PasswordViolation::new(
+                PasswordRule::MaxLength,
+                format!(
+                    "Too long. Should be {} to {} characters.",
+                    self.min_length, self.max_length
+                ),
+            ));
+            // Not worth estimating the strength of
+            return Err(violations);
+        }
+
+        let banned_words = self.banned_words(email);
+        let lower = password.to_lowercase();
+        let unleeted = lower.chars().map(unleet).collect::<String>();
+        for word in &banned_words {
+            if lower.contains(word.as_str()) || unleeted.contains(word.as_str())
+            {
+                violations.push(PasswordViolation::new(
+                    PasswordRule::BannedWord,
+                    format!("Contains \"{}\", which isn't allowed.", word),
+                ));
+            }
+        }
+
+        if self.min_strength > 0 {
+            let strength = estimate_strength(password, &banned_words);
+            if strength.score < self.min_strength {
+                violations.push(PasswordViolation::new(
+                    PasswordRule::Strength,
+                    "Too easy to guess. Add more words or characters, and \
+                     avoid common words, names and patterns."
+                        .to_owned(),
+                ));
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    fn banned_words(&self, email: &Email) -> Vec<String> {
+        let local_part = email
+            .as_ref()
+            .expose_secret()
+            .split('@')
+            .next()
+            .unwrap_or_default()
+            .to_owned();
+
+        let mut words = Vec::new();
+        for word in self.banned_words.iter().chain([&local_part]) {
+            let word = word.trim().to_lowercase();
+            if word.chars().count() >= MIN_BANNED_WORD_LENGTH
+                && !words.contains(&word)
+            {
+                words.push(word);
+            }
+        }
+        words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+
+    fn password(s: &str) -> Password {
+        Password::parse(Secret::new(s.to_owned())).unwrap()
+    }
+
+    fn email(s: &str) -> Email {
+        Email::parse(Secret::new(s.to_owned())).unwrap()
+    }
+
+    fn rules(policy: &PasswordPolicy, s: &str) -> Vec<PasswordRule> {
+        match policy.validate(&password(s), &email("user@example.com")) {
+            Ok(()) => Vec::new(),
+            Err(violations) => violations.into_iter().map(|v| v.rule).collect(),
+        }
+    }
+
+    #[test]
+    fn test_default_policy_checks_only_lengths() {
+        let policy = PasswordPolicy::default();
+
+        for valid in ["12345678", "password", "😀😁😂😃😄😅😆😎"]
+        {
+            assert_eq!(rules(&policy, valid), [], "{}", valid);
+        }
+        for short in ["1234567", "😀😁😂😃😄😅😆"] {
+            assert_eq!(rules(&policy, short), [PasswordRule::MinLength]);
+        }
+        assert_eq!(rules(&policy, &"a".repeat(129)), [PasswordRule::MaxLength]);
+    }
+
+    #[test]
+    fn test_length_messages_give_the_allowed_range() {
+        let policy = PasswordPolicy {
+            min_length: 10,
+            max_length: 20,
+            ..PasswordPolicy::default()
+        };
+
+        let violations = policy
+            .validate(&password("short"), &email("user@example.com"))
+            .unwrap_err();
+
+        assert_eq!(
+            violations,
+            [PasswordViolation::new(
+                PasswordRule::MinLength,
+                "Too short. Should be 10 to 20 characters.".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_rejects_banned_words_and_the_email_local_part() {
+        let policy = PasswordPolicy {
+            banned_words: vec!["Acme".to_owned(), "ab".to_owned()],
+            ..PasswordPolicy::default()
+        };
+
+        assert_eq!(rules(&policy, "ilove4CME!"), [PasswordRule::BannedWord]);
+        assert_eq!(rules(&policy, "xUSERx2024"), [PasswordRule::BannedWord]);
+        assert_eq!(rules(&policy, "abababab"), []);
+    }
+
+    #[test]
+    fn test_rejects_weak_passwords_when_a_strength_is_required() {
+        let policy = PasswordPolicy {
+            min_strength: 3,
+            ..PasswordPolicy::default()
+        };
+
+        assert_eq!(rules(&policy, "P@ssw0rd123"), [PasswordRule::Strength]);
+        assert_eq!(rules(&policy, "correct horse battery staple"), []);
+    }
+
+    #[test]
+    fn test_reports_every_broken_rule() {
+        let policy = PasswordPolicy {
+            min_strength: 1,
+            ..PasswordPolicy::default()
+        };
+
+        assert_eq!(
+            rules(&policy, "user"),
+            [
+                PasswordRule::MinLength,
+                PasswordRule::BannedWord,
+                PasswordRule::Strength
+            ]
+        );
+    }
+}
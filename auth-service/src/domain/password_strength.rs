@@ -0,0 +1,258 @@
+// A zxcvbn-style estimate of how many guesses it takes to find a password.
+// The password is split into the cheapest run of patterns an attacker would
+// try: common or user-specific words (also in l33t speak), repeated
+// characters, alphabetical or numeric sequences and keyboard rows. Whatever is
+// left is brute forced at 10 guesses a character
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PasswordStrength {
+    pub guesses_log10: f64,
+    // 0 (too guessable) to 4 (very unguessable), on zxcvbn's scale
+    pub score: u8,
+}
+
+pub fn estimate_strength(
+    password: &str,
+    user_inputs: &[String],
+) -> PasswordStrength {
+    let chars = password.chars().collect::<Vec<_>>();
+    let lower = chars.iter().map(|&c| to_lower(c)).collect::<Vec<_>>();
+    let user_inputs = user_inputs
+        .iter()
+        .map(|input| input.chars().map(to_lower).collect::<String>())
+        .collect::<Vec<_>>();
+
+    // Fewest guesses (as log10) to find the first i characters
+    let mut best = vec![f64::INFINITY; chars.len() + 1];
+    best[0] = 0.0;
+    for end in 1..=chars.len() {
+        for start in 0..end {
+            let guesses = pattern_guesses_log10(
+                &chars[start..end],
+                &lower[start..end],
+                &user_inputs,
+            );
+            best[end] = best[end].min(best[start] + guesses);
+        }
+    }
+
+    let guesses_log10 = best[chars.len()];
+    PasswordStrength {
+        guesses_log10,
+        score: score(guesses_log10),
+    }
+}
+
+// zxcvbn's thresholds: under a thousand guesses is 0, a million 1, a hundred
+// million 2 and ten billion 3
+fn score(guesses_log10: f64) -> u8 {
+    match guesses_log10 {
+        g if g < 3.0 => 0,
+        g if g < 6.0 => 1,
+        g if g < 8.0 => 2,
+        g if g < 10.0 => 3,
+        _ => 4,
+    }
+}
+
+fn pattern_guesses_log10(
+    chars: &[char],
+    lower: &[char],
+    user_inputs: &[String],
+) -> f64 {
+    let length = chars.len();
+    let mut guesses = length as f64;
+    if length < 3 {
+        return guesses;
+    }
+
+    let word = lower.iter().collect::<String>();
+    let unleeted = lower.iter().map(|&c| unleet(c)).collect::<String>();
+    let has_upper = chars.iter().any(|c| c.is_uppercase());
+    for (candidate, l33t) in [(&word, false), (&unleeted, unleeted != word)] {
+        let rank = if user_inputs.contains(candidate) {
+            Some(1)
+        } else {
+            COMMON_WORDS
+                .iter()
+                .position(|common| common == candidate)
+                .map(|index| index + 1)
+        };
+        if let Some(rank) = rank {
+            let variations = 1 + usize::from(has_upper) + usize::from(l33t);
+            guesses = guesses.min(((rank * variations) as f64).log10());
+        }
+    }
+
+    if lower.iter().all(|&c| c == lower[0]) {
+        guesses = guesses.min((10.0 * length as f64).log10());
+    }
+
+    if let Some(descending) = sequence_direction(lower) {
+        let base = match lower[0] {
+            'a' | 'z' | '0' | '1' | '9' => 4.0,
+            c if c.is_ascii_digit() => 10.0,
+            _ => 26.0,
+        };
+        let direction = if descending { 2.0 } else { 1.0 };
+        guesses = guesses.min((base * direction * length as f64).log10());
+    }
+
+    let reversed = word.chars().rev().collect::<String>();
+    for row in KEYBOARD_ROWS {
+        if row.contains(&word) {
+            guesses = guesses.min((10.0 * length as f64).log10());
+        } else if row.contains(&reversed) {
+            guesses = guesses.min((20.0 * length as f64).log10());
+        }
+    }
+
+    guesses
+}
+
+// Whether each character is one after (or before) the last, like "abcd" or
+// "4321", and which way it runs
+fn sequence_direction(lower: &[char]) -> Option<bool> {
+    let delta = lower[1] as i64 - lower[0] as i64;
+    let is_sequence = delta.abs() == 1
+        && lower
+            .windows(2)
+            .all(|pair| pair[1] as i64 - pair[0] as i64 == delta);
+    is_sequence.then_some(delta < 0)
+}
+
+// Characters that only lower case to one character are lower cased, so each
+// still lines up with the original
+fn to_lower(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower,
+        _ => c,
+    }
+}
+
+pub(crate) fn unleet(c: char) -> char {
+    match c {
+        '4' | '@' => 'a',
+        '8' => 'b',
+        '3' => 'e',
+        '6' | '9' => 'g',
+        '1' | '!' | '|' => 'i',
+        '0' => 'o',
+        '5' | '$' => 's',
+        '7' | '+' => 't',
+        '2' => 'z',
+        c => c,
+    }
+}
+
+const KEYBOARD_ROWS: [&str; 4] =
+    ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+// The most used passwords and password words, most used first
+const COMMON_WORDS: [&str; 60] = [
+    "password",
+    "123456",
+    "qwerty",
+    "letmein",
+    "welcome",
+    "admin",
+    "login",
+    "monkey",
+    "dragon",
+    "football",
+    "baseball",
+    "iloveyou",
+    "master",
+    "sunshine",
+    "princess",
+    "shadow",
+    "superman",
+    "trustno",
+    "starwars",
+    "whatever",
+    "hello",
+    "freedom",
+    "secret",
+    "summer",
+    "winter",
+    "spring",
+    "autumn",
+    "love",
+    "pass",
+    "test",
+    "guest",
+    "root",
+    "access",
+    "flower",
+    "michael",
+    "jordan",
+    "hunter",
+    "ranger",
+    "buster",
+    "soccer",
+    "hockey",
+    "killer",
+    "george",
+    "charlie",
+    "andrew",
+    "thomas",
+    "jessica",
+    "pepper",
+    "ginger",
+    "cheese",
+    "computer",
+    "internet",
+    "batman",
+    "matrix",
+    "mustang",
+    "silver",
+    "orange",
+    "purple",
+    "banana",
+    "chocolate",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score_of(password: &str) -> u8 {
+        estimate_strength(password, &[]).score
+    }
+
+    #[test]
+    fn test_common_patterns_are_too_guessable() {
+        for password in [
+            "password",
+            "P@ssw0rd",
+            "abcd1234",
+            "aaaaaaaa",
+            "qwertyuiop",
+            "87654321",
+        ] {
+            assert_eq!(score_of(password), 0, "{}", password);
+        }
+    }
+
+    #[test]
+    fn test_long_random_passwords_are_very_unguessable() {
+        for password in ["correct horse battery staple", "x7#Kq9!vLm2@Tz"] {
+            assert_eq!(score_of(password), 4, "{}", password);
+        }
+    }
+
+    #[test]
+    fn test_user_inputs_count_as_words() {
+        let without = estimate_strength("janedoe2024", &[]);
+        let with = estimate_strength("janedoe2024", &["JaneDoe".to_owned()]);
+
+        assert_eq!(without.score, 4);
+        assert_eq!(with.score, 1);
+        assert!(with.guesses_log10 < without.guesses_log10);
+    }
+
+    #[test]
+    fn test_empty_password_needs_no_guesses() {
+        assert_eq!(estimate_strength("", &[]).guesses_log10, 0.0);
+    }
+}
@@ -0,0 +1,74 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use color_eyre::eyre::{eyre, Result};
+use secrecy::{ExposeSecret, Secret};
+
+use super::{Email, Scopes};
+
+#[derive(Debug, Clone)]
+pub struct RefreshToken(Secret<String>);
+
+impl PartialEq for RefreshToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl RefreshToken {
+    pub fn parse(token: Secret<String>) -> Result<Self> {
+        let token_str = token.expose_secret();
+        if !token_str.is_empty()
+            && token_str
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            Ok(Self(token))
+        } else {
+            Err(eyre!("Refresh token is invalid"))
+        }
+    }
+}
+
+impl Default for RefreshToken {
+    fn default() -> Self {
+        let bytes: [u8; 32] = rand::random();
+        RefreshToken(Secret::new(URL_SAFE_NO_PAD.encode(bytes)))
+    }
+}
+
+impl AsRef<Secret<String>> for RefreshToken {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+// The client, user and scopes a refresh token was issued for
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefreshGrant {
+    pub client_id: String,
+    pub email: Email,
+    pub scopes: Scopes,
+    pub auth_time: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_tokens_are_valid() {
+        let token = RefreshToken::default();
+        assert!(RefreshToken::parse(token.as_ref().clone()).is_ok());
+        assert_ne!(token, RefreshToken::default());
+    }
+
+    #[test]
+    fn test_invalid_tokens() {
+        let invalid_tokens = ["", "abc def", "abc.def", "abc+def="];
+        for invalid_token in invalid_tokens.iter() {
+            let result =
+                RefreshToken::parse(Secret::new(invalid_token.to_string()));
+            let error = result.expect_err(invalid_token);
+            assert_eq!(error.to_string(), "Refresh token is invalid");
+        }
+    }
+}
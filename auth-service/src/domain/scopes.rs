@@ -0,0 +1,102 @@
+use color_eyre::eyre::{eyre, Result};
+use std::fmt;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scopes(Vec<String>);
+
+impl Scopes {
+    // Scopes are space-delimited, see RFC 6749 section 3.3
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut scopes: Vec<String> = Vec::new();
+
+        for scope in s.split(' ').filter(|scope| !scope.is_empty()) {
+            if !scope.chars().all(is_scope_char) {
+                return Err(eyre!("Scope is invalid"));
+            }
+            if !scopes.iter().any(|existing| existing == scope) {
+                scopes.push(scope.to_owned());
+            }
+        }
+
+        Ok(Self(scopes))
+    }
+
+    pub fn contains(&self, scope: &str) -> bool {
+        self.0.iter().any(|s| s == scope)
+    }
+
+    pub fn is_subset(&self, other: &Scopes) -> bool {
+        self.0.iter().all(|scope| other.contains(scope))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+}
+
+fn is_scope_char(c: char) -> bool {
+    matches!(c, '\x21' | '\x23'..='\x5B' | '\x5D'..='\x7E')
+}
+
+impl fmt::Display for Scopes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join(" "))
+    }
+}
+
+impl AsRef<[String]> for Scopes {
+    fn as_ref(&self) -> &[String] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_scopes() {
+        let valid_scopes = [
+            ("", ""),
+            ("read", "read"),
+            ("read write", "read write"),
+            ("  read   write ", "read write"),
+            ("read write read", "read write"),
+            (
+                "profile:read https://example.com/x",
+                "profile:read https://example.com/x",
+            ),
+        ];
+        for (valid_scope, expected) in valid_scopes.iter() {
+            let parsed = Scopes::parse(valid_scope).expect(valid_scope);
+            assert_eq!(parsed.to_string(), *expected);
+        }
+    }
+
+    #[test]
+    fn test_invalid_scopes() {
+        let invalid_scopes =
+            ["read\"", "read\\write", "read\twrite", "caf\u{e9}"];
+        for invalid_scope in invalid_scopes.iter() {
+            let result = Scopes::parse(invalid_scope);
+            let error = result.expect_err(invalid_scope);
+            assert_eq!(error.to_string(), "Scope is invalid");
+        }
+    }
+
+    #[test]
+    fn test_is_subset() {
+        let all = Scopes::parse("read write admin").unwrap();
+        let some = Scopes::parse("write read").unwrap();
+        let other = Scopes::parse("read delete").unwrap();
+
+        assert!(some.is_subset(&all));
+        assert!(Scopes::default().is_subset(&all));
+        assert!(!other.is_subset(&all));
+        assert!(!all.is_subset(&some));
+    }
+}
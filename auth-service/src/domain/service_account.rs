@@ -0,0 +1,26 @@
+use super::{ClientSecret, Scopes};
+
+// A machine caller, such as app-service, that authenticates with the
+// client_credentials grant rather than on behalf of a user
+#[derive(Debug, Clone)]
+pub struct ServiceAccount {
+    pub client_id: String,
+    pub name: String,
+    pub scopes: Scopes,
+    pub client_secret: ClientSecret,
+}
+
+impl ServiceAccount {
+    pub fn new(
+        name: String,
+        scopes: Scopes,
+        client_secret: ClientSecret,
+    ) -> Self {
+        Self {
+            client_id: uuid::Uuid::new_v4().to_string(),
+            name,
+            scopes,
+            client_secret,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use super::Email;
+
+// A browser the user chose to remember after completing 2FA, so later logins
+// from it can skip the emailed code
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrustedDevice {
+    pub device_id: String,
+    pub email: Email,
+    pub name: String,
+    pub created_at: usize,
+}
+
+impl TrustedDevice {
+    pub fn new(email: Email, name: String, created_at: usize) -> Self {
+        Self {
+            device_id: uuid::Uuid::new_v4().to_string(),
+            email,
+            name,
+            created_at,
+        }
+    }
+}
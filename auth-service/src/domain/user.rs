@@ -1,3 +1,5 @@
+use secrecy::Secret;
+
 use super::{Email, Password};
 
 #[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
@@ -5,6 +7,8 @@ pub struct User {
     pub email: Email,
     pub password: Password,
     pub requires_2fa: bool,
+    // Set when mail to the address bounced for good, so none is sent to it
+    pub email_undeliverable: Option<UndeliverableEmail>,
 }
 
 impl User {
@@ -13,6 +17,38 @@ impl User {
             email,
             password,
             requires_2fa,
+            email_undeliverable: None,
+        }
+    }
+}
+
+// Why the email provider gave up on an address, e.g. `HardBounce` or
+// `SpamComplaint`, and when we were told
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndeliverableEmail {
+    pub reason: String,
+    pub marked_at: usize,
+}
+
+// A user moved over from another system, whose password is only known by its
+// hash. It is replaced with our own hash the first time they sign in
+#[derive(Debug, Clone)]
+pub struct ImportedUser {
+    pub email: Email,
+    pub password_hash: Secret<String>,
+    pub requires_2fa: bool,
+}
+
+impl ImportedUser {
+    pub fn new(
+        email: Email,
+        password_hash: Secret<String>,
+        requires_2fa: bool,
+    ) -> Self {
+        Self {
+            email,
+            password_hash,
+            requires_2fa,
         }
     }
 }
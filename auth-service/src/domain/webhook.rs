@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum WebhookEventType {
+    #[serde(rename = "user.signed_up")]
+    UserSignedUp,
+    // Sent on every login completed with a 2FA code, not just the first
+    #[serde(rename = "user.login_verified")]
+    UserLoginVerified,
+    #[serde(rename = "user.deleted")]
+    UserDeleted,
+    #[serde(rename = "user.2fa_changed")]
+    TwoFAChanged,
+}
+
+// The body of a webhook request
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct WebhookEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: WebhookEventType,
+    pub created_at: usize,
+    pub data: WebhookEventData,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct WebhookEventData {
+    pub email: String,
+    #[serde(rename = "requires2FA", skip_serializing_if = "Option::is_none")]
+    pub requires_2fa: Option<bool>,
+}
+
+impl WebhookEvent {
+    pub fn new(
+        event_type: WebhookEventType,
+        created_at: usize,
+        data: WebhookEventData,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            event_type,
+            created_at,
+            data,
+        }
+    }
+}
+
+// An event waiting to be sent to one endpoint. Times are in milliseconds,
+// so retries can back off by less than a second
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookDelivery {
+    pub delivery_id: String,
+    pub endpoint: String,
+    pub payload: String,
+    pub attempts: u32,
+    pub next_attempt_at: u64,
+    pub last_error: Option<String>,
+}
+
+impl WebhookDelivery {
+    pub fn new(
+        endpoint: String,
+        payload: String,
+        next_attempt_at: u64,
+    ) -> Self {
+        Self {
+            delivery_id: uuid::Uuid::new_v4().to_string(),
+            endpoint,
+            payload,
+            attempts: 0,
+            next_attempt_at,
+            last_error: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_serializes_type_and_data() {
+        let event = WebhookEvent::new(
+            WebhookEventType::TwoFAChanged,
+            1,
+            WebhookEventData {
+                email: "test@example.com".to_owned(),
+                requires_2fa: Some(true),
+            },
+        );
+
+        let value = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(value["type"], "user.2fa_changed");
+        assert_eq!(value["data"]["email"], "test@example.com");
+        assert_eq!(value["data"]["requires2FA"], true);
+    }
+
+    #[test]
+    fn test_event_omits_missing_2fa_setting() {
+        let event = WebhookEvent::new(
+            WebhookEventType::UserDeleted,
+            1,
+            WebhookEventData {
+                email: "test@example.com".to_owned(),
+                requires_2fa: None,
+            },
+        );
+
+        let value = serde_json::to_value(&event).unwrap();
+
+        assert!(value["data"].get("requires2FA").is_none());
+    }
+}
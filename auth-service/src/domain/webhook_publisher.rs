@@ -0,0 +1,8 @@
+use super::WebhookEvent;
+use color_eyre::eyre::Result;
+
+// Queues an event for delivery to the configured webhook endpoints
+#[async_trait::async_trait]
+pub trait WebhookPublisher {
+    async fn publish(&self, event: WebhookEvent) -> Result<()>;
+}
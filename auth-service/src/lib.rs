@@ -1,8 +1,11 @@
 use askama::Template;
 use axum::{
-    http::{Method, StatusCode},
+    extract::{connect_info::IntoMakeServiceWithConnectInfo, ConnectInfo},
+    http::{header, Method, StatusCode},
+    middleware,
+    middleware::AddExtension,
     response::{IntoResponse, Response},
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
     serve::Serve,
     Json, Router,
 };
@@ -11,16 +14,34 @@ use redis::{Client, RedisResult};
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgPoolOptions, PgPool};
-use std::error::Error;
+use std::{error::Error, net::SocketAddr};
 use tokio::signal;
-use tower_http::{cors::CorsLayer, services::ServeDir, trace::TraceLayer};
+use tower_http::{
+    cors::CorsLayer,
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    services::ServeDir,
+    trace::TraceLayer,
+};
 
-use domain::AuthAPIError;
+use domain::{
+    AuthAPIError, EmailDomainRejection, OAuthError, PasswordViolation,
+};
 pub mod routes;
 use crate::routes::{
-    delete_user, login, logout, signup, verify_2fa, verify_token,
+    change_password, clear_undeliverable_email, create_service_account,
+    delete_service_account, delete_trusted_device, delete_user,
+    email_provider_metrics, get_mailbox_message, import_users, jwks,
+    list_mailbox, list_service_accounts, list_trusted_devices,
+    list_undeliverable_emails, login, login_history, logout, oauth_authorize,
+    oauth_authorize_consent, oauth_device, oauth_device_approval,
+    oauth_device_authorization, oauth_introspect, oauth_revoke, oauth_token,
+    openid_configuration, postmark_webhook, register_oauth_client, signup,
+    update_account_2fa, userinfo, verify_2fa, verify_token,
+};
+use crate::utils::{
+    constants::APP_SERVICE_EXTERNAL_ADDRESS, sliding_session::sliding_session,
+    tracing::*,
 };
-use crate::utils::{constants::APP_SERVICE_EXTERNAL_ADDRESS, tracing::*};
 pub mod app_state;
 pub mod domain;
 pub mod services;
@@ -30,11 +51,15 @@ pub mod utils;
 #[derive(Serialize, Deserialize)]
 pub struct ErrorResponse {
     pub error: String,
+    // Each password policy rule a new password breaks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<PasswordViolation>,
 }
 
 impl IntoResponse for AuthAPIError {
     fn into_response(self) -> Response {
         log_error_chain(&self);
+        let mut violations = Vec::new();
         let (status, error_message) = match self {
             AuthAPIError::UserAlreadyExists => {
                 (StatusCode::CONFLICT, "User already exists")
@@ -51,20 +76,93 @@ impl IntoResponse for AuthAPIError {
             AuthAPIError::UnexpectedError(_) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, "Unexpected error")
             }
+            AuthAPIError::PasswordPolicyViolation(password_violations) => {
+                violations = password_violations;
+                (StatusCode::BAD_REQUEST, "Password does not meet the policy")
+            }
+            AuthAPIError::PasswordReused => (
+                StatusCode::BAD_REQUEST,
+                "Password was used recently. Choose another one.",
+            ),
+            AuthAPIError::BreachedPassword => (
+                StatusCode::BAD_REQUEST,
+                "Password has appeared in a data breach. Choose another one.",
+            ),
+            AuthAPIError::BreachCheckFailed(_) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Password could not be checked for data breaches. Try again later.",
+            ),
+            AuthAPIError::EmailDomainRejected(rejection) => (
+                StatusCode::BAD_REQUEST,
+                match rejection {
+                    EmailDomainRejection::NotAllowed => {
+                        "Email domain is not allowed to sign up"
+                    }
+                    EmailDomainRejection::Denied => "Email domain is blocked",
+                    EmailDomainRejection::Disposable => {
+                        "Disposable email addresses are not allowed"
+                    }
+                },
+            ),
+            AuthAPIError::EmailUndeliverable => {
+                (StatusCode::CONFLICT, "Email address is undeliverable")
+            }
+            AuthAPIError::MessageNotFound => {
+                (StatusCode::NOT_FOUND, "Message not found")
+            }
             AuthAPIError::MissingToken => {
                 (StatusCode::BAD_REQUEST, "Missing token")
             }
             AuthAPIError::InvalidToken => {
                 (StatusCode::UNAUTHORIZED, "Invalid token")
             }
+            AuthAPIError::InsufficientScope => {
+                (StatusCode::FORBIDDEN, "Insufficient scope")
+            }
+            AuthAPIError::ServiceAccountNotFound => {
+                (StatusCode::NOT_FOUND, "Service account not found")
+            }
+            AuthAPIError::TrustedDeviceNotFound => {
+                (StatusCode::NOT_FOUND, "Trusted device not found")
+            }
         };
         let body = Json(ErrorResponse {
             error: error_message.to_string(),
+            violations,
         });
         (status, body).into_response()
     }
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct OAuthErrorResponse {
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+}
+
+impl IntoResponse for OAuthError {
+    fn into_response(self) -> Response {
+        log_error_chain(&self);
+        let body = Json(OAuthErrorResponse {
+            error: self.error_code().to_owned(),
+            error_description: self.description(),
+        });
+        match self {
+            OAuthError::InvalidClient => (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Basic")],
+                body,
+            )
+                .into_response(),
+            OAuthError::UnexpectedError(_) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
+            }
+            _ => (StatusCode::BAD_REQUEST, body).into_response(),
+        }
+    }
+}
+
 fn log_error_chain(e: &(dyn Error + 'static)) {
     let separator = "\n-----------------------------------------------------------------------------------\n";
     let mut report = format!("{}{:?}\n", separator, e);
@@ -79,7 +177,10 @@ fn log_error_chain(e: &(dyn Error + 'static)) {
 }
 
 pub struct Application {
-    server: Serve<Router, Router>,
+    server: Serve<
+        IntoMakeServiceWithConnectInfo<Router, SocketAddr>,
+        AddExtension<Router, ConnectInfo<SocketAddr>>,
+    >,
     pub address: String,
 }
 
@@ -105,7 +206,8 @@ impl Application {
             .allow_credentials(true)
             .allow_origin(allowed_origins);
 
-        let router = Router::new()
+        let mailbox = app_state.mailbox.clone();
+        let mut router = Router::new()
             .nest_service("/", ServeDir::new("assets"))
             .route("/signup", post(signup))
             .route("/login", post(login))
@@ -113,19 +215,86 @@ impl Application {
             .route("/logout", post(logout))
             .route("/verify-token", post(verify_token))
             .route("/delete-user", delete(delete_user))
+            .route("/oauth/clients", post(register_oauth_client))
+            .route(
+                "/oauth/authorize",
+                get(oauth_authorize).post(oauth_authorize_consent),
+            )
+            .route("/oauth/token", post(oauth_token))
+            .route("/oauth/introspect", post(oauth_introspect))
+            .route("/oauth/revoke", post(oauth_revoke))
+            .route(
+                "/oauth/device_authorization",
+                post(oauth_device_authorization),
+            )
+            .route(
+                "/oauth/device",
+                get(oauth_device).post(oauth_device_approval),
+            )
+            .route(
+                "/.well-known/openid-configuration",
+                get(openid_configuration),
+            )
+            .route("/.well-known/jwks.json", get(jwks))
+            .route("/userinfo", get(userinfo).post(userinfo))
+            .route(
+                "/admin/service-accounts",
+                get(list_service_accounts).post(create_service_account),
+            )
+            .route(
+                "/admin/service-accounts/:client_id",
+                delete(delete_service_account),
+            )
+            .route("/admin/email-providers", get(email_provider_metrics))
+            .route(
+                "/admin/undeliverable-emails",
+                get(list_undeliverable_emails),
+            )
+            .route(
+                "/admin/undeliverable-emails/:email",
+                delete(clear_undeliverable_email),
+            )
+            .route("/admin/users/import", post(import_users))
+            .route("/webhooks/postmark", post(postmark_webhook))
+            .route("/account/2fa", put(update_account_2fa))
+            .route("/account/password", put(change_password))
+            .route("/account/login-history", get(login_history))
+            .route("/trusted-devices", get(list_trusted_devices))
+            .route("/trusted-devices/:device_id", delete(delete_trusted_device))
             .route("/app.js", get(serve_app_js))
-            .with_state(app_state)
+            .layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                sliding_session,
+            ))
+            .with_state(app_state);
+        // Only when turned on with DEV_MAILBOX_ENABLED, in development
+        if let Some(mailbox) = mailbox {
+            router = router.merge(
+                Router::new()
+                    .route("/dev/mailbox", get(list_mailbox))
+                    .route("/dev/mailbox/:id", get(get_mailbox_message))
+                    .with_state(mailbox),
+            );
+        }
+        let router = router
             .layer(cors)
             .layer(
                 TraceLayer::new_for_http()
                     .make_span_with(make_span_with_request_id)
                     .on_request(on_request)
                     .on_response(on_response),
-            );
+            )
+            // Outermost, so the id is set before the request's span is made
+            .layer(PropagateRequestIdLayer::x_request_id())
+            .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid));
 
         let listener = tokio::net::TcpListener::bind(address).await?;
         let address = listener.local_addr()?.to_string();
-        let server = axum::serve(listener, router);
+        // The peer address is used as the client IP when not behind nginx
+        let server = axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        );
 
         Ok(Application { server, address })
     }
@@ -1,22 +1,64 @@
+use argon2::Params;
 use reqwest::Client;
+use secrecy::Secret;
 use sqlx::PgPool;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use auth_service::{
     app_state::AppState,
-    domain::Email,
+    app_state::{
+        AuditSinkType, BreachedPasswordCheckerType, EmailClientType,
+        EmailFailoverType, MailboxType, ServiceAccountStoreType,
+    },
+    domain::{
+        ClientSecret, Email, EmailDomainPolicy, PasswordPolicy, Scopes,
+        ServiceAccount,
+    },
     get_postgres_pool, get_redis_client,
     services::{
+        argon2_password_hasher::{Argon2PasswordHasher, Pepper},
         data_stores::{
-            PostgresUserStore, RedisBannedTokenStore, RedisTwoFACodeStore,
+            PostgresEmailOutboxStore, PostgresLoginHistoryStore,
+            PostgresOAuthClientStore, PostgresServiceAccountStore,
+            PostgresTrustedDeviceStore, PostgresUserStore,
+            PostgresWebhookStore, RedisAuthorizationCodeStore,
+            RedisBannedTokenStore, RedisDeviceCodeStore,
+            RedisRefreshTokenStore, RedisTwoFACodeStore,
         },
+        email_outbox::EmailOutbox,
+        fail_open_breached_password_checker::FailOpenBreachedPasswordChecker,
+        failover_email_client::FailoverEmailClient,
+        file_email_client::FileEmailClient,
+        hibp_breached_password_checker::HibpBreachedPasswordChecker,
+        json_lines_audit_sink::JsonLinesAuditSink,
+        no_op_breached_password_checker::NoOpBreachedPasswordChecker,
+        no_op_claims_enricher::NoOpClaimsEnricher,
+        password_hashers::PasswordHashers,
+        postgres_audit_sink::PostgresAuditSink,
         postmark_email_client::PostmarkEmailClient,
+        range_file_breached_password_checker::RangeFileBreachedPasswordChecker,
+        retry_policy::RetryPolicy,
+        smtp_email_client::SmtpEmailClient,
+        webhook_dispatcher::WebhookDispatcher,
     },
     utils::{
+        client_info::TrustedProxies,
         constants::{
-            prod, DATABASE_URL, POSTMARK_AUTH_TOKEN,
-            POSTMARK_EMAIL_SENDER_ADDRESS, REDIS_HOST_NAME,
+            prod, ServiceCredentials, ADMIN_CREDENTIALS, ADMIN_SCOPE,
+            APP_SERVICE_CREDENTIALS, AUDIT_LOG_PATH, BREACHED_PASSWORD_CHECK,
+            BREACHED_PASSWORD_CHECK_FAIL_OPEN, BREACHED_PASSWORD_RANGES_DIR,
+            DATABASE_URL, DEV_MAILBOX_ENABLED, DISPOSABLE_EMAIL_DOMAINS_FILE,
+            EMAIL_PROVIDER, HIBP_API_URL, MAILBOX_DIR, MAILBOX_SENDER_ADDRESS,
+            PASSWORD_BANNED_WORDS, PASSWORD_HASH_ITERATIONS,
+            PASSWORD_HASH_MEMORY_KIB, PASSWORD_HASH_PARALLELISM,
+            PASSWORD_HISTORY_SIZE, PASSWORD_MAX_LENGTH, PASSWORD_MIN_LENGTH,
+            PASSWORD_MIN_STRENGTH, PASSWORD_PEPPERS, POSTMARK_AUTH_TOKEN,
+            POSTMARK_EMAIL_SENDER_ADDRESS, POSTMARK_WEBHOOK_CREDENTIALS,
+            POSTMARK_WEBHOOK_SCOPE, REDIS_HOST_NAME,
+            SIGNUP_ALLOWED_EMAIL_DOMAINS, SIGNUP_DENIED_EMAIL_DOMAINS,
+            SMTP_SENDER_ADDRESS, SMTP_URL, TRUSTED_PROXIES, VERIFY_TOKEN_SCOPE,
+            WEBHOOK_SECRET, WEBHOOK_URLS,
         },
         tracing::init_tracing,
     },
@@ -29,23 +71,101 @@ async fn main() {
     init_tracing().expect("Failed to initialise tracing");
 
     let pg_pool = configure_postgresql().await;
-    let user_store = Arc::new(RwLock::new(PostgresUserStore::new(pg_pool)));
+    let user_store = Arc::new(RwLock::new(PostgresUserStore::new(
+        pg_pool.clone(),
+        configure_password_hashers(),
+        *PASSWORD_HISTORY_SIZE,
+    )));
+    let oauth_client_store =
+        Arc::new(RwLock::new(PostgresOAuthClientStore::new(pg_pool.clone())));
+    let service_account_store: ServiceAccountStoreType = Arc::new(RwLock::new(
+        PostgresServiceAccountStore::new(pg_pool.clone()),
+    ));
+    let trusted_device_store = Arc::new(RwLock::new(
+        PostgresTrustedDeviceStore::new(pg_pool.clone()),
+    ));
+    let login_history_store =
+        Arc::new(RwLock::new(PostgresLoginHistoryStore::new(pg_pool.clone())));
+    let email_outbox_store =
+        Arc::new(RwLock::new(PostgresEmailOutboxStore::new(pg_pool.clone())));
+    let webhook_dispatcher = configure_webhook_dispatcher(pg_pool.clone());
+    let audit_sink = configure_audit_sink(pg_pool).await;
+    seed_service_account(
+        &service_account_store,
+        "app-service",
+        APP_SERVICE_CREDENTIALS.as_ref(),
+        VERIFY_TOKEN_SCOPE,
+    )
+    .await;
+    seed_service_account(
+        &service_account_store,
+        "admin",
+        ADMIN_CREDENTIALS.as_ref(),
+        ADMIN_SCOPE,
+    )
+    .await;
+    seed_service_account(
+        &service_account_store,
+        "postmark-webhooks",
+        POSTMARK_WEBHOOK_CREDENTIALS.as_ref(),
+        POSTMARK_WEBHOOK_SCOPE,
+    )
+    .await;
 
     let redis_connection = Arc::new(RwLock::new(configure_redis()));
     let banned_token_store = Arc::new(RwLock::new(RedisBannedTokenStore::new(
         redis_connection.clone(),
     )));
 
-    let two_fa_code_store =
-        Arc::new(RwLock::new(RedisTwoFACodeStore::new(redis_connection)));
+    let two_fa_code_store = Arc::new(RwLock::new(RedisTwoFACodeStore::new(
+        redis_connection.clone(),
+    )));
+    let authorization_code_store = Arc::new(RwLock::new(
+        RedisAuthorizationCodeStore::new(redis_connection.clone()),
+    ));
+    let device_code_store = Arc::new(RwLock::new(RedisDeviceCodeStore::new(
+        redis_connection.clone(),
+    )));
+    let refresh_token_store =
+        Arc::new(RwLock::new(RedisRefreshTokenStore::new(redis_connection)));
 
-    let email_client = Arc::new(configure_postmark_email_client());
+    let (email_client, mailbox, email_failover) = configure_email_client();
+    let email_outbox = Arc::new(EmailOutbox::new(
+        email_outbox_store,
+        email_client,
+        RetryPolicy {
+            max_attempts: prod::email_outbox::MAX_ATTEMPTS,
+            base_delay: prod::email_outbox::BASE_RETRY_DELAY,
+            poll_interval: prod::email_outbox::POLL_INTERVAL,
+        },
+    ));
     let app_state = AppState::new(
         user_store,
         banned_token_store,
         two_fa_code_store,
-        email_client,
+        email_outbox.clone(),
+        oauth_client_store,
+        authorization_code_store,
+        refresh_token_store,
+        service_account_store,
+        device_code_store,
+        Arc::new(NoOpClaimsEnricher),
+        trusted_device_store,
+        login_history_store,
+        audit_sink,
+        webhook_dispatcher.clone(),
+        configure_breached_password_checker(),
+        Arc::new(configure_password_policy()),
+        Arc::new(configure_email_domain_policy()),
+        Arc::new(
+            TrustedProxies::parse(&TRUSTED_PROXIES)
+                .expect("Failed to parse TRUSTED_PROXIES"),
+        ),
+        mailbox,
+        email_failover,
     );
+    tokio::spawn(email_outbox.run());
+    tokio::spawn(webhook_dispatcher.run());
 
     let application = Application::build(app_state, prod::APP_ADDRESS)
         .await
@@ -69,6 +189,69 @@ async fn configure_postgresql() -> PgPool {
     pg_pool
 }
 
+async fn configure_audit_sink(pg_pool: PgPool) -> AuditSinkType {
+    match AUDIT_LOG_PATH.as_ref() {
+        Some(path) => Arc::new(
+            JsonLinesAuditSink::open(path)
+                .await
+                .expect("Failed to open audit log"),
+        ),
+        None => Arc::new(PostgresAuditSink::new(pg_pool)),
+    }
+}
+
+fn configure_webhook_dispatcher(pg_pool: PgPool) -> Arc<WebhookDispatcher> {
+    // Without endpoints nothing is sent, so there is nothing to sign
+    let signing_secret = match WEBHOOK_SECRET.as_ref() {
+        Some(secret) => secret.clone(),
+        None if WEBHOOK_URLS.is_empty() => Secret::new(String::new()),
+        None => panic!("WEBHOOK_SECRET must be set when WEBHOOK_URLS is"),
+    };
+    let http_client = Client::builder()
+        .timeout(prod::webhooks::TIMEOUT)
+        .build()
+        .expect("Failed to build HTTP client");
+
+    Arc::new(WebhookDispatcher::new(
+        Arc::new(RwLock::new(PostgresWebhookStore::new(pg_pool))),
+        WEBHOOK_URLS.clone(),
+        signing_secret,
+        RetryPolicy {
+            max_attempts: prod::webhooks::MAX_ATTEMPTS,
+            base_delay: prod::webhooks::BASE_RETRY_DELAY,
+            poll_interval: prod::webhooks::POLL_INTERVAL,
+        },
+        http_client,
+    ))
+}
+
+// Recreate the account on every start, so changing its secret takes effect
+async fn seed_service_account(
+    service_account_store: &ServiceAccountStoreType,
+    name: &str,
+    credentials: Option<&ServiceCredentials>,
+    scope: &str,
+) {
+    let Some((client_id, client_secret)) = credentials else {
+        return;
+    };
+
+    let account = ServiceAccount {
+        client_id: client_id.to_owned(),
+        name: name.to_owned(),
+        scopes: Scopes::parse(scope).expect("Failed to parse scope"),
+        client_secret: ClientSecret::parse(client_secret.to_owned())
+            .expect("Failed to parse client secret"),
+    };
+
+    let mut store = service_account_store.write().await;
+    let _ = store.delete_account(client_id).await;
+    store
+        .add_account(account)
+        .await
+        .expect("Failed to create service account");
+}
+
 fn configure_redis() -> redis::Connection {
     get_redis_client(REDIS_HOST_NAME.to_owned())
         .expect("Failed to get Redis client")
@@ -76,6 +259,154 @@ fn configure_redis() -> redis::Connection {
         .expect("Failed to get Redis connection")
 }
 
+fn configure_password_hashers() -> PasswordHashers {
+    let params = Params::new(
+        *PASSWORD_HASH_MEMORY_KIB,
+        *PASSWORD_HASH_ITERATIONS,
+        *PASSWORD_HASH_PARALLELISM,
+        None,
+    )
+    .expect("Invalid PASSWORD_HASH_* settings");
+    let peppers = PASSWORD_PEPPERS
+        .iter()
+        .map(|pepper| Pepper::parse(pepper).expect("Invalid PASSWORD_PEPPERS"))
+        .collect::<Vec<_>>();
+    for (i, pepper) in peppers.iter().enumerate() {
+        assert!(
+            peppers[..i].iter().all(|p| p.version() != pepper.version()),
+            "PASSWORD_PEPPERS versions must be unique"
+        );
+    }
+    PasswordHashers::with_legacy_formats(Argon2PasswordHasher::new(
+        params, peppers,
+    ))
+}
+
+fn configure_password_policy() -> PasswordPolicy {
+    let policy = PasswordPolicy {
+        min_length: *PASSWORD_MIN_LENGTH,
+        max_length: *PASSWORD_MAX_LENGTH,
+        min_strength: *PASSWORD_MIN_STRENGTH,
+        banned_words: PASSWORD_BANNED_WORDS.clone(),
+    };
+    assert!(
+        policy.min_length <= policy.max_length,
+        "PASSWORD_MIN_LENGTH must not be more than PASSWORD_MAX_LENGTH"
+    );
+    assert!(
+        policy.min_strength <= 4,
+        "PASSWORD_MIN_STRENGTH must be from 0 to 4"
+    );
+    policy
+}
+
+fn configure_email_domain_policy() -> EmailDomainPolicy {
+    let disposable_domains = match DISPOSABLE_EMAIL_DOMAINS_FILE.as_ref() {
+        Some(path) => EmailDomainPolicy::parse_domain_list(
+            &std::fs::read_to_string(path)
+                .expect("Failed to read DISPOSABLE_EMAIL_DOMAINS_FILE"),
+        ),
+        None => Default::default(),
+    };
+    EmailDomainPolicy {
+        allowed_domains: SIGNUP_ALLOWED_EMAIL_DOMAINS.clone(),
+        denied_domains: SIGNUP_DENIED_EMAIL_DOMAINS.clone(),
+        disposable_domains,
+    }
+}
+
+fn configure_breached_password_checker() -> BreachedPasswordCheckerType {
+    let checker: BreachedPasswordCheckerType =
+        match BREACHED_PASSWORD_CHECK.as_str() {
+            "off" => return Arc::new(NoOpBreachedPasswordChecker),
+            "file" => Arc::new(RangeFileBreachedPasswordChecker::new(
+                BREACHED_PASSWORD_RANGES_DIR.clone(),
+            )),
+            "hibp" => {
+                let http_client = Client::builder()
+                    .timeout(prod::breached_passwords::TIMEOUT)
+                    .build()
+                    .expect("Failed to build HTTP client");
+                Arc::new(HibpBreachedPasswordChecker::new(
+                    HIBP_API_URL.to_owned(),
+                    http_client,
+                ))
+            }
+            check => panic!("Unknown BREACHED_PASSWORD_CHECK: {}", check),
+        };
+    if *BREACHED_PASSWORD_CHECK_FAIL_OPEN {
+        Arc::new(FailOpenBreachedPasswordChecker::new(checker))
+    } else {
+        checker
+    }
+}
+
+// A single provider is used as is, and several are failed over between in
+// order. With `DEV_MAILBOX_ENABLED`, a lone `file` client is also returned as
+// the mailbox served at `/dev/mailbox`. The failover client is returned for
+// its metrics
+fn configure_email_client() -> (
+    EmailClientType,
+    Option<MailboxType>,
+    Option<EmailFailoverType>,
+) {
+    let mut file_email_client = None;
+    let mut providers: Vec<(String, EmailClientType)> = Vec::new();
+    for provider in EMAIL_PROVIDER.split(',').map(str::trim) {
+        let email_client: EmailClientType = match provider {
+            "postmark" => Arc::new(configure_postmark_email_client()),
+            "smtp" => Arc::new(configure_smtp_email_client()),
+            "file" => {
+                let email_client = Arc::new(FileEmailClient::new(
+                    MAILBOX_DIR.clone(),
+                    Email::parse(Secret::new(
+                        MAILBOX_SENDER_ADDRESS.to_owned(),
+                    ))
+                    .expect("Failed to parse MAILBOX_SENDER_ADDRESS"),
+                ));
+                file_email_client = Some(email_client.clone());
+                email_client
+            }
+            provider => panic!("Unknown EMAIL_PROVIDER: {}", provider),
+        };
+        providers.push((provider.to_owned(), email_client));
+    }
+
+    if *DEV_MAILBOX_ENABLED
+        && (providers.len() != 1 || file_email_client.is_none())
+    {
+        panic!("DEV_MAILBOX_ENABLED needs EMAIL_PROVIDER=file");
+    }
+    if providers.len() == 1 {
+        let mailbox = file_email_client.filter(|_| *DEV_MAILBOX_ENABLED);
+        return (providers.remove(0).1, mailbox, None);
+    }
+    let email_failover = Arc::new(FailoverEmailClient::new(
+        providers,
+        prod::email_failover::TIMEOUT,
+        prod::email_failover::FAILURE_THRESHOLD,
+        prod::email_failover::OPEN_DURATION,
+    ));
+    (email_failover.clone(), None, Some(email_failover))
+}
+
+fn configure_smtp_email_client() -> SmtpEmailClient {
+    let url = SMTP_URL
+        .as_ref()
+        .expect("SMTP_URL must be set when EMAIL_PROVIDER is smtp");
+    let sender = SMTP_SENDER_ADDRESS
+        .clone()
+        .expect("SMTP_SENDER_ADDRESS must be set when EMAIL_PROVIDER is smtp");
+
+    SmtpEmailClient::new(
+        url,
+        Email::parse(sender).expect("Failed to parse SMTP_SENDER_ADDRESS"),
+        prod::smtp::TIMEOUT,
+        prod::smtp::POOL_SIZE,
+    )
+    .expect("Failed to build SMTP email client")
+}
+
 fn configure_postmark_email_client() -> PostmarkEmailClient {
     let http_client = Client::builder()
         .timeout(prod::email_client::TIMEOUT)
@@ -0,0 +1,70 @@
+use axum::{extract::State, http::StatusCode, Json};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuditAction, AuditOutcome, AuthAPIError, UserStoreError,
+        WebhookEventType,
+    },
+    utils::{
+        audit::audit, auth::validate_session, client_info::ClientInfo,
+        webhooks::publish_user_event,
+    },
+};
+
+#[tracing::instrument(name = "Update 2FA route handler", skip_all)]
+pub async fn update_account_2fa(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    client: ClientInfo,
+    Json(request): Json<UpdateAccount2FARequest>,
+) -> Result<StatusCode, AuthAPIError> {
+    let session =
+        validate_session(&jar, state.banned_token_store.clone()).await?;
+    let email = session.email;
+
+    let result = state
+        .user_store
+        .write()
+        .await
+        .set_requires_2fa(&email, request.requires_2fa)
+        .await;
+    let outcome = match result {
+        Ok(()) => AuditOutcome::Success,
+        Err(_) => AuditOutcome::Failure,
+    };
+    let email_str = email.as_ref().expose_secret();
+    audit(
+        &state,
+        &client,
+        AuditAction::Update2FA,
+        outcome,
+        Some(email_str),
+        Some(email_str),
+    )
+    .await;
+    result.map_err(|e| match e {
+        UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+        err => AuthAPIError::UnexpectedError(eyre!(err)),
+    })?;
+
+    publish_user_event(
+        &state,
+        WebhookEventType::TwoFAChanged,
+        &email,
+        Some(request.requires_2fa),
+    )
+    .await;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+pub struct UpdateAccount2FARequest {
+    #[serde(rename = "requires2FA")]
+    pub requires_2fa: bool,
+}
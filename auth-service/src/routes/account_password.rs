@@ -0,0 +1,91 @@
+use axum::{extract::State, http::StatusCode, Json};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+
+use super::signup::ensure_not_breached;
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuditAction, AuditOutcome, AuthAPIError, Password, UserStoreError,
+    },
+    utils::{audit::audit, auth::validate_session, client_info::ClientInfo},
+};
+
+// Needs the current password as well as a session, so a stolen session alone
+// can't take over the account
+#[tracing::instrument(name = "Change password route handler", skip_all)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    client: ClientInfo,
+    Json(request): Json<ChangePasswordRequest>,
+) -> Result<StatusCode, AuthAPIError> {
+    let session =
+        validate_session(&jar, state.banned_token_store.clone()).await?;
+    let email = session.email;
+
+    let current_password = Password::parse(request.current_password)
+        .map_err(|_| AuthAPIError::ValidationError)?;
+    let new_password = Password::parse(request.new_password)
+        .map_err(|_| AuthAPIError::ValidationError)?
+        .normalised();
+
+    let validation = state
+        .user_store
+        .read()
+        .await
+        .validate_user(&email, &current_password)
+        .await;
+    let result = match validation {
+        Ok(()) => {
+            state
+                .password_policy
+                .validate(&new_password, &email)
+                .map_err(AuthAPIError::PasswordPolicyViolation)?;
+            ensure_not_breached(&state, &new_password).await?;
+
+            state
+                .user_store
+                .write()
+                .await
+                .update_password(&email, new_password)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+
+    let outcome = match result {
+        Ok(()) => AuditOutcome::Success,
+        Err(_) => AuditOutcome::Failure,
+    };
+    let email_str = email.as_ref().expose_secret();
+    audit(
+        &state,
+        &client,
+        AuditAction::ChangePassword,
+        outcome,
+        Some(email_str),
+        Some(email_str),
+    )
+    .await;
+    result.map_err(|e| match e {
+        UserStoreError::InvalidCredentials => {
+            AuthAPIError::IncorrectCredentials
+        }
+        UserStoreError::PasswordReused => AuthAPIError::PasswordReused,
+        UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+        err => AuthAPIError::UnexpectedError(eyre!(err)),
+    })?;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+pub struct ChangePasswordRequest {
+    #[serde(rename = "currentPassword")]
+    pub current_password: Secret<String>,
+    #[serde(rename = "newPassword")]
+    pub new_password: Secret<String>,
+}
@@ -0,0 +1,30 @@
+use axum::{extract::State, http::HeaderMap, response::IntoResponse, Json};
+
+use crate::{
+    app_state::AppState,
+    domain::AuthAPIError,
+    utils::{auth::validate_service_token, constants::ADMIN_SCOPE},
+};
+
+// How many emails each provider has sent, failed or skipped since start up.
+// Empty unless there's more than one provider to fail over between
+#[tracing::instrument(name = "Email provider metrics route handler", skip_all)]
+pub async fn email_provider_metrics(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    validate_service_token(
+        &headers,
+        state.banned_token_store.clone(),
+        ADMIN_SCOPE,
+    )
+    .await?;
+
+    Ok(Json(
+        state
+            .email_failover
+            .as_ref()
+            .map(|email_failover| email_failover.delivery_metrics())
+            .unwrap_or_default(),
+    ))
+}
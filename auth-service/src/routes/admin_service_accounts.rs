@@ -0,0 +1,188 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use color_eyre::eyre::eyre;
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuditAction, AuditOutcome, AuthAPIError, ClientSecret, Scopes,
+        ServiceAccount, ServiceAccountStoreError,
+    },
+    utils::{
+        audit::audit, auth::validate_service_token, client_info::ClientInfo,
+        constants::ADMIN_SCOPE,
+    },
+};
+
+#[tracing::instrument(name = "Create service account route handler", skip_all)]
+pub async fn create_service_account(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    client: ClientInfo,
+    Json(request): Json<CreateServiceAccountRequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let claims = validate_service_token(
+        &headers,
+        state.banned_token_store.clone(),
+        ADMIN_SCOPE,
+    )
+    .await?;
+
+    if request.name.trim().is_empty() {
+        return Err(AuthAPIError::ValidationError);
+    }
+    let scopes = Scopes::parse(&request.scope)
+        .map_err(|_| AuthAPIError::ValidationError)?;
+    if scopes.is_empty() {
+        return Err(AuthAPIError::ValidationError);
+    }
+
+    let account =
+        ServiceAccount::new(request.name, scopes, ClientSecret::default());
+
+    let response = CreateServiceAccountResponse {
+        client_id: account.client_id.clone(),
+        // Only the hash is kept, so this is the one chance to see the secret
+        client_secret: account
+            .client_secret
+            .as_ref()
+            .expose_secret()
+            .to_owned(),
+        name: account.name.clone(),
+        scope: account.scopes.to_string(),
+    };
+
+    let result = state
+        .service_account_store
+        .write()
+        .await
+        .add_account(account)
+        .await;
+    audit_admin_action(
+        &state,
+        &client,
+        AuditAction::CreateServiceAccount,
+        result.is_ok(),
+        claims.client_id.as_deref(),
+        &response.client_id,
+    )
+    .await;
+    result.map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[tracing::instrument(name = "List service accounts route handler", skip_all)]
+pub async fn list_service_accounts(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    validate_service_token(
+        &headers,
+        state.banned_token_store.clone(),
+        ADMIN_SCOPE,
+    )
+    .await?;
+
+    let accounts = state
+        .service_account_store
+        .read()
+        .await
+        .get_accounts()
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    let response: Vec<ServiceAccountResponse> = accounts
+        .into_iter()
+        .map(|account| ServiceAccountResponse {
+            client_id: account.client_id,
+            name: account.name,
+            scope: account.scopes.to_string(),
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[tracing::instrument(name = "Delete service account route handler", skip_all)]
+pub async fn delete_service_account(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    client: ClientInfo,
+    Path(client_id): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let claims = validate_service_token(
+        &headers,
+        state.banned_token_store.clone(),
+        ADMIN_SCOPE,
+    )
+    .await?;
+
+    // Tokens already issued to the account stay valid until they expire,
+    // which service tokens do quickly
+    let result = state
+        .service_account_store
+        .write()
+        .await
+        .delete_account(&client_id)
+        .await;
+    audit_admin_action(
+        &state,
+        &client,
+        AuditAction::DeleteServiceAccount,
+        result.is_ok(),
+        claims.client_id.as_deref(),
+        &client_id,
+    )
+    .await;
+    result.map_err(|e| match e {
+        ServiceAccountStoreError::AccountNotFound => {
+            AuthAPIError::ServiceAccountNotFound
+        }
+        e => AuthAPIError::UnexpectedError(eyre!(e)),
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn audit_admin_action(
+    state: &AppState,
+    client: &ClientInfo,
+    action: AuditAction,
+    success: bool,
+    actor: Option<&str>,
+    target: &str,
+) {
+    let outcome = match success {
+        true => AuditOutcome::Success,
+        false => AuditOutcome::Failure,
+    };
+    audit(state, client, action, outcome, actor, Some(target)).await;
+}
+
+#[derive(Deserialize)]
+pub struct CreateServiceAccountRequest {
+    pub name: String,
+    pub scope: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateServiceAccountResponse {
+    pub client_id: String,
+    pub client_secret: String,
+    pub name: String,
+    pub scope: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServiceAccountResponse {
+    pub client_id: String,
+    pub name: String,
+    pub scope: String,
+}
@@ -0,0 +1,110 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use color_eyre::eyre::eyre;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditAction, AuditOutcome, AuthAPIError, Email, UserStoreError},
+    utils::{
+        audit::audit, auth::validate_service_token, client_info::ClientInfo,
+        constants::ADMIN_SCOPE,
+    },
+};
+
+#[tracing::instrument(
+    name = "List undeliverable emails route handler",
+    skip_all
+)]
+pub async fn list_undeliverable_emails(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    validate_service_token(
+        &headers,
+        state.banned_token_store.clone(),
+        ADMIN_SCOPE,
+    )
+    .await?;
+
+    let users = state
+        .user_store
+        .read()
+        .await
+        .get_undeliverable_users()
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    let response: Vec<UndeliverableEmailResponse> = users
+        .into_iter()
+        .filter_map(|user| {
+            let undeliverable = user.email_undeliverable?;
+            Some(UndeliverableEmailResponse {
+                email: user.email.as_ref().expose_secret().to_owned(),
+                reason: undeliverable.reason,
+                marked_at: undeliverable.marked_at,
+            })
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+// For when the user has fixed their inbox, so 2FA codes are sent again
+#[tracing::instrument(
+    name = "Clear undeliverable email route handler",
+    skip_all
+)]
+pub async fn clear_undeliverable_email(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    client: ClientInfo,
+    Path(email): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let claims = validate_service_token(
+        &headers,
+        state.banned_token_store.clone(),
+        ADMIN_SCOPE,
+    )
+    .await?;
+
+    let email = Email::parse(Secret::new(email))
+        .map_err(|_| AuthAPIError::ValidationError)?;
+    let result = state
+        .user_store
+        .write()
+        .await
+        .set_email_undeliverable(&email, None)
+        .await;
+    let outcome = match result {
+        Ok(()) => AuditOutcome::Success,
+        Err(_) => AuditOutcome::Failure,
+    };
+    audit(
+        &state,
+        &client,
+        AuditAction::ClearEmailUndeliverable,
+        outcome,
+        claims.client_id.as_deref(),
+        Some(email.as_ref().expose_secret()),
+    )
+    .await;
+    result.map_err(|e| match e {
+        UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+        e => AuthAPIError::UnexpectedError(eyre!(e)),
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct UndeliverableEmailResponse {
+    pub email: String,
+    pub reason: String,
+    pub marked_at: usize,
+}
@@ -0,0 +1,157 @@
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Json,
+};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuditAction, AuditOutcome, AuthAPIError, Email, ImportedUser,
+        UserStoreError,
+    },
+    utils::{
+        audit::audit, auth::validate_service_token, client_info::ClientInfo,
+        constants::ADMIN_SCOPE,
+    },
+};
+
+// Moves users over from another system with their existing password hashes,
+// as a JSON array or as CSV with an `email,password_hash,requires_2fa`
+// header. Each row is imported on its own, and the ones that can't be are
+// reported rather than failing the rest
+#[tracing::instrument(name = "Import users route handler", skip_all)]
+pub async fn import_users(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    client: ClientInfo,
+    body: Bytes,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let claims = validate_service_token(
+        &headers,
+        state.banned_token_store.clone(),
+        ADMIN_SCOPE,
+    )
+    .await?;
+
+    let rows = if is_csv(&headers) {
+        parse_csv(&body)
+    } else {
+        serde_json::from_slice::<Vec<ImportUserRequest>>(&body)
+            .map_err(|_| AuthAPIError::ValidationError)?
+            .into_iter()
+            .map(Ok)
+            .collect()
+    };
+
+    let mut response = ImportUsersResponse::default();
+    for (index, row) in rows.into_iter().enumerate() {
+        let row_number = index + 1;
+        let request = match row {
+            Ok(request) => request,
+            Err(error) => {
+                response
+                    .failed
+                    .push(ImportUserFailure::new(row_number, None, error));
+                continue;
+            }
+        };
+
+        let Ok(email) = Email::parse(Secret::new(request.email.clone())) else {
+            response.failed.push(ImportUserFailure::new(
+                row_number,
+                Some(request.email),
+                "Invalid email".to_owned(),
+            ));
+            continue;
+        };
+        let user = ImportedUser::new(
+            email.clone(),
+            request.password_hash,
+            request.requires_2fa,
+        );
+
+        let result = state.user_store.write().await.import_user(user).await;
+        let outcome = match result {
+            Ok(()) => AuditOutcome::Success,
+            Err(_) => AuditOutcome::Failure,
+        };
+        audit(
+            &state,
+            &client,
+            AuditAction::ImportUser,
+            outcome,
+            claims.client_id.as_deref(),
+            Some(email.as_ref().expose_secret()),
+        )
+        .await;
+
+        match result {
+            Ok(()) => response.imported += 1,
+            Err(e) => {
+                if let UserStoreError::UnexpectedError(report) = &e {
+                    tracing::error!("Failed to import user: {:?}", report);
+                }
+                response.failed.push(ImportUserFailure::new(
+                    row_number,
+                    Some(request.email),
+                    e.to_string(),
+                ));
+            }
+        }
+    }
+
+    Ok(Json(response))
+}
+
+fn is_csv(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/csv"))
+}
+
+// One result per data row, so a malformed row is reported by its number
+fn parse_csv(body: &[u8]) -> Vec<Result<ImportUserRequest, String>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body);
+    reader
+        .deserialize::<ImportUserRequest>()
+        .map(|row| row.map_err(|_| "Malformed row".to_owned()))
+        .collect()
+}
+
+#[derive(Deserialize)]
+pub struct ImportUserRequest {
+    pub email: String,
+    pub password_hash: Secret<String>,
+    // CSV headers are snake case
+    #[serde(rename = "requires2FA", alias = "requires_2fa", default)]
+    pub requires_2fa: bool,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ImportUsersResponse {
+    pub imported: usize,
+    pub failed: Vec<ImportUserFailure>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct ImportUserFailure {
+    // Counting from 1, not including a CSV header
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub error: String,
+}
+
+impl ImportUserFailure {
+    fn new(row: usize, email: Option<String>, error: String) -> Self {
+        Self { row, email, error }
+    }
+}
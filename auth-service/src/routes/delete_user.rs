@@ -5,27 +5,48 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     app_state::AppState,
-    domain::{AuthAPIError, Email, UserStoreError},
+    domain::{
+        AuditAction, AuditOutcome, AuthAPIError, Email, UserStoreError,
+        WebhookEventType,
+    },
+    utils::{
+        audit::audit, client_info::ClientInfo, webhooks::publish_user_event,
+    },
 };
 
 #[tracing::instrument(name = "Delete user route handler", skip_all)]
 pub async fn delete_user(
     State(state): State<AppState>,
+    client: ClientInfo,
     Json(request): Json<DeleteUserRequest>,
 ) -> Result<impl IntoResponse, AuthAPIError> {
     let email = Email::parse(Secret::new(request.email))
         .map_err(|_| AuthAPIError::ValidationError)?;
 
-    {
-        let mut user_store = state.user_store.write().await;
-        user_store.delete_user(&email).await.map_err(|e| match e {
-            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
-            err => AuthAPIError::UnexpectedError(eyre!(err)),
-        })?;
-    }
+    let result = state.user_store.write().await.delete_user(&email).await;
+    let outcome = match result {
+        Ok(()) => AuditOutcome::Success,
+        Err(_) => AuditOutcome::Failure,
+    };
+    audit(
+        &state,
+        &client,
+        AuditAction::DeleteUser,
+        outcome,
+        None,
+        Some(email.as_ref().expose_secret()),
+    )
+    .await;
+    result.map_err(|e| match e {
+        UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+        err => AuthAPIError::UnexpectedError(eyre!(err)),
+    })?;
+
+    publish_user_event(&state, WebhookEventType::UserDeleted, &email, None)
+        .await;
 
     let message = format!("User deleted: {}", email.as_ref().expose_secret());
-    let response = Json(DeleteUserResponse { message: message });
+    let response = Json(DeleteUserResponse { message });
 
     Ok((StatusCode::OK, response))
 }
@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+
+use crate::{app_state::MailboxType, domain::AuthAPIError};
+
+#[derive(Debug, Deserialize)]
+pub struct MailboxQuery {
+    pub to: Option<String>,
+}
+
+// Lists the emails written by `FileEmailClient`, newest first, optionally
+// only those sent to one address
+#[tracing::instrument(name = "List mailbox route handler", skip_all)]
+pub async fn list_mailbox(
+    State(mailbox): State<MailboxType>,
+    Query(query): Query<MailboxQuery>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let messages = mailbox
+        .list_messages()
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    let response: Vec<MailboxEntryResponse> = messages
+        .into_iter()
+        .filter(|message| match &query.to {
+            Some(to) => message.to.eq_ignore_ascii_case(to),
+            None => true,
+        })
+        .map(|message| MailboxEntryResponse {
+            id: message.id,
+            from: message.from,
+            to: message.to,
+            subject: message.subject,
+            date: message.date,
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[tracing::instrument(name = "Get mailbox message route handler", skip_all)]
+pub async fn get_mailbox_message(
+    State(mailbox): State<MailboxType>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let message = mailbox
+        .get_message(&id)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?
+        .ok_or(AuthAPIError::MessageNotFound)?;
+
+    Ok(Json(message))
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct MailboxEntryResponse {
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub date: String,
+}
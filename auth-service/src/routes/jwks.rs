@@ -0,0 +1,17 @@
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::oidc::{Jwk, ID_TOKEN_SIGNING_KEY};
+
+// Public keys for verifying ID tokens
+#[tracing::instrument(name = "JWKS route handler", skip_all)]
+pub async fn jwks() -> impl IntoResponse {
+    Json(JwkSet {
+        keys: vec![ID_TOKEN_SIGNING_KEY.jwk().clone()],
+    })
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
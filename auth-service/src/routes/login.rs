@@ -1,21 +1,31 @@
 use axum::{extract::State, http::StatusCode, Json};
 use axum_extra::extract::CookieJar;
+use color_eyre::eyre::Result;
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::{
     app_state::AppState,
     domain::{
-        AuthAPIError, Email, LoginAttemptId, Password, TwoFACode,
-        UserStoreError,
+        AuditAction, AuditOutcome, AuthAPIError, Email, LoginAttemptId,
+        LoginMethod, Password, TwoFACode, User, UserStoreError,
+    },
+    utils::{
+        audit::audit,
+        auth::{generate_auth_cookie, validate_trusted_device},
+        client_info::ClientInfo,
+        constants::TWO_FA_CODE_TTL_SECONDS,
+        email_templates::{Branding, TwoFACodeEmail},
+        login_history::record_login,
     },
-    utils::auth::generate_auth_cookie,
 };
 
 #[tracing::instrument(name = "Login", skip_all)]
 pub async fn login(
     State(state): State<AppState>,
     jar: CookieJar,
+    client: ClientInfo,
     Json(request): Json<LoginRequest>,
 ) -> (
     CookieJar,
@@ -36,10 +46,23 @@ pub async fn login(
     match user_store.validate_user(&email, &password).await {
         Ok(()) => (),
         Err(UserStoreError::InvalidCredentials) => {
-            return (jar, Err(AuthAPIError::IncorrectCredentials))
+            audit_failed_login(&state, &client, &email).await;
+            let result = record_login(
+                &state,
+                &email,
+                &client,
+                LoginMethod::Password,
+                false,
+            )
+            .await;
+            if let Err(err) = result {
+                return (jar, Err(AuthAPIError::UnexpectedError(err)));
+            }
+            return (jar, Err(AuthAPIError::IncorrectCredentials));
         }
         Err(UserStoreError::UserNotFound) => {
-            return (jar, Err(AuthAPIError::IncorrectCredentials))
+            audit_failed_login(&state, &client, &email).await;
+            return (jar, Err(AuthAPIError::IncorrectCredentials));
         }
         Err(err) => {
             return (jar, Err(AuthAPIError::UnexpectedError(err.into())))
@@ -52,8 +75,17 @@ pub async fn login(
     };
 
     match user.requires_2fa {
-        true => handle_2fa(&user.email, &state, jar).await,
-        false => handle_no_2fa(&user.email, jar).await,
+        true => handle_2fa(&user, &client, &state, jar).await,
+        false => {
+            complete_login(
+                &user.email,
+                LoginMethod::Password,
+                &client,
+                &state,
+                jar,
+            )
+            .await
+        }
     }
 }
 
@@ -65,13 +97,38 @@ pub struct LoginRequest {
 
 #[tracing::instrument(name = "Handling 2FA login", skip_all)]
 async fn handle_2fa(
-    email: &Email,
+    user: &User,
+    client: &ClientInfo,
     state: &AppState,
     jar: CookieJar,
 ) -> (
     CookieJar,
     Result<(StatusCode, Json<LoginResponse>), AuthAPIError>,
 ) {
+    let email = &user.email;
+    // Browsers trusted after an earlier 2FA login don't need a new code
+    match validate_trusted_device(&jar, email, &state.trusted_device_store)
+        .await
+    {
+        Ok(true) => {
+            return complete_login(
+                email,
+                LoginMethod::TrustedDevice,
+                client,
+                state,
+                jar,
+            )
+            .await
+        }
+        Ok(false) => (),
+        Err(err) => return (jar, Err(AuthAPIError::UnexpectedError(err))),
+    }
+
+    // The code would never arrive, so say why rather than leave them waiting
+    if user.email_undeliverable.is_some() {
+        return (jar, Err(AuthAPIError::EmailUndeliverable));
+    }
+
     let login_attempt_id = LoginAttemptId::default();
     let two_fa_code = TwoFACode::default();
 
@@ -88,17 +145,8 @@ async fn handle_2fa(
         }
     }
 
-    match state
-        .email_client
-        .send_email(
-            &email,
-            "LGR Bootcamp 2FA Code",
-            two_fa_code.as_ref().expose_secret(),
-        )
-        .await
-    {
-        Ok(()) => (),
-        Err(err) => return (jar, Err(AuthAPIError::UnexpectedError(err))),
+    if let Err(err) = send_two_fa_code(state, email, &two_fa_code).await {
+        return (jar, Err(AuthAPIError::UnexpectedError(err)));
     }
 
     let response = Json(LoginResponse::TwoFactorAuth(TwoFactorAuthResponse {
@@ -111,21 +159,66 @@ async fn handle_2fa(
     (jar, Ok((StatusCode::PARTIAL_CONTENT, response)))
 }
 
-#[tracing::instrument(name = "Handling login without 2FA", skip_all)]
-async fn handle_no_2fa(
+async fn send_two_fa_code(
+    state: &AppState,
     email: &Email,
+    two_fa_code: &TwoFACode,
+) -> Result<()> {
+    let message = TwoFACodeEmail {
+        code: two_fa_code.as_ref().expose_secret().to_owned(),
+        expires_in_minutes: *TWO_FA_CODE_TTL_SECONDS / 60,
+    }
+    .render(&Branding::from_config())?;
+
+    state
+        .email_client
+        .send_email(
+            &Uuid::new_v4().to_string(),
+            email,
+            &message.subject,
+            &message.html_body,
+            &message.text_body,
+        )
+        .await
+}
+
+#[tracing::instrument(name = "Completing login", skip_all)]
+async fn complete_login(
+    email: &Email,
+    method: LoginMethod,
+    client: &ClientInfo,
+    state: &AppState,
     jar: CookieJar,
 ) -> (
     CookieJar,
     Result<(StatusCode, Json<LoginResponse>), AuthAPIError>,
 ) {
-    let auth_cookie = match generate_auth_cookie(&email) {
+    // A trusted device stands in for the 2FA code
+    let auth_cookie = match generate_auth_cookie(
+        email,
+        method != LoginMethod::Password,
+        &state.claims_enricher,
+    )
+    .await
+    {
         Ok(cookie) => cookie,
-        Err(err) => {
-            return (jar, Err(AuthAPIError::UnexpectedError(err.into())))
-        }
+        Err(err) => return (jar, Err(AuthAPIError::UnexpectedError(err))),
     };
 
+    if let Err(err) = record_login(state, email, client, method, true).await {
+        return (jar, Err(AuthAPIError::UnexpectedError(err)));
+    }
+    let email = email.as_ref().expose_secret();
+    audit(
+        state,
+        client,
+        AuditAction::Login,
+        AuditOutcome::Success,
+        Some(email),
+        Some(email),
+    )
+    .await;
+
     let updated_jar = jar.add(auth_cookie);
 
     (
@@ -134,6 +227,22 @@ async fn handle_no_2fa(
     )
 }
 
+async fn audit_failed_login(
+    state: &AppState,
+    client: &ClientInfo,
+    email: &Email,
+) {
+    audit(
+        state,
+        client,
+        AuditAction::Login,
+        AuditOutcome::Failure,
+        None,
+        Some(email.as_ref().expose_secret()),
+    )
+    .await;
+}
+
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
 pub enum LoginResponse {
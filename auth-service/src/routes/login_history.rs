@@ -0,0 +1,49 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState, domain::AuthAPIError, utils::auth::validate_session,
+};
+
+const MAX_LOGIN_HISTORY_EVENTS: usize = 100;
+
+#[tracing::instrument(name = "Login history route handler", skip_all)]
+pub async fn login_history(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let session =
+        validate_session(&jar, state.banned_token_store.clone()).await?;
+
+    let events = state
+        .login_history_store
+        .read()
+        .await
+        .get_events(&session.email, MAX_LOGIN_HISTORY_EVENTS)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    let response: Vec<LoginEventResponse> = events
+        .into_iter()
+        .map(|event| LoginEventResponse {
+            timestamp: event.timestamp,
+            ip: event.ip,
+            user_agent: event.user_agent,
+            method: event.method.as_ref().to_owned(),
+            success: event.success,
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct LoginEventResponse {
+    pub timestamp: usize,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub method: String,
+    pub success: bool,
+}
@@ -4,8 +4,11 @@ use color_eyre::eyre::eyre;
 use secrecy::Secret;
 
 use crate::{
-    domain::AuthAPIError,
-    utils::{auth::validate_token, constants::JWT_COOKIE_NAME},
+    domain::{AuditAction, AuditOutcome, AuthAPIError},
+    utils::{
+        audit::audit, auth::validate_token, client_info::ClientInfo,
+        constants::JWT_COOKIE_NAME,
+    },
     AppState,
 };
 
@@ -13,6 +16,7 @@ use crate::{
 pub async fn logout(
     State(state): State<AppState>,
     jar: CookieJar,
+    client: ClientInfo,
 ) -> (CookieJar, Result<impl IntoResponse, AuthAPIError>) {
     let cookie = match jar.get(JWT_COOKIE_NAME) {
         Some(cookie) => cookie,
@@ -21,10 +25,22 @@ pub async fn logout(
 
     let token = Secret::new(cookie.value().to_string());
 
-    match validate_token(&token, state.banned_token_store.clone()).await {
-        Ok(_) => (),
-        Err(_) => return (jar, Err(AuthAPIError::InvalidToken)),
-    };
+    let claims =
+        match validate_token(&token, state.banned_token_store.clone()).await {
+            Ok(claims) => claims,
+            Err(_) => {
+                audit(
+                    &state,
+                    &client,
+                    AuditAction::Logout,
+                    AuditOutcome::Failure,
+                    None,
+                    None,
+                )
+                .await;
+                return (jar, Err(AuthAPIError::InvalidToken));
+            }
+        };
 
     match state
         .banned_token_store
@@ -39,6 +55,16 @@ pub async fn logout(
         }
     }
 
+    audit(
+        &state,
+        &client,
+        AuditAction::Logout,
+        AuditOutcome::Success,
+        Some(&claims.sub),
+        Some(&claims.sub),
+    )
+    .await;
+
     let jar = jar.remove(cookie::Cookie::from(JWT_COOKIE_NAME));
 
     (jar, Ok(StatusCode::OK))
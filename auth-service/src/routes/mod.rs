@@ -1,13 +1,53 @@
+mod account_2fa;
+mod account_password;
+mod admin_email_providers;
+mod admin_service_accounts;
+mod admin_undeliverable_emails;
+mod admin_user_import;
 mod delete_user;
+mod dev_mailbox;
+mod jwks;
 mod login;
+mod login_history;
 mod logout;
+mod oauth_authorize;
+mod oauth_clients;
+mod oauth_device;
+mod oauth_device_authorization;
+mod oauth_introspect;
+mod oauth_revoke;
+mod oauth_token;
+mod openid_configuration;
+mod postmark_webhook;
 mod signup;
+mod trusted_devices;
+mod userinfo;
 mod verify_2fa;
 mod verify_token;
 
+pub use account_2fa::*;
+pub use account_password::*;
+pub use admin_email_providers::*;
+pub use admin_service_accounts::*;
+pub use admin_undeliverable_emails::*;
+pub use admin_user_import::*;
 pub use delete_user::*;
+pub use dev_mailbox::*;
+pub use jwks::*;
 pub use login::*;
+pub use login_history::*;
 pub use logout::*;
+pub use oauth_authorize::*;
+pub use oauth_clients::*;
+pub use oauth_device::*;
+pub use oauth_device_authorization::*;
+pub use oauth_introspect::*;
+pub use oauth_revoke::*;
+pub use oauth_token::*;
+pub use openid_configuration::*;
+pub use postmark_webhook::*;
 pub use signup::*;
+pub use trusted_devices::*;
+pub use userinfo::*;
 pub use verify_2fa::*;
 pub use verify_token::*;
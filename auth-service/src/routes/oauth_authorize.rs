@@ -0,0 +1,304 @@
+use askama::Template;
+use axum::{
+    extract::{Query, RawQuery, State},
+    response::{Html, IntoResponse, Redirect, Response},
+    Form,
+};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+use url::{form_urlencoded, Url};
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuthorizationCode, AuthorizationGrant, CodeChallenge, OAuthClient,
+        OAuthClientStoreError, OAuthError, Scopes,
+    },
+    utils::auth::validate_session,
+};
+
+#[tracing::instrument(name = "OAuth authorize route handler", skip_all)]
+pub async fn oauth_authorize(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    RawQuery(query): RawQuery,
+    Query(request): Query<AuthorizeRequest>,
+) -> Result<Response, AuthorizeError> {
+    let request = validate_request(&state, request).await?;
+
+    // Send the user to the login page first, returning here once signed in
+    let session = match validate_session(&jar, state.banned_token_store.clone())
+        .await
+    {
+        Ok(session) => session,
+        Err(_) => {
+            let next = format!("oauth/authorize?{}", query.unwrap_or_default());
+            let next: String =
+                form_urlencoded::byte_serialize(next.as_bytes()).collect();
+            return Ok(
+                Redirect::to(&format!("../?next={}", next)).into_response()
+            );
+        }
+    };
+
+    let template = ConsentTemplate {
+        client_name: request.client.name,
+        email: session.email.as_ref().expose_secret().to_owned(),
+        scopes: request.scopes.iter().map(str::to_owned).collect(),
+        client_id: request.client.client_id,
+        redirect_uri: request.redirect_uri,
+        scope: request.scopes.to_string(),
+        state: request.state,
+        code_challenge: request.code_challenge.as_ref().to_owned(),
+        nonce: request.nonce,
+    };
+
+    let page = template.render().map_err(|e| {
+        AuthorizeError::Direct(OAuthError::UnexpectedError(eyre!(e)))
+    })?;
+
+    Ok(Html(page).into_response())
+}
+
+#[tracing::instrument(name = "OAuth consent route handler", skip_all)]
+pub async fn oauth_authorize_consent(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Form(consent): Form<ConsentRequest>,
+) -> Result<Response, AuthorizeError> {
+    let request = validate_request(&state, consent.request).await?;
+
+    let session =
+        match validate_session(&jar, state.banned_token_store.clone()).await {
+            Ok(session) => session,
+            Err(_) => return Err(request.error(OAuthError::AccessDenied)),
+        };
+
+    if consent.decision != "allow" {
+        return Err(request.error(OAuthError::AccessDenied));
+    }
+
+    let code = AuthorizationCode::default();
+    let grant = AuthorizationGrant {
+        client_id: request.client.client_id.clone(),
+        redirect_uri: request.redirect_uri.clone(),
+        email: session.email,
+        scopes: request.scopes.clone(),
+        code_challenge: request.code_challenge.clone(),
+        nonce: request.nonce.clone(),
+        auth_time: session.auth_time,
+    };
+
+    if let Err(e) = state
+        .authorization_code_store
+        .write()
+        .await
+        .add_code(code.clone(), grant)
+        .await
+    {
+        return Err(request.error(OAuthError::UnexpectedError(eyre!(e))));
+    }
+
+    Ok(redirect_to_client(
+        &request.redirect_uri,
+        &[("code", code.as_ref().expose_secret())],
+        request.state.as_deref(),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuthorizeRequest {
+    pub response_type: Option<String>,
+    pub client_id: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    pub nonce: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConsentRequest {
+    #[serde(flatten)]
+    pub request: AuthorizeRequest,
+    pub decision: String,
+}
+
+#[derive(Template)]
+#[template(path = "consent.html")]
+struct ConsentTemplate {
+    client_name: String,
+    email: String,
+    scopes: Vec<String>,
+    client_id: String,
+    redirect_uri: String,
+    scope: String,
+    state: Option<String>,
+    code_challenge: String,
+    nonce: Option<String>,
+}
+
+struct ValidatedRequest {
+    client: OAuthClient,
+    redirect_uri: String,
+    scopes: Scopes,
+    state: Option<String>,
+    code_challenge: CodeChallenge,
+    nonce: Option<String>,
+}
+
+impl ValidatedRequest {
+    fn error(&self, error: OAuthError) -> AuthorizeError {
+        AuthorizeError::Redirect {
+            redirect_uri: self.redirect_uri.clone(),
+            state: self.state.clone(),
+            error,
+        }
+    }
+}
+
+// Errors are only sent back to the client once we know the redirect URI is
+// genuinely theirs, see RFC 6749 section 4.1.2.1
+#[derive(Debug)]
+pub enum AuthorizeError {
+    Direct(OAuthError),
+    Redirect {
+        redirect_uri: String,
+        state: Option<String>,
+        error: OAuthError,
+    },
+}
+
+impl IntoResponse for AuthorizeError {
+    fn into_response(self) -> Response {
+        match self {
+            AuthorizeError::Direct(error) => error.into_response(),
+            AuthorizeError::Redirect {
+                redirect_uri,
+                state,
+                error,
+            } => {
+                if let OAuthError::UnexpectedError(e) = &error {
+                    tracing::error!("{:?}", e);
+                }
+                redirect_to_client(
+                    &redirect_uri,
+                    &[("error", error.error_code())],
+                    state.as_deref(),
+                )
+            }
+        }
+    }
+}
+
+#[tracing::instrument(name = "Validating authorization request", skip_all)]
+async fn validate_request(
+    state: &AppState,
+    request: AuthorizeRequest,
+) -> Result<ValidatedRequest, AuthorizeError> {
+    let client_id = request.client_id.ok_or(AuthorizeError::Direct(
+        OAuthError::InvalidRequest("Missing client_id".to_owned()),
+    ))?;
+
+    let client = state
+        .oauth_client_store
+        .read()
+        .await
+        .get_client(&client_id)
+        .await
+        .map_err(|e| match e {
+            OAuthClientStoreError::UnexpectedError(e) => {
+                AuthorizeError::Direct(OAuthError::UnexpectedError(e))
+            }
+            _ => AuthorizeError::Direct(OAuthError::InvalidRequest(
+                "Unknown client_id".to_owned(),
+            )),
+        })?;
+
+    // The redirect URI may only be left out if the client registered just one
+    let redirect_uri = match request.redirect_uri {
+        Some(uri) if client.redirect_uris.contains(&uri) => uri,
+        None if client.redirect_uris.len() == 1 => {
+            client.redirect_uris[0].clone()
+        }
+        _ => {
+            return Err(AuthorizeError::Direct(OAuthError::InvalidRequest(
+                "Invalid redirect_uri".to_owned(),
+            )))
+        }
+    };
+
+    let redirect = |error| AuthorizeError::Redirect {
+        redirect_uri: redirect_uri.clone(),
+        state: request.state.clone(),
+        error,
+    };
+
+    if request.response_type.as_deref() != Some("code") {
+        return Err(redirect(OAuthError::UnsupportedResponseType));
+    }
+
+    // PKCE is required of every client, confidential or not
+    let code_challenge = match (
+        request.code_challenge.as_deref(),
+        request.code_challenge_method.as_deref(),
+    ) {
+        (Some(challenge), Some("S256")) => CodeChallenge::parse(challenge)
+            .map_err(|_| {
+                redirect(OAuthError::InvalidRequest(
+                    "Invalid code_challenge".to_owned(),
+                ))
+            })?,
+        _ => {
+            return Err(redirect(OAuthError::InvalidRequest(
+                "PKCE with code_challenge_method S256 is required".to_owned(),
+            )))
+        }
+    };
+
+    // Clients get everything they registered for unless they ask for less
+    let scopes = match request.scope.as_deref() {
+        Some(scope) => Scopes::parse(scope)
+            .map_err(|_| redirect(OAuthError::InvalidScope))?,
+        None => client.scopes.clone(),
+    };
+    if !scopes.is_subset(&client.scopes) {
+        return Err(redirect(OAuthError::InvalidScope));
+    }
+
+    Ok(ValidatedRequest {
+        client,
+        redirect_uri,
+        scopes,
+        state: request.state,
+        code_challenge,
+        nonce: request.nonce,
+    })
+}
+
+fn redirect_to_client(
+    redirect_uri: &str,
+    params: &[(&str, &str)],
+    state: Option<&str>,
+) -> Response {
+    // Redirect URIs are checked to be absolute URLs when clients register
+    let Ok(mut url) = Url::parse(redirect_uri) else {
+        return OAuthError::UnexpectedError(eyre!(
+            "registered redirect_uri is not a valid URL"
+        ))
+        .into_response();
+    };
+
+    {
+        let mut query = url.query_pairs_mut();
+        query.extend_pairs(params);
+        if let Some(state) = state {
+            query.append_pair("state", state);
+        }
+    }
+
+    Redirect::to(url.as_str()).into_response()
+}
@@ -0,0 +1,114 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use color_eyre::eyre::eyre;
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, ClientSecret, OAuthClient, Scopes},
+    utils::{auth::validate_service_token, constants::ADMIN_SCOPE},
+};
+
+// Client registration, modelled on a subset of RFC 7591. The admin service
+// token acts as the initial access token, so users can't register clients
+// with whatever scopes they like.
+#[tracing::instrument(name = "Register OAuth client route handler", skip_all)]
+pub async fn register_oauth_client(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<RegisterClientRequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    validate_service_token(
+        &headers,
+        state.banned_token_store.clone(),
+        ADMIN_SCOPE,
+    )
+    .await?;
+
+    if request.client_name.trim().is_empty()
+        || request.redirect_uris.is_empty()
+        || !request
+            .redirect_uris
+            .iter()
+            .all(|uri| is_valid_redirect_uri(uri))
+    {
+        return Err(AuthAPIError::ValidationError);
+    }
+
+    let scopes = Scopes::parse(request.scope.as_deref().unwrap_or_default())
+        .map_err(|_| AuthAPIError::ValidationError)?;
+
+    let client_secret = match request.token_endpoint_auth_method.as_deref() {
+        None | Some("client_secret_basic") | Some("client_secret_post") => {
+            Some(ClientSecret::default())
+        }
+        Some("none") => None,
+        Some(_) => return Err(AuthAPIError::ValidationError),
+    };
+
+    let client = OAuthClient::new(
+        request.client_name,
+        request.redirect_uris,
+        scopes,
+        client_secret,
+    );
+
+    let response = RegisterClientResponse {
+        client_id: client.client_id.clone(),
+        // Only the hash is kept, so this is the one chance to see the secret
+        client_secret: client
+            .client_secret
+            .as_ref()
+            .map(|secret| secret.as_ref().expose_secret().to_owned()),
+        client_name: client.name.clone(),
+        redirect_uris: client.redirect_uris.clone(),
+        scope: client.scopes.to_string(),
+        token_endpoint_auth_method: match client.is_public() {
+            true => "none".to_owned(),
+            false => "client_secret_basic".to_owned(),
+        },
+    };
+
+    state
+        .oauth_client_store
+        .write()
+        .await
+        .add_client(client)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[derive(Deserialize)]
+pub struct RegisterClientRequest {
+    pub client_name: String,
+    pub redirect_uris: Vec<String>,
+    pub scope: Option<String>,
+    pub token_endpoint_auth_method: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RegisterClientResponse {
+    pub client_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    pub client_name: String,
+    pub redirect_uris: Vec<String>,
+    pub scope: String,
+    pub token_endpoint_auth_method: String,
+}
+
+// Redirect URIs must be absolute and must not contain a fragment (RFC 6749 section 3.1.2)
+fn is_valid_redirect_uri(uri: &str) -> bool {
+    match Url::parse(uri) {
+        Ok(url) => !url.cannot_be_a_base() && url.fragment().is_none(),
+        Err(_) => false,
+    }
+}
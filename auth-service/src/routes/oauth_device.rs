@@ -0,0 +1,216 @@
+use askama::Template;
+use axum::{
+    extract::{Query, State},
+    response::{Html, IntoResponse, Redirect, Response},
+    Form,
+};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+use url::form_urlencoded;
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        DeviceCode, DeviceCodeStoreError, DeviceGrant, DeviceGrantStatus,
+        OAuthError, UserCode,
+    },
+    utils::auth::{current_timestamp, validate_session, Session},
+};
+
+// Verification page where the user approves a device by its user code, see
+// RFC 8628 section 3.3
+#[tracing::instrument(
+    name = "OAuth device verification route handler",
+    skip_all
+)]
+pub async fn oauth_device(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(request): Query<DeviceVerificationRequest>,
+) -> Result<Response, OAuthError> {
+    let session = match validate_session(&jar, state.banned_token_store.clone())
+        .await
+    {
+        Ok(session) => session,
+        Err(_) => return Ok(redirect_to_login(request.user_code.as_deref())),
+    };
+
+    let page = match request.user_code.as_deref() {
+        None => DevicePage::Entry { error: None },
+        Some(user_code) => match find_pending_grant(&state, user_code).await? {
+            Some((_, grant)) => {
+                let client_name = state
+                    .oauth_client_store
+                    .read()
+                    .await
+                    .get_client(&grant.client_id)
+                    .await
+                    .map(|client| client.name)
+                    .map_err(|e| OAuthError::UnexpectedError(eyre!(e)))?;
+
+                DevicePage::Confirm {
+                    user_code: grant.user_code.formatted(),
+                    client_name,
+                    scopes: grant.scopes.iter().map(str::to_owned).collect(),
+                }
+            }
+            None => DevicePage::invalid_code(),
+        },
+    };
+
+    render(&session, page)
+}
+
+#[tracing::instrument(name = "OAuth device approval route handler", skip_all)]
+pub async fn oauth_device_approval(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Form(request): Form<DeviceApprovalRequest>,
+) -> Result<Response, OAuthError> {
+    let session =
+        match validate_session(&jar, state.banned_token_store.clone()).await {
+            Ok(session) => session,
+            Err(_) => return Ok(redirect_to_login(Some(&request.user_code))),
+        };
+
+    let Some((code, mut grant)) =
+        find_pending_grant(&state, &request.user_code).await?
+    else {
+        return render(&session, DevicePage::invalid_code());
+    };
+
+    let message = match request.decision.as_str() {
+        "allow" => {
+            grant.status = DeviceGrantStatus::Approved {
+                email: session.email.clone(),
+                auth_time: session.auth_time,
+            };
+            "Your device is now signed in. You can close this page."
+        }
+        _ => {
+            grant.status = DeviceGrantStatus::Denied;
+            "The request was denied. Your device has not been signed in."
+        }
+    };
+
+    state
+        .device_code_store
+        .write()
+        .await
+        .update_code(&code, grant)
+        .await
+        .map_err(|e| OAuthError::UnexpectedError(eyre!(e)))?;
+
+    render(
+        &session,
+        DevicePage::Done {
+            message: message.to_owned(),
+        },
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeviceVerificationRequest {
+    pub user_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeviceApprovalRequest {
+    pub user_code: String,
+    pub decision: String,
+}
+
+enum DevicePage {
+    Entry {
+        error: Option<String>,
+    },
+    Confirm {
+        user_code: String,
+        client_name: String,
+        scopes: Vec<String>,
+    },
+    Done {
+        message: String,
+    },
+}
+
+impl DevicePage {
+    fn invalid_code() -> Self {
+        DevicePage::Entry {
+            error: Some("That code is invalid or has expired.".to_owned()),
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "device.html")]
+struct DeviceTemplate {
+    email: String,
+    page: DevicePage,
+}
+
+fn render(session: &Session, page: DevicePage) -> Result<Response, OAuthError> {
+    let template = DeviceTemplate {
+        email: session.email.as_ref().expose_secret().to_owned(),
+        page,
+    };
+    let page = template
+        .render()
+        .map_err(|e| OAuthError::UnexpectedError(eyre!(e)))?;
+
+    Ok(Html(page).into_response())
+}
+
+// Only grants still waiting for a decision can be approved or denied
+#[tracing::instrument(name = "Finding pending device grant", skip_all)]
+async fn find_pending_grant(
+    state: &AppState,
+    user_code: &str,
+) -> Result<Option<(DeviceCode, DeviceGrant)>, OAuthError> {
+    let Ok(user_code) = UserCode::parse(user_code) else {
+        return Ok(None);
+    };
+
+    let device_code_store = state.device_code_store.read().await;
+    let result = match device_code_store.find_user_code(&user_code).await {
+        Ok(code) => device_code_store
+            .get_code(&code)
+            .await
+            .map(|grant| (code, grant)),
+        Err(e) => Err(e),
+    };
+
+    let (code, grant) = match result {
+        Ok(found) => found,
+        Err(DeviceCodeStoreError::CodeNotFound) => return Ok(None),
+        Err(DeviceCodeStoreError::UnexpectedError(e)) => {
+            return Err(OAuthError::UnexpectedError(e))
+        }
+    };
+
+    let now = current_timestamp().map_err(OAuthError::UnexpectedError)?;
+    if grant.status != DeviceGrantStatus::Pending || grant.is_expired(now) {
+        return Ok(None);
+    }
+
+    Ok(Some((code, grant)))
+}
+
+// Send the user to the login page first, returning here once signed in
+fn redirect_to_login(user_code: Option<&str>) -> Response {
+    let next = match user_code {
+        Some(user_code) => {
+            let query: String = form_urlencoded::Serializer::new(String::new())
+                .append_pair("user_code", user_code)
+                .finish();
+            format!("oauth/device?{}", query)
+        }
+        None => "oauth/device".to_owned(),
+    };
+    let next: String =
+        form_urlencoded::byte_serialize(next.as_bytes()).collect();
+
+    Redirect::to(&format!("../?next={}", next)).into_response()
+}
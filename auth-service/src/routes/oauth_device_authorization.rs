@@ -0,0 +1,94 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Form, Json,
+};
+use color_eyre::eyre::eyre;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::{
+    app_state::AppState,
+    domain::{DeviceCode, DeviceGrant, OAuthError, Scopes},
+    utils::{
+        auth::current_timestamp, constants::ISSUER, oauth::authenticate_client,
+    },
+};
+
+// Device authorization request, see RFC 8628 section 3.1
+#[tracing::instrument(
+    name = "OAuth device authorization route handler",
+    skip_all
+)]
+pub async fn oauth_device_authorization(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Form(request): Form<DeviceAuthorizationRequest>,
+) -> Result<impl IntoResponse, OAuthError> {
+    let client = authenticate_client(
+        &state.oauth_client_store,
+        &headers,
+        request.client_id,
+        request.client_secret,
+    )
+    .await?;
+
+    let scopes = match request.scope.as_deref() {
+        Some(scope) => {
+            Scopes::parse(scope).map_err(|_| OAuthError::InvalidScope)?
+        }
+        None => client.scopes.clone(),
+    };
+    if !scopes.is_subset(&client.scopes) {
+        return Err(OAuthError::InvalidScope);
+    }
+
+    let now = current_timestamp().map_err(OAuthError::UnexpectedError)?;
+    let code = DeviceCode::default();
+    let grant = DeviceGrant::new(client.client_id, scopes, now);
+
+    let verification_uri =
+        format!("{}/oauth/device", ISSUER.trim_end_matches('/'));
+    let user_code = grant.user_code.formatted();
+    let query: String = form_urlencoded::Serializer::new(String::new())
+        .append_pair("user_code", &user_code)
+        .finish();
+
+    let response = DeviceAuthorizationResponse {
+        device_code: code.as_ref().expose_secret().to_owned(),
+        verification_uri_complete: format!("{}?{}", verification_uri, query),
+        verification_uri,
+        user_code,
+        expires_in: grant.expires_at - now,
+        interval: grant.interval,
+    };
+
+    state
+        .device_code_store
+        .write()
+        .await
+        .add_code(code, grant)
+        .await
+        .map_err(|e| OAuthError::UnexpectedError(eyre!(e)))?;
+
+    Ok(([(header::CACHE_CONTROL, "no-store")], Json(response)))
+}
+
+#[derive(Deserialize)]
+pub struct DeviceAuthorizationRequest {
+    pub client_id: Option<String>,
+    pub client_secret: Option<Secret<String>>,
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeviceAuthorizationResponse {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: String,
+    pub expires_in: usize,
+    pub interval: usize,
+}
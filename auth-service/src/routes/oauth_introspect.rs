@@ -0,0 +1,101 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Form, Json,
+};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::OAuthError,
+    utils::{
+        auth::{validate_token, Claims},
+        constants::VERIFY_TOKEN_SCOPE,
+        oauth::authenticate_caller,
+    },
+};
+
+// Token introspection, see RFC 7662. OAuth clients may only introspect tokens
+// issued to them; anything else is reported as inactive.
+#[tracing::instrument(name = "OAuth introspect route handler", skip_all)]
+pub async fn oauth_introspect(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Form(request): Form<IntrospectionRequest>,
+) -> Result<impl IntoResponse, OAuthError> {
+    let caller = authenticate_caller(
+        &state.oauth_client_store,
+        &state.service_account_store,
+        &headers,
+        request.client_id,
+        request.client_secret,
+    )
+    .await?;
+    let audience = caller.audience(VERIFY_TOKEN_SCOPE)?;
+
+    let token = request
+        .token
+        .ok_or(OAuthError::InvalidRequest("Missing token".to_owned()))?;
+
+    let response =
+        match validate_token(&token, state.banned_token_store.clone()).await {
+            Ok(claims)
+                if audience.is_none()
+                    || claims.client_audience() == audience =>
+            {
+                IntrospectionResponse::from(claims)
+            }
+            _ => IntrospectionResponse::default(),
+        };
+
+    Ok(([(header::CACHE_CONTROL, "no-store")], Json(response)))
+}
+
+#[derive(Deserialize)]
+pub struct IntrospectionRequest {
+    pub token: Option<Secret<String>>,
+    pub token_type_hint: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<Secret<String>>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct IntrospectionResponse {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+}
+
+impl From<Claims> for IntrospectionResponse {
+    fn from(claims: Claims) -> Self {
+        let client_id = claims
+            .client_audience()
+            .map(str::to_owned)
+            .or(claims.client_id);
+
+        Self {
+            active: true,
+            scope: claims.scope,
+            client_id,
+            token_type: Some("Bearer".to_owned()),
+            sub: Some(claims.sub),
+            exp: Some(claims.exp),
+            iat: Some(claims.iat),
+            jti: Some(claims.jti),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Form,
+};
+use color_eyre::eyre::eyre;
+use secrecy::Secret;
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{OAuthError, RefreshToken, RefreshTokenStoreError},
+    utils::{
+        auth::validate_token, constants::REVOKE_TOKEN_SCOPE,
+        oauth::authenticate_caller,
+    },
+};
+
+// Token revocation, see RFC 7009. Access tokens are banned until they expire
+// and refresh tokens are removed. Unknown tokens, and tokens issued to another
+// client, are ignored rather than reported.
+#[tracing::instrument(name = "OAuth revoke route handler", skip_all)]
+pub async fn oauth_revoke(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Form(request): Form<RevocationRequest>,
+) -> Result<impl IntoResponse, OAuthError> {
+    let caller = authenticate_caller(
+        &state.oauth_client_store,
+        &state.service_account_store,
+        &headers,
+        request.client_id,
+        request.client_secret,
+    )
+    .await?;
+    let audience = caller.audience(REVOKE_TOKEN_SCOPE)?;
+
+    let token = request
+        .token
+        .ok_or(OAuthError::InvalidRequest("Missing token".to_owned()))?;
+
+    match validate_token(&token, state.banned_token_store.clone()).await {
+        Ok(claims) => {
+            if audience.is_none() || claims.client_audience() == audience {
+                state
+                    .banned_token_store
+                    .write()
+                    .await
+                    .add_token(&token)
+                    .await
+                    .map_err(OAuthError::UnexpectedError)?;
+            }
+        }
+        Err(_) => {
+            if let Ok(refresh_token) = RefreshToken::parse(token) {
+                revoke_refresh_token(&state, refresh_token, audience).await?;
+            }
+        }
+    }
+
+    Ok(StatusCode::OK)
+}
+
+#[tracing::instrument(name = "Revoking refresh token", skip_all)]
+async fn revoke_refresh_token(
+    state: &AppState,
+    token: RefreshToken,
+    audience: Option<&str>,
+) -> Result<(), OAuthError> {
+    let mut refresh_token_store = state.refresh_token_store.write().await;
+    let grant = match refresh_token_store.get_token(&token).await {
+        Ok(grant) => grant,
+        Err(RefreshTokenStoreError::TokenNotFound) => return Ok(()),
+        Err(RefreshTokenStoreError::UnexpectedError(e)) => {
+            return Err(OAuthError::UnexpectedError(e))
+        }
+    };
+
+    // Leave tokens that belong to another client untouched
+    if audience.is_some_and(|audience| audience != grant.client_id) {
+        return Ok(());
+    }
+
+    refresh_token_store
+        .remove_token(&token)
+        .await
+        .map_err(|e| OAuthError::UnexpectedError(eyre!(e)))
+}
+
+#[derive(Deserialize)]
+pub struct RevocationRequest {
+    pub token: Option<Secret<String>>,
+    pub token_type_hint: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<Secret<String>>,
+}
@@ -0,0 +1,373 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Form, Json,
+};
+use color_eyre::eyre::eyre;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuthorizationCode, AuthorizationCodeStoreError, DeviceCode,
+        DeviceCodeStoreError, DeviceGrantStatus, OAuthClient, OAuthError,
+        RefreshGrant, RefreshToken, RefreshTokenStoreError, Scopes,
+        UserStoreError,
+    },
+    utils::{
+        auth::{
+            current_timestamp, generate_access_token, generate_service_token,
+            SERVICE_TOKEN_TTL_SECONDS,
+        },
+        constants::TOKEN_TTL_SECONDS,
+        oauth::{authenticate_client, authenticate_service_account},
+        oidc::generate_id_token,
+    },
+};
+
+#[tracing::instrument(name = "OAuth token route handler", skip_all)]
+pub async fn oauth_token(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Form(request): Form<TokenRequest>,
+) -> Result<impl IntoResponse, OAuthError> {
+    let response = match request.grant_type.as_deref() {
+        Some("authorization_code") => {
+            let client = authenticate(&state, &headers, &request).await?;
+            exchange_authorization_code(&state, &client, request).await?
+        }
+        Some("refresh_token") => {
+            let client = authenticate(&state, &headers, &request).await?;
+            exchange_refresh_token(&state, &client, request).await?
+        }
+        Some(DEVICE_CODE_GRANT_TYPE) => {
+            let client = authenticate(&state, &headers, &request).await?;
+            exchange_device_code(&state, &client, request).await?
+        }
+        Some("client_credentials") => {
+            issue_service_token(&state, &headers, request).await?
+        }
+        Some(_) => return Err(OAuthError::UnsupportedGrantType),
+        None => {
+            return Err(OAuthError::InvalidRequest(
+                "Missing grant_type".to_owned(),
+            ))
+        }
+    };
+
+    // Token responses must never be cached, see RFC 6749 section 5.1
+    Ok((
+        [
+            (header::CACHE_CONTROL, "no-store"),
+            (header::PRAGMA, "no-cache"),
+        ],
+        Json(response),
+    ))
+}
+
+pub const DEVICE_CODE_GRANT_TYPE: &str =
+    "urn:ietf:params:oauth:grant-type:device_code";
+
+#[derive(Deserialize)]
+pub struct TokenRequest {
+    pub grant_type: Option<String>,
+    pub code: Option<Secret<String>>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<Secret<String>>,
+    pub refresh_token: Option<Secret<String>>,
+    pub device_code: Option<Secret<String>>,
+    pub scope: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<Secret<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    pub scope: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+}
+
+async fn authenticate(
+    state: &AppState,
+    headers: &HeaderMap,
+    request: &TokenRequest,
+) -> Result<OAuthClient, OAuthError> {
+    authenticate_client(
+        &state.oauth_client_store,
+        headers,
+        request.client_id.clone(),
+        request.client_secret.clone(),
+    )
+    .await
+}
+
+#[tracing::instrument(name = "Exchanging authorization code", skip_all)]
+async fn exchange_authorization_code(
+    state: &AppState,
+    client: &OAuthClient,
+    request: TokenRequest,
+) -> Result<TokenResponse, OAuthError> {
+    let code = request
+        .code
+        .ok_or(OAuthError::InvalidRequest("Missing code".to_owned()))?;
+    let code =
+        AuthorizationCode::parse(code).map_err(|_| OAuthError::InvalidGrant)?;
+
+    let code_verifier = request.code_verifier.ok_or(
+        OAuthError::InvalidRequest("Missing code_verifier".to_owned()),
+    )?;
+
+    let grant = state
+        .authorization_code_store
+        .write()
+        .await
+        .take_code(&code)
+        .await
+        .map_err(|e| match e {
+            AuthorizationCodeStoreError::CodeNotFound => {
+                OAuthError::InvalidGrant
+            }
+            AuthorizationCodeStoreError::UnexpectedError(e) => {
+                OAuthError::UnexpectedError(e)
+            }
+        })?;
+
+    if grant.client_id != client.client_id
+        || request
+            .redirect_uri
+            .is_some_and(|uri| uri != grant.redirect_uri)
+        || !grant.code_challenge.verify(&code_verifier)
+    {
+        return Err(OAuthError::InvalidGrant);
+    }
+
+    let scopes = grant.scopes.clone();
+    let refresh_grant = RefreshGrant {
+        client_id: grant.client_id,
+        email: grant.email,
+        scopes: grant.scopes,
+        auth_time: grant.auth_time,
+    };
+
+    issue_tokens(state, refresh_grant, &scopes, grant.nonce).await
+}
+
+#[tracing::instrument(name = "Exchanging refresh token", skip_all)]
+async fn exchange_refresh_token(
+    state: &AppState,
+    client: &OAuthClient,
+    request: TokenRequest,
+) -> Result<TokenResponse, OAuthError> {
+    let token = request.refresh_token.ok_or(OAuthError::InvalidRequest(
+        "Missing refresh_token".to_owned(),
+    ))?;
+    let token =
+        RefreshToken::parse(token).map_err(|_| OAuthError::InvalidGrant)?;
+
+    let grant = state
+        .refresh_token_store
+        .write()
+        .await
+        .take_token(&token)
+        .await
+        .map_err(|e| match e {
+            RefreshTokenStoreError::TokenNotFound => OAuthError::InvalidGrant,
+            RefreshTokenStoreError::UnexpectedError(e) => {
+                OAuthError::UnexpectedError(e)
+            }
+        })?;
+
+    if grant.client_id != client.client_id {
+        return Err(OAuthError::InvalidGrant);
+    }
+
+    // The access token may be narrowed, but never widened beyond the original grant
+    let scopes = match request.scope.as_deref() {
+        Some(scope) => {
+            Scopes::parse(scope).map_err(|_| OAuthError::InvalidScope)?
+        }
+        None => grant.scopes.clone(),
+    };
+    if !scopes.is_subset(&grant.scopes) {
+        return Err(OAuthError::InvalidScope);
+    }
+
+    issue_tokens(state, grant, &scopes, None).await
+}
+
+// Devices poll here until the user has made a decision, see RFC 8628 section 3.4
+#[tracing::instrument(name = "Exchanging device code", skip_all)]
+async fn exchange_device_code(
+    state: &AppState,
+    client: &OAuthClient,
+    request: TokenRequest,
+) -> Result<TokenResponse, OAuthError> {
+    let code = request
+        .device_code
+        .ok_or(OAuthError::InvalidRequest("Missing device_code".to_owned()))?;
+    let code = DeviceCode::parse(code).map_err(|_| OAuthError::InvalidGrant)?;
+
+    let mut device_code_store = state.device_code_store.write().await;
+    let mut grant =
+        device_code_store
+            .get_code(&code)
+            .await
+            .map_err(|e| match e {
+                DeviceCodeStoreError::CodeNotFound => OAuthError::InvalidGrant,
+                DeviceCodeStoreError::UnexpectedError(e) => {
+                    OAuthError::UnexpectedError(e)
+                }
+            })?;
+
+    if grant.client_id != client.client_id {
+        return Err(OAuthError::InvalidGrant);
+    }
+
+    let now = current_timestamp().map_err(OAuthError::UnexpectedError)?;
+    if grant.is_expired(now) {
+        return Err(OAuthError::ExpiredToken);
+    }
+
+    let (email, auth_time) = match grant.status.clone() {
+        DeviceGrantStatus::Pending => {
+            let too_soon = grant.poll(now);
+            device_code_store
+                .update_code(&code, grant)
+                .await
+                .map_err(|e| OAuthError::UnexpectedError(eyre!(e)))?;
+            return Err(match too_soon {
+                true => OAuthError::SlowDown,
+                false => OAuthError::AuthorizationPending,
+            });
+        }
+        DeviceGrantStatus::Denied => {
+            device_code_store
+                .remove_code(&code)
+                .await
+                .map_err(|e| OAuthError::UnexpectedError(eyre!(e)))?;
+            return Err(OAuthError::AccessDenied);
+        }
+        DeviceGrantStatus::Approved { email, auth_time } => (email, auth_time),
+    };
+
+    // Device codes are single use, like authorization codes
+    device_code_store
+        .remove_code(&code)
+        .await
+        .map_err(|e| OAuthError::UnexpectedError(eyre!(e)))?;
+    drop(device_code_store);
+
+    let scopes = grant.scopes.clone();
+    let refresh_grant = RefreshGrant {
+        client_id: grant.client_id,
+        email,
+        scopes: grant.scopes,
+        auth_time,
+    };
+
+    issue_tokens(state, refresh_grant, &scopes, None).await
+}
+
+// Refresh tokens are rotated on every use and keep the scopes of the original
+// grant, while the access token gets the scopes asked for this time
+#[tracing::instrument(name = "Issuing OAuth tokens", skip_all)]
+async fn issue_tokens(
+    state: &AppState,
+    grant: RefreshGrant,
+    scopes: &Scopes,
+    nonce: Option<String>,
+) -> Result<TokenResponse, OAuthError> {
+    // Tokens are only issued while the user still exists
+    let user = match state.user_store.read().await.get_user(&grant.email).await
+    {
+        Ok(user) => user,
+        Err(UserStoreError::UserNotFound) => {
+            return Err(OAuthError::InvalidGrant)
+        }
+        Err(e) => return Err(OAuthError::UnexpectedError(eyre!(e))),
+    };
+
+    let access_token =
+        generate_access_token(&grant.email, &grant.client_id, scopes)
+            .map_err(OAuthError::UnexpectedError)?;
+
+    let id_token = match scopes.contains("openid") {
+        true => Some(
+            generate_id_token(
+                &grant.email,
+                &grant.client_id,
+                nonce,
+                grant.auth_time,
+                user.requires_2fa,
+            )
+            .map_err(OAuthError::UnexpectedError)?,
+        ),
+        false => None,
+    };
+
+    let refresh_token = RefreshToken::default();
+
+    state
+        .refresh_token_store
+        .write()
+        .await
+        .add_token(refresh_token.clone(), grant)
+        .await
+        .map_err(|e| OAuthError::UnexpectedError(eyre!(e)))?;
+
+    Ok(TokenResponse {
+        access_token: access_token.expose_secret().to_owned(),
+        token_type: "Bearer".to_owned(),
+        expires_in: *TOKEN_TTL_SECONDS,
+        refresh_token: Some(refresh_token.as_ref().expose_secret().to_owned()),
+        scope: scopes.to_string(),
+        id_token: id_token.map(|token| token.expose_secret().to_owned()),
+    })
+}
+
+// Client credentials grant (RFC 6749 section 4.4) for service accounts. No
+// refresh token is issued, see section 4.4.3.
+#[tracing::instrument(name = "Issuing service token", skip_all)]
+async fn issue_service_token(
+    state: &AppState,
+    headers: &HeaderMap,
+    request: TokenRequest,
+) -> Result<TokenResponse, OAuthError> {
+    let account = authenticate_service_account(
+        &state.service_account_store,
+        headers,
+        request.client_id,
+        request.client_secret,
+    )
+    .await?;
+
+    let scopes = match request.scope.as_deref() {
+        Some(scope) => {
+            Scopes::parse(scope).map_err(|_| OAuthError::InvalidScope)?
+        }
+        None => account.scopes.clone(),
+    };
+    if !scopes.is_subset(&account.scopes) {
+        return Err(OAuthError::InvalidScope);
+    }
+
+    let access_token = generate_service_token(&account, &scopes)
+        .map_err(OAuthError::UnexpectedError)?;
+
+    Ok(TokenResponse {
+        access_token: access_token.expose_secret().to_owned(),
+        token_type: "Bearer".to_owned(),
+        expires_in: SERVICE_TOKEN_TTL_SECONDS,
+        refresh_token: None,
+        scope: scopes.to_string(),
+        id_token: None,
+    })
+}
@@ -0,0 +1,78 @@
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+
+use super::DEVICE_CODE_GRANT_TYPE;
+use crate::utils::constants::ISSUER;
+
+// OpenID Connect discovery document, see OpenID Connect Discovery 1.0 section 3
+#[tracing::instrument(name = "OpenID configuration route handler", skip_all)]
+pub async fn openid_configuration() -> impl IntoResponse {
+    let issuer = ISSUER.trim_end_matches('/');
+
+    Json(OpenIdConfiguration {
+        issuer: issuer.to_owned(),
+        authorization_endpoint: format!("{}/oauth/authorize", issuer),
+        token_endpoint: format!("{}/oauth/token", issuer),
+        userinfo_endpoint: format!("{}/userinfo", issuer),
+        jwks_uri: format!("{}/.well-known/jwks.json", issuer),
+        registration_endpoint: format!("{}/oauth/clients", issuer),
+        device_authorization_endpoint: format!(
+            "{}/oauth/device_authorization",
+            issuer
+        ),
+        introspection_endpoint: format!("{}/oauth/introspect", issuer),
+        revocation_endpoint: format!("{}/oauth/revoke", issuer),
+        scopes_supported: to_strings(&["openid", "email"]),
+        response_types_supported: to_strings(&["code"]),
+        grant_types_supported: to_strings(&[
+            "authorization_code",
+            "refresh_token",
+            "client_credentials",
+            DEVICE_CODE_GRANT_TYPE,
+        ]),
+        subject_types_supported: to_strings(&["public"]),
+        id_token_signing_alg_values_supported: to_strings(&["ES256"]),
+        token_endpoint_auth_methods_supported: to_strings(&[
+            "client_secret_basic",
+            "client_secret_post",
+            "none",
+        ]),
+        code_challenge_methods_supported: to_strings(&["S256"]),
+        claims_supported: to_strings(&[
+            "iss",
+            "sub",
+            "aud",
+            "exp",
+            "iat",
+            "auth_time",
+            "nonce",
+            "amr",
+            "email",
+        ]),
+    })
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct OpenIdConfiguration {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+    pub jwks_uri: String,
+    pub registration_endpoint: String,
+    pub device_authorization_endpoint: String,
+    pub introspection_endpoint: String,
+    pub revocation_endpoint: String,
+    pub scopes_supported: Vec<String>,
+    pub response_types_supported: Vec<String>,
+    pub grant_types_supported: Vec<String>,
+    pub subject_types_supported: Vec<String>,
+    pub id_token_signing_alg_values_supported: Vec<String>,
+    pub token_endpoint_auth_methods_supported: Vec<String>,
+    pub code_challenge_methods_supported: Vec<String>,
+    pub claims_supported: Vec<String>,
+}
+
+fn to_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
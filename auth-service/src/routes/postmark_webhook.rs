@@ -0,0 +1,121 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use color_eyre::eyre::eyre;
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuditAction, AuditOutcome, AuthAPIError, Email, OAuthError,
+        UndeliverableEmail, UserStoreError,
+    },
+    utils::{
+        audit::audit, auth::current_timestamp, client_info::ClientInfo,
+        constants::POSTMARK_WEBHOOK_SCOPE, oauth::authenticate_service_account,
+    },
+};
+
+// Bounce types after which Postmark stops sending to the address
+const UNDELIVERABLE_BOUNCE_TYPES: [&str; 4] = [
+    "HardBounce",
+    "BadEmailAddress",
+    "ManuallyDeactivated",
+    "SpamComplaint",
+];
+
+// Bounce and spam complaint webhooks from Postmark. It authenticates as a
+// service account with the webhook scope, using HTTP Basic auth from the
+// credentials in the webhook URL
+#[tracing::instrument(name = "Postmark webhook route handler", skip_all)]
+pub async fn postmark_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    client: ClientInfo,
+    Json(event): Json<PostmarkWebhookEvent>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let account = authenticate_service_account(
+        &state.service_account_store,
+        &headers,
+        None,
+        None,
+    )
+    .await
+    .map_err(|e| match e {
+        OAuthError::UnexpectedError(e) => AuthAPIError::UnexpectedError(e),
+        _ => AuthAPIError::IncorrectCredentials,
+    })?;
+    if !account.scopes.contains(POSTMARK_WEBHOOK_SCOPE) {
+        return Err(AuthAPIError::InsufficientScope);
+    }
+
+    // Anything else, like a soft bounce, is acknowledged so Postmark doesn't
+    // retry it. So are addresses that aren't our users'
+    let Some(reason) = event.undeliverable_reason() else {
+        return Ok(StatusCode::OK);
+    };
+    let Some(email) = event
+        .email
+        .and_then(|email| Email::parse(Secret::new(email)).ok())
+    else {
+        return Ok(StatusCode::OK);
+    };
+
+    let undeliverable = UndeliverableEmail {
+        reason,
+        marked_at: current_timestamp()
+            .map_err(AuthAPIError::UnexpectedError)?,
+    };
+    let result = state
+        .user_store
+        .write()
+        .await
+        .set_email_undeliverable(&email, Some(undeliverable))
+        .await;
+    match result {
+        Ok(()) => {
+            audit(
+                &state,
+                &client,
+                AuditAction::MarkEmailUndeliverable,
+                AuditOutcome::Success,
+                Some(&account.client_id),
+                Some(email.as_ref().expose_secret()),
+            )
+            .await
+        }
+        Err(UserStoreError::UserNotFound) => (),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(eyre!(e))),
+    }
+
+    Ok(StatusCode::OK)
+}
+
+// The fields used from Postmark's bounce and spam complaint payloads. Other
+// webhooks, like deliveries, have no `Email`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PostmarkWebhookEvent {
+    pub record_type: String,
+    #[serde(rename = "Type")]
+    pub bounce_type: Option<String>,
+    pub email: Option<String>,
+}
+
+impl PostmarkWebhookEvent {
+    fn undeliverable_reason(&self) -> Option<String> {
+        match (self.record_type.as_str(), self.bounce_type.as_deref()) {
+            ("SpamComplaint", _) => Some("SpamComplaint".to_owned()),
+            ("Bounce", Some(bounce_type))
+                if UNDELIVERABLE_BOUNCE_TYPES.contains(&bounce_type) =>
+            {
+                Some(bounce_type.to_owned())
+            }
+            _ => None,
+        }
+    }
+}
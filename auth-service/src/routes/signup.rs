@@ -1,33 +1,67 @@
 use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
-use secrecy::Secret;
+use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     app_state::AppState,
-    domain::{AuthAPIError, Email, Password, User, UserStoreError},
+    domain::{
+        AuditAction, AuditOutcome, AuthAPIError, Email, Password, User,
+        UserStoreError, WebhookEventType,
+    },
+    utils::{
+        audit::audit, client_info::ClientInfo, webhooks::publish_user_event,
+    },
 };
 
 #[tracing::instrument(name = "Signup", skip_all)]
 pub async fn signup(
     State(state): State<AppState>,
+    client: ClientInfo,
     Json(request): Json<SignupRequest>,
 ) -> Result<impl IntoResponse, AuthAPIError> {
     let email = Email::parse(Secret::new(request.email))
         .map_err(|_| AuthAPIError::ValidationError)?;
+    state
+        .email_domain_policy
+        .check(&email)
+        .map_err(AuthAPIError::EmailDomainRejected)?;
     let password = Password::parse(request.password)
-        .map_err(|_| AuthAPIError::ValidationError)?;
+        .map_err(|_| AuthAPIError::ValidationError)?
+        .normalised();
+    state
+        .password_policy
+        .validate(&password, &email)
+        .map_err(AuthAPIError::PasswordPolicyViolation)?;
+    ensure_not_breached(&state, &password).await?;
 
-    let user = User::new(email, password, request.requires_2fa);
+    let user = User::new(email.clone(), password, request.requires_2fa);
 
-    {
-        let mut user_store = state.user_store.write().await;
-        user_store.add_user(user).await.map_err(|e| match e {
-            UserStoreError::UserAlreadyExists => {
-                AuthAPIError::UserAlreadyExists
-            }
-            err => AuthAPIError::UnexpectedError(err.into()),
-        })?;
-    }
+    let result = state.user_store.write().await.add_user(user).await;
+    let outcome = match result {
+        Ok(()) => AuditOutcome::Success,
+        Err(_) => AuditOutcome::Failure,
+    };
+    audit(
+        &state,
+        &client,
+        AuditAction::Signup,
+        outcome,
+        None,
+        Some(email.as_ref().expose_secret()),
+    )
+    .await;
+    result.map_err(|e| match e {
+        UserStoreError::UserAlreadyExists => AuthAPIError::UserAlreadyExists,
+        err => AuthAPIError::UnexpectedError(err.into()),
+    })?;
+
+    publish_user_event(
+        &state,
+        WebhookEventType::UserSignedUp,
+        &email,
+        Some(request.requires_2fa),
+    )
+    .await;
 
     let response = Json(SignupResponse {
         message: "User created successfully".to_string(),
@@ -36,6 +70,19 @@ pub async fn signup(
     Ok((StatusCode::CREATED, response))
 }
 
+// A failed check refuses the password, unless the checker is wrapped to let
+// it through with BREACHED_PASSWORD_CHECK_FAIL_OPEN
+pub(crate) async fn ensure_not_breached(
+    state: &AppState,
+    password: &Password,
+) -> Result<(), AuthAPIError> {
+    match state.breached_password_checker.is_breached(password).await {
+        Ok(true) => Err(AuthAPIError::BreachedPassword),
+        Ok(false) => Ok(()),
+        Err(e) => Err(AuthAPIError::BreachCheckFailed(e)),
+    }
+}
+
 #[derive(Deserialize)]
 pub struct SignupRequest {
     pub email: String,
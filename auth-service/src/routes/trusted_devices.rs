@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, TrustedDeviceStoreError},
+    utils::auth::validate_session,
+};
+
+#[tracing::instrument(name = "List trusted devices route handler", skip_all)]
+pub async fn list_trusted_devices(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let session =
+        validate_session(&jar, state.banned_token_store.clone()).await?;
+
+    let devices = state
+        .trusted_device_store
+        .read()
+        .await
+        .get_devices(&session.email)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    let response: Vec<TrustedDeviceResponse> = devices
+        .into_iter()
+        .map(|device| TrustedDeviceResponse {
+            device_id: device.device_id,
+            name: device.name,
+            created_at: device.created_at,
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[tracing::instrument(name = "Delete trusted device route handler", skip_all)]
+pub async fn delete_trusted_device(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(device_id): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let session =
+        validate_session(&jar, state.banned_token_store.clone()).await?;
+
+    // The trust cookie stays in the browser but no longer matches a device,
+    // so the next login from it asks for a 2FA code again
+    state
+        .trusted_device_store
+        .write()
+        .await
+        .delete_device(&session.email, &device_id)
+        .await
+        .map_err(|e| match e {
+            TrustedDeviceStoreError::DeviceNotFound => {
+                AuthAPIError::TrustedDeviceNotFound
+            }
+            err => AuthAPIError::UnexpectedError(eyre!(err)),
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct TrustedDeviceResponse {
+    pub device_id: String,
+    pub name: String,
+    pub created_at: usize,
+}
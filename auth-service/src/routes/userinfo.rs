@@ -0,0 +1,46 @@
+use axum::{extract::State, http::HeaderMap, response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, Scopes},
+    utils::auth::{bearer_token, validate_token},
+};
+
+// OpenID Connect UserInfo endpoint, called with an access token that was
+// granted the openid scope
+#[tracing::instrument(name = "UserInfo route handler", skip_all)]
+pub async fn userinfo(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let token = bearer_token(&headers).ok_or(AuthAPIError::MissingToken)?;
+
+    let claims = validate_token(&token, state.banned_token_store.clone())
+        .await
+        .map_err(|_| AuthAPIError::InvalidToken)?;
+
+    // Session cookies carry no scopes, so they are turned away here too
+    let scopes = Scopes::parse(&claims.scope.unwrap_or_default())
+        .map_err(|_| AuthAPIError::InvalidToken)?;
+    if claims.client_id.is_some() || !scopes.contains("openid") {
+        return Err(AuthAPIError::InvalidToken);
+    }
+
+    let email = match scopes.contains("email") {
+        true => Some(claims.sub.clone()),
+        false => None,
+    };
+
+    Ok(Json(UserInfoResponse {
+        sub: claims.sub,
+        email,
+    }))
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UserInfoResponse {
+    pub sub: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
@@ -1,13 +1,25 @@
 use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
-use axum_extra::extract::CookieJar;
+use axum_extra::extract::{cookie::Cookie, CookieJar};
 use color_eyre::eyre::eyre;
-use secrecy::Secret;
+use secrecy::{ExposeSecret, Secret};
 use serde::Deserialize;
 
 use crate::{
     app_state::AppState,
-    domain::{Email, LoginAttemptId, TwoFACode},
-    utils::auth::generate_auth_cookie,
+    domain::{
+        AuditAction, AuditOutcome, Email, LoginAttemptId, LoginMethod,
+        TrustedDevice, TwoFACode, WebhookEventType,
+    },
+    utils::{
+        audit::audit,
+        auth::{
+            current_timestamp, generate_auth_cookie,
+            generate_trusted_device_cookie,
+        },
+        client_info::ClientInfo,
+        login_history::record_login,
+        webhooks::publish_user_event,
+    },
     AuthAPIError,
 };
 
@@ -15,6 +27,7 @@ use crate::{
 pub async fn verify_2fa(
     State(state): State<AppState>,
     jar: CookieJar,
+    client: ClientInfo,
     Json(request): Json<Verify2FARequest>,
 ) -> (CookieJar, Result<impl IntoResponse, AuthAPIError>) {
     let email = match Email::parse(Secret::new(request.email)) {
@@ -36,16 +49,32 @@ pub async fn verify_2fa(
     let (expected_login_attempt_id, expected_two_fa_code) =
         match state.two_fa_code_store.read().await.get_code(&email).await {
             Ok(code_tuple) => code_tuple,
-            Err(_) => return (jar, Err(AuthAPIError::IncorrectCredentials)),
+            Err(_) => {
+                audit_verification(&state, &client, &email, false).await;
+                return (jar, Err(AuthAPIError::IncorrectCredentials));
+            }
         };
 
     if login_attempt_id != expected_login_attempt_id
         || two_fa_code != expected_two_fa_code
     {
+        audit_verification(&state, &client, &email, false).await;
+        let result =
+            record_login(&state, &email, &client, LoginMethod::TwoFA, false)
+                .await;
+        if let Err(err) = result {
+            return (jar, Err(AuthAPIError::UnexpectedError(err)));
+        }
         return (jar, Err(AuthAPIError::IncorrectCredentials));
     }
 
-    let auth_cookie = match generate_auth_cookie(&email) {
+    let auth_cookie = match generate_auth_cookie(
+        &email,
+        true,
+        &state.claims_enricher,
+    )
+    .await
+    {
         Ok(cookie) => cookie,
         Err(err) => {
             return (jar, Err(AuthAPIError::UnexpectedError(eyre!(err))))
@@ -65,10 +94,88 @@ pub async fn verify_2fa(
         }
     };
 
-    let updated_jar = jar.add(auth_cookie);
+    let result =
+        record_login(&state, &email, &client, LoginMethod::TwoFA, true).await;
+    if let Err(err) = result {
+        return (jar, Err(AuthAPIError::UnexpectedError(err)));
+    }
+    audit_verification(&state, &client, &email, true).await;
+    publish_user_event(
+        &state,
+        WebhookEventType::UserLoginVerified,
+        &email,
+        None,
+    )
+    .await;
+
+    let mut updated_jar = jar.add(auth_cookie);
+
+    if request.remember_device {
+        match trust_device(&state, &client, email).await {
+            Ok(cookie) => updated_jar = updated_jar.add(cookie),
+            Err(err) => {
+                return (updated_jar, Err(AuthAPIError::UnexpectedError(err)))
+            }
+        }
+    }
+
     (updated_jar, Ok(StatusCode::OK.into_response()))
 }
 
+async fn audit_verification(
+    state: &AppState,
+    client: &ClientInfo,
+    email: &Email,
+    success: bool,
+) {
+    // Only a correct code shows who is acting
+    let email = email.as_ref().expose_secret();
+    let (outcome, actor) = match success {
+        true => (AuditOutcome::Success, Some(email.as_str())),
+        false => (AuditOutcome::Failure, None),
+    };
+    audit(
+        state,
+        client,
+        AuditAction::Verify2FA,
+        outcome,
+        actor,
+        Some(email),
+    )
+    .await;
+}
+
+// Remember the browser, named after its user agent, so later logins from it
+// can skip 2FA
+#[tracing::instrument(name = "Trusting device", skip_all)]
+async fn trust_device(
+    state: &AppState,
+    client: &ClientInfo,
+    email: Email,
+) -> color_eyre::eyre::Result<Cookie<'static>> {
+    let name: String = client
+        .user_agent
+        .as_deref()
+        .unwrap_or("Unknown device")
+        .chars()
+        .take(MAX_DEVICE_NAME_LENGTH)
+        .collect();
+    let device = TrustedDevice::new(email, name, current_timestamp()?);
+    let cookie = generate_trusted_device_cookie(&device)?;
+
+    state
+        .trusted_device_store
+        .write()
+        .await
+        .add_device(device)
+        .await
+        .map_err(|e| eyre!(e))?;
+
+    Ok(cookie)
+}
+
+const MAX_DEVICE_NAME_LENGTH: usize = 200;
+
 #[derive(Debug, Deserialize)]
 pub struct Verify2FARequest {
     email: String,
@@ -76,4 +183,6 @@ pub struct Verify2FARequest {
     login_attempt_id: String,
     #[serde(rename = "2FACode")]
     two_fa_code: String,
+    #[serde(rename = "rememberDevice", default)]
+    remember_device: bool,
 }
@@ -1,19 +1,46 @@
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
 use secrecy::Secret;
 use serde::Deserialize;
 
-use crate::{app_state::AppState, utils::auth::validate_token, AuthAPIError};
+use crate::{
+    app_state::AppState,
+    utils::{
+        auth::{validate_service_token, validate_token},
+        constants::VERIFY_TOKEN_SCOPE,
+    },
+    AuthAPIError,
+};
 
+// Only service accounts may check tokens on behalf of users
 #[tracing::instrument(name = "Verify token route handler", skip_all)]
 pub async fn verify_token(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(request): Json<VerifyTokenRequest>,
 ) -> Result<impl IntoResponse, AuthAPIError> {
+    validate_service_token(
+        &headers,
+        state.banned_token_store.clone(),
+        VERIFY_TOKEN_SCOPE,
+    )
+    .await?;
+
     let token = Secret::new(request.token);
-    let _claims = validate_token(&token, state.banned_token_store.clone())
+    let claims = validate_token(&token, state.banned_token_store.clone())
         .await
         .map_err(|_| AuthAPIError::InvalidToken)?;
 
+    // Only browser sessions count, not tokens issued to OAuth clients or
+    // service accounts
+    if claims.client_audience().is_some() || claims.client_id.is_some() {
+        return Err(AuthAPIError::InvalidToken);
+    }
+
     Ok(StatusCode::OK.into_response())
 }
 
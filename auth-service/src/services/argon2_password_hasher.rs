@@ -0,0 +1,292 @@
+use argon2::{
+    password_hash::SaltString, Algorithm, Argon2, KeyId, Params, ParamsBuilder,
+    PasswordHash, PasswordHasher as _, PasswordVerifier, Version,
+};
+use color_eyre::eyre::{eyre, Context, Result};
+use secrecy::{ExposeSecret, Secret};
+
+use crate::{
+    domain::PasswordHasher,
+    utils::constants::{
+        DEFAULT_PASSWORD_HASH_ITERATIONS, DEFAULT_PASSWORD_HASH_MEMORY_KIB,
+        DEFAULT_PASSWORD_HASH_PARALLELISM,
+    },
+};
+
+// A secret kept out of the database and used as Argon2's secret input, so
+// leaked hashes can't be cracked without it. Hashes record its version as
+// their `keyid`, so it can be rotated
+#[derive(Debug, Clone)]
+pub struct Pepper {
+    version: u32,
+    secret: Secret<String>,
+}
+
+impl Pepper {
+    pub fn new(version: u32, secret: Secret<String>) -> Self {
+        Self { version, secret }
+    }
+
+    // From `VERSION:SECRET`, e.g. `2:4f1c...`
+    pub fn parse(pepper: &Secret<String>) -> Result<Self> {
+        let (version, secret) = pepper
+            .expose_secret()
+            .split_once(':')
+            .ok_or_else(|| eyre!("Pepper must be VERSION:SECRET"))?;
+        let version = version
+            .trim()
+            .parse()
+            .wrap_err("Pepper version must be a number")?;
+        if secret.is_empty() {
+            return Err(eyre!("Pepper secret must not be empty"));
+        }
+        Ok(Self::new(version, Secret::new(secret.to_owned())))
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn keyid(&self) -> Vec<u8> {
+        self.version.to_string().into_bytes()
+    }
+}
+
+// Makes every new hash, as Argon2id with the configured cost and the current
+// pepper. Reads any Argon2 variant, peppered with any known version or not
+// peppered at all
+#[derive(Debug, Clone)]
+pub struct Argon2PasswordHasher {
+    params: Params,
+    // The first is used for new hashes. The rest are old versions, only kept
+    // until every hash using them has been upgraded
+    peppers: Vec<Pepper>,
+}
+
+impl Argon2PasswordHasher {
+    pub fn new(params: Params, peppers: Vec<Pepper>) -> Self {
+        Self { params, peppers }
+    }
+
+    pub fn hash(&self, password: &Secret<String>) -> Result<Secret<String>> {
+        let salt = SaltString::generate(&mut rand::thread_rng());
+        let pepper = self.peppers.first();
+        let mut params = ParamsBuilder::new();
+        params
+            .m_cost(self.params.m_cost())
+            .t_cost(self.params.t_cost())
+            .p_cost(self.params.p_cost());
+        if let Some(pepper) = pepper {
+            params.keyid(KeyId::new(&pepper.keyid())?);
+        }
+
+        let password_hash = argon2(pepper, params.build()?)?
+            .hash_password(password.expose_secret().as_bytes(), &salt)?
+            .to_string();
+
+        Ok(Secret::new(password_hash))
+    }
+
+    // Whether a hash isn't Argon2id, any of its costs are lower than ours, or
+    // it isn't peppered with the current version
+    pub fn needs_rehash(&self, password_hash: &Secret<String>) -> Result<bool> {
+        let password_hash = PasswordHash::new(password_hash.expose_secret())?;
+        if password_hash.algorithm != Algorithm::Argon2id.ident()
+            || password_hash.version != Some(Version::V0x13.into())
+        {
+            return Ok(true);
+        }
+
+        let current = Params::try_from(&password_hash)?;
+        let keyid = self.peppers.first().map(Pepper::keyid).unwrap_or_default();
+        Ok(current.m_cost() < self.params.m_cost()
+            || current.t_cost() < self.params.t_cost()
+            || current.p_cost() < self.params.p_cost()
+            || current.keyid() != keyid)
+    }
+
+    // None for a hash without a pepper
+    fn pepper_for(&self, keyid: &[u8]) -> Result<Option<&Pepper>> {
+        if keyid.is_empty() {
+            return Ok(None);
+        }
+        self.peppers
+            .iter()
+            .find(|pepper| pepper.keyid() == keyid)
+            .map(Some)
+            .ok_or_else(|| eyre!("Unknown pepper version"))
+    }
+}
+
+// For hashes whose cost isn't configured, such as client secrets
+impl Default for Argon2PasswordHasher {
+    fn default() -> Self {
+        Self::new(
+            Params::new(
+                DEFAULT_PASSWORD_HASH_MEMORY_KIB,
+                DEFAULT_PASSWORD_HASH_ITERATIONS,
+                DEFAULT_PASSWORD_HASH_PARALLELISM,
+                None,
+            )
+            .expect("Default password hash params are valid"),
+            Vec::new(),
+        )
+    }
+}
+
+impl PasswordHasher for Argon2PasswordHasher {
+    fn recognises(&self, password_hash: &str) -> bool {
+        ["$argon2id$", "$argon2i$", "$argon2d$"]
+            .iter()
+            .any(|prefix| password_hash.starts_with(prefix))
+    }
+
+    fn verify(
+        &self,
+        password_hash: &Secret<String>,
+        password: &Secret<String>,
+    ) -> Result<()> {
+        let password_hash = PasswordHash::new(password_hash.expose_secret())?;
+        let pepper =
+            self.pepper_for(Params::try_from(&password_hash)?.keyid())?;
+
+        // The hash carries its own variant and costs
+        argon2(pepper, Params::default())?
+            .verify_password(
+                password.expose_secret().as_bytes(),
+                &password_hash,
+            )
+            .wrap_err("failed to verify password hash")
+    }
+}
+
+fn argon2(pepper: Option<&Pepper>, params: Params) -> Result<Argon2<'_>> {
+    Ok(match pepper {
+        Some(pepper) => Argon2::new_with_secret(
+            pepper.secret.expose_secret().as_bytes(),
+            Algorithm::Argon2id,
+            Version::V0x13,
+            params,
+        )?,
+        None => Argon2::new(Algorithm::Argon2id, Version::V0x13, params),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn password() -> Secret<String> {
+        Secret::new("password123".to_owned())
+    }
+
+    fn params() -> Params {
+        Params::new(4096, 2, 1, None).unwrap()
+    }
+
+    fn pepper(version: u32, secret: &str) -> Pepper {
+        Pepper::new(version, Secret::new(secret.to_owned()))
+    }
+
+    fn hash(memory_kib: u32, iterations: u32) -> Secret<String> {
+        Argon2PasswordHasher::new(
+            Params::new(memory_kib, iterations, 1, None).unwrap(),
+            Vec::new(),
+        )
+        .hash(&password())
+        .unwrap()
+    }
+
+    fn hasher() -> Argon2PasswordHasher {
+        Argon2PasswordHasher::new(params(), Vec::new())
+    }
+
+    #[test]
+    fn test_verifies_its_own_hashes() {
+        let password_hash = hasher().hash(&password()).unwrap();
+
+        assert!(hasher().recognises(password_hash.expose_secret()));
+        assert!(hasher().verify(&password_hash, &password()).is_ok());
+        assert!(hasher()
+            .verify(&password_hash, &Secret::new("password124".to_owned()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_needs_rehash_if_any_cost_is_lower() {
+        let hasher = hasher();
+
+        assert!(!hasher.needs_rehash(&hash(4096, 2)).unwrap());
+        assert!(!hasher.needs_rehash(&hash(8192, 3)).unwrap());
+        assert!(hasher.needs_rehash(&hash(1024, 2)).unwrap());
+        assert!(hasher.needs_rehash(&hash(8192, 1)).unwrap());
+    }
+
+    #[test]
+    fn test_needs_rehash_if_not_argon2id() {
+        let salt = SaltString::generate(&mut rand::thread_rng());
+        let argon2i_hash =
+            Argon2::new(Algorithm::Argon2i, Version::V0x13, params())
+                .hash_password(b"password123", &salt)
+                .unwrap()
+                .to_string();
+        let argon2i_hash = Secret::new(argon2i_hash);
+
+        assert!(hasher().needs_rehash(&argon2i_hash).unwrap());
+        assert!(hasher().verify(&argon2i_hash, &password()).is_ok());
+    }
+
+    #[test]
+    fn test_needs_rehash_fails_for_a_malformed_hash() {
+        assert!(hasher()
+            .needs_rehash(&Secret::new("nope".to_owned()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_peppered_hashes_need_the_pepper() {
+        let peppered =
+            Argon2PasswordHasher::new(params(), vec![pepper(1, "pepper")]);
+        let password_hash = peppered.hash(&password()).unwrap();
+
+        assert!(peppered.verify(&password_hash, &password()).is_ok());
+        assert!(!peppered.needs_rehash(&password_hash).unwrap());
+        // Unknown version
+        assert!(hasher().verify(&password_hash, &password()).is_err());
+        // Same version, different secret
+        let wrong_secret =
+            Argon2PasswordHasher::new(params(), vec![pepper(1, "salt")]);
+        assert!(wrong_secret.verify(&password_hash, &password()).is_err());
+    }
+
+    #[test]
+    fn test_rotating_the_pepper_keeps_old_hashes_until_rehashed() {
+        let old = Argon2PasswordHasher::new(params(), vec![pepper(1, "old")]);
+        let rotated = Argon2PasswordHasher::new(
+            params(),
+            vec![pepper(2, "new"), pepper(1, "old")],
+        );
+        let old_hash = old.hash(&password()).unwrap();
+        let unpeppered_hash = hasher().hash(&password()).unwrap();
+
+        for password_hash in [&old_hash, &unpeppered_hash] {
+            assert!(rotated.verify(password_hash, &password()).is_ok());
+            assert!(rotated.needs_rehash(password_hash).unwrap());
+        }
+        let new_hash = rotated.hash(&password()).unwrap();
+        assert!(!rotated.needs_rehash(&new_hash).unwrap());
+        assert!(old.verify(&new_hash, &password()).is_err());
+    }
+
+    #[test]
+    fn test_parses_versioned_peppers() {
+        let pepper =
+            Pepper::parse(&Secret::new("3:s3cr:et".to_owned())).unwrap();
+        assert_eq!(pepper.version(), 3);
+        assert_eq!(pepper.secret.expose_secret(), "s3cr:et");
+
+        for malformed in ["secret", "v3:secret", "3:"] {
+            assert!(Pepper::parse(&Secret::new(malformed.to_owned())).is_err());
+        }
+    }
+}
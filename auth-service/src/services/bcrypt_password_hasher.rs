@@ -0,0 +1,174 @@
+use argon2::password_hash::Output;
+use base64::{
+    alphabet,
+    engine::{general_purpose::NO_PAD, GeneralPurpose},
+    Engine,
+};
+use blowfish::Blowfish;
+use color_eyre::eyre::{eyre, Result};
+use secrecy::{ExposeSecret, Secret};
+
+use crate::domain::PasswordHasher;
+
+// Reads modular crypt bcrypt hashes, e.g. `$2b$12$` then a 22 character salt
+// and a 31 character hash. `$2a$` and `$2y$` only differ from `$2b$` for
+// passwords over 255 bytes, which bcrypt cuts to 72 anyway
+#[derive(Debug, Default, Clone)]
+pub struct BcryptPasswordHasher;
+
+const PREFIXES: [&str; 3] = ["$2a$", "$2b$", "$2y$"];
+
+// bcrypt's own base64 alphabet. The last character of the salt and hash carry
+// unused bits, which some implementations don't zero
+const BCRYPT_BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::BCRYPT,
+    NO_PAD.with_decode_allow_trailing_bits(true),
+);
+
+const MIN_COST: u32 = 4;
+const MAX_COST: u32 = 31;
+const MAX_PASSWORD_BYTES: usize = 72;
+
+// Encrypted 64 times to make the hash
+const MAGIC: [u32; 6] = [
+    0x4f72_7068,
+    0x6561_6e42,
+    0x6568_6f6c,
+    0x6465_7253,
+    0x6372_7944,
+    0x6f75_6274,
+];
+
+impl PasswordHasher for BcryptPasswordHasher {
+    fn recognises(&self, password_hash: &str) -> bool {
+        PREFIXES
+            .iter()
+            .any(|prefix| password_hash.starts_with(prefix))
+    }
+
+    fn verify(
+        &self,
+        password_hash: &Secret<String>,
+        password: &Secret<String>,
+    ) -> Result<()> {
+        let (cost, salt, expected) = parse(password_hash.expose_secret())?;
+
+        // The terminating NUL is part of the key
+        let mut key = password.expose_secret().as_bytes().to_vec();
+        key.push(0);
+        key.truncate(MAX_PASSWORD_BYTES);
+
+        let actual = bcrypt(cost, &salt, &key);
+        // Only 23 of the 24 bytes are kept in the hash
+        if Output::new(&actual[..23])? == Output::new(&expected)? {
+            Ok(())
+        } else {
+            Err(eyre!("Password does not match bcrypt hash"))
+        }
+    }
+}
+
+fn parse(password_hash: &str) -> Result<(u32, [u8; 16], Vec<u8>)> {
+    let malformed = || eyre!("Malformed bcrypt hash");
+    let mut parts = password_hash.split('$');
+    let (Some(""), Some(_), Some(cost), Some(salt_and_hash), None) = (
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+    ) else {
+        return Err(malformed());
+    };
+
+    let cost = cost.parse::<u32>().map_err(|_| malformed())?;
+    if !(MIN_COST..=MAX_COST).contains(&cost)
+        || salt_and_hash.len() != 53
+        || !salt_and_hash.is_ascii()
+    {
+        return Err(malformed());
+    }
+
+    let (salt, hash) = salt_and_hash.split_at(22);
+    let salt = BCRYPT_BASE64
+        .decode(salt)?
+        .try_into()
+        .map_err(|_| malformed())?;
+    let hash = BCRYPT_BASE64.decode(hash)?;
+    Ok((cost, salt, hash))
+}
+
+// Eksblowfish key setup, then encrypting the magic text with the result
+fn bcrypt(cost: u32, salt: &[u8; 16], key: &[u8]) -> [u8; 24] {
+    let mut state = Blowfish::bc_init_state();
+    state.salted_expand_key(salt, key);
+    for _ in 0..1u64 << cost {
+        state.bc_expand_key(key);
+        state.bc_expand_key(salt);
+    }
+
+    let mut text = MAGIC;
+    for block in text.chunks_exact_mut(2) {
+        for _ in 0..64 {
+            let [left, right] = state.bc_encrypt([block[0], block[1]]);
+            block[0] = left;
+            block[1] = right;
+        }
+    }
+
+    let mut output = [0u8; 24];
+    for (bytes, word) in output.chunks_exact_mut(4).zip(text) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn verify(password_hash: &str, password: &str) -> Result<()> {
+        BcryptPasswordHasher.verify(
+            &Secret::new(password_hash.to_owned()),
+            &Secret::new(password.to_owned()),
+        )
+    }
+
+    #[test]
+    fn test_verifies_known_hashes() {
+        let hashes = [
+            (
+                "$2b$04$saltsaltsaltsaltsalt1uShJHmKw.Ovu4GAyULDoQiKPbIPiYlz2",
+                "password123",
+            ),
+            (
+                "$2y$05$Bb3ZkQ0yD7cN1pLmR9sTuODHk9V6RD0fiVHezwN9c4w0aKWvherP.",
+                "correct horse",
+            ),
+        ];
+
+        for (password_hash, password) in hashes {
+            assert!(BcryptPasswordHasher.recognises(password_hash));
+            assert!(verify(password_hash, password).is_ok(), "{}", password);
+            assert!(verify(password_hash, "wrong password").is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_malformed_hashes() {
+        for password_hash in [
+            "$2b$04$saltsaltsaltsaltsalt1uShJHmKw.Ovu4GAyULDoQiKPbIPiYlz",
+            "$2b$03$saltsaltsaltsaltsalt1uShJHmKw.Ovu4GAyULDoQiKPbIPiYlz2",
+            "$2b$xx$saltsaltsaltsaltsalt1uShJHmKw.Ovu4GAyULDoQiKPbIPiYlz2",
+            "$2b$04",
+        ] {
+            assert!(verify(password_hash, "password123").is_err());
+        }
+    }
+
+    #[test]
+    fn test_does_not_recognise_other_formats() {
+        assert!(!BcryptPasswordHasher.recognises("$2x$04$abc"));
+        assert!(!BcryptPasswordHasher.recognises("$argon2id$v=19$m=4096"));
+    }
+}
@@ -0,0 +1,152 @@
+use std::time::{Duration, Instant};
+
+// Stops calling something that keeps failing. After failure_threshold
+// failures in a row the circuit opens and calls are refused for
+// open_duration. Then a single trial call is let through: success closes the
+// circuit, failure opens it again
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    open_duration: Duration,
+    state: CircuitState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CircuitState {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    // A trial call that never finishes mustn't keep the circuit shut, so
+    // another is let through once it has had open_duration
+    HalfOpen { trial_started: Instant },
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, open_duration: Duration) -> Self {
+        Self {
+            failure_threshold,
+            open_duration,
+            state: CircuitState::Closed { failures: 0 },
+        }
+    }
+
+    // Whether a call may be made now. Letting a trial call through counts as
+    // making it, so only one caller gets true while half open
+    pub fn allows(&mut self, now: Instant) -> bool {
+        match self.state {
+            CircuitState::Closed { .. } => true,
+            CircuitState::Open { until } if now < until => false,
+            CircuitState::HalfOpen { trial_started }
+                if now < trial_started + self.open_duration =>
+            {
+                false
+            }
+            CircuitState::Open { .. } | CircuitState::HalfOpen { .. } => {
+                self.state = CircuitState::HalfOpen { trial_started: now };
+                true
+            }
+        }
+    }
+
+    pub fn record_success(&mut self) {
+        self.state = CircuitState::Closed { failures: 0 };
+    }
+
+    pub fn record_failure(&mut self, now: Instant) {
+        self.state = match self.state {
+            CircuitState::Closed { failures }
+                if failures + 1 < self.failure_threshold =>
+            {
+                CircuitState::Closed {
+                    failures: failures + 1,
+                }
+            }
+            _ => CircuitState::Open {
+                until: now + self.open_duration,
+            },
+        };
+    }
+
+    pub fn state_name(&self) -> &'static str {
+        match self.state {
+            CircuitState::Closed { .. } => "closed",
+            CircuitState::Open { .. } => "open",
+            CircuitState::HalfOpen { .. } => "half_open",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPEN_DURATION: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_opens_after_threshold_failures_in_a_row() {
+        let mut breaker = CircuitBreaker::new(3, OPEN_DURATION);
+        let now = Instant::now();
+
+        breaker.record_failure(now);
+        breaker.record_failure(now);
+        assert!(breaker.allows(now));
+        assert_eq!(breaker.state_name(), "closed");
+
+        breaker.record_failure(now);
+        assert!(!breaker.allows(now));
+        assert_eq!(breaker.state_name(), "open");
+    }
+
+    #[test]
+    fn test_success_resets_the_failure_count() {
+        let mut breaker = CircuitBreaker::new(2, OPEN_DURATION);
+        let now = Instant::now();
+
+        breaker.record_failure(now);
+        breaker.record_success();
+        breaker.record_failure(now);
+
+        assert!(breaker.allows(now));
+    }
+
+    #[test]
+    fn test_lets_one_trial_call_through_after_open_duration() {
+        let mut breaker = CircuitBreaker::new(1, OPEN_DURATION);
+        let now = Instant::now();
+        breaker.record_failure(now);
+
+        let later = now + OPEN_DURATION;
+        assert!(breaker.allows(later));
+        assert_eq!(breaker.state_name(), "half_open");
+        assert!(!breaker.allows(later));
+
+        breaker.record_success();
+        assert!(breaker.allows(later));
+        assert_eq!(breaker.state_name(), "closed");
+    }
+
+    #[test]
+    fn test_reopens_if_the_trial_call_fails() {
+        let mut breaker = CircuitBreaker::new(3, OPEN_DURATION);
+        let now = Instant::now();
+        for _ in 0..3 {
+            breaker.record_failure(now);
+        }
+
+        let later = now + OPEN_DURATION;
+        assert!(breaker.allows(later));
+        breaker.record_failure(later);
+
+        assert!(!breaker.allows(later));
+        assert_eq!(breaker.state_name(), "open");
+        assert!(breaker.allows(later + OPEN_DURATION));
+    }
+
+    #[test]
+    fn test_lets_another_trial_through_if_one_never_finishes() {
+        let mut breaker = CircuitBreaker::new(1, OPEN_DURATION);
+        let now = Instant::now();
+        breaker.record_failure(now);
+        assert!(breaker.allows(now + OPEN_DURATION));
+
+        assert!(breaker.allows(now + OPEN_DURATION * 2));
+    }
+}
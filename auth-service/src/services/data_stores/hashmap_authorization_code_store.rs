@@ -0,0 +1,91 @@
+use secrecy::ExposeSecret;
+use std::collections::HashMap;
+
+use crate::domain::{
+    AuthorizationCode, AuthorizationCodeStore, AuthorizationCodeStoreError,
+    AuthorizationGrant,
+};
+
+#[derive(Default)]
+pub struct HashmapAuthorizationCodeStore {
+    codes: HashMap<String, AuthorizationGrant>,
+}
+
+#[async_trait::async_trait]
+impl AuthorizationCodeStore for HashmapAuthorizationCodeStore {
+    async fn add_code(
+        &mut self,
+        code: AuthorizationCode,
+        grant: AuthorizationGrant,
+    ) -> Result<(), AuthorizationCodeStoreError> {
+        self.codes
+            .insert(code.as_ref().expose_secret().to_owned(), grant);
+        Ok(())
+    }
+
+    async fn take_code(
+        &mut self,
+        code: &AuthorizationCode,
+    ) -> Result<AuthorizationGrant, AuthorizationCodeStoreError> {
+        self.codes
+            .remove(code.as_ref().expose_secret())
+            .ok_or(AuthorizationCodeStoreError::CodeNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CodeChallenge, Email, Scopes};
+    use secrecy::Secret;
+
+    fn get_test_grant() -> AuthorizationGrant {
+        AuthorizationGrant {
+            client_id: "client".to_owned(),
+            redirect_uri: "http://localhost:8000/callback".to_owned(),
+            email: Email::parse(Secret::new("foo@bar.com".to_owned())).unwrap(),
+            scopes: Scopes::parse("read").unwrap(),
+            code_challenge: CodeChallenge::parse(
+                "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM",
+            )
+            .unwrap(),
+            nonce: Some("nonce".to_owned()),
+            auth_time: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn take_code() {
+        let mut store = HashmapAuthorizationCodeStore::default();
+        let code = AuthorizationCode::default();
+        let grant = get_test_grant();
+
+        assert_eq!(store.add_code(code.clone(), grant.clone()).await, Ok(()));
+        assert_eq!(store.take_code(&code).await, Ok(grant));
+    }
+
+    #[tokio::test]
+    async fn code_can_only_be_taken_once() {
+        let mut store = HashmapAuthorizationCodeStore::default();
+        let code = AuthorizationCode::default();
+        store
+            .add_code(code.clone(), get_test_grant())
+            .await
+            .unwrap();
+
+        assert!(store.take_code(&code).await.is_ok());
+        assert_eq!(
+            store.take_code(&code).await,
+            Err(AuthorizationCodeStoreError::CodeNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn take_non_existent_code_returns_error() {
+        let mut store = HashmapAuthorizationCodeStore::default();
+        assert_eq!(
+            store.take_code(&AuthorizationCode::default()).await,
+            Err(AuthorizationCodeStoreError::CodeNotFound)
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use secrecy::ExposeSecret;
+
+use crate::domain::{
+    DeviceCode, DeviceCodeStore, DeviceCodeStoreError, DeviceGrant, UserCode,
+};
+
+#[derive(Default)]
+pub struct HashmapDeviceCodeStore {
+    codes: HashMap<String, (DeviceCode, DeviceGrant)>,
+}
+
+#[async_trait::async_trait]
+impl DeviceCodeStore for HashmapDeviceCodeStore {
+    async fn add_code(
+        &mut self,
+        code: DeviceCode,
+        grant: DeviceGrant,
+    ) -> Result<(), DeviceCodeStoreError> {
+        self.codes
+            .insert(code.as_ref().expose_secret().to_owned(), (code, grant));
+        Ok(())
+    }
+
+    async fn get_code(
+        &self,
+        code: &DeviceCode,
+    ) -> Result<DeviceGrant, DeviceCodeStoreError> {
+        match self.codes.get(code.as_ref().expose_secret()) {
+            Some((_, grant)) => Ok(grant.clone()),
+            None => Err(DeviceCodeStoreError::CodeNotFound),
+        }
+    }
+
+    async fn update_code(
+        &mut self,
+        code: &DeviceCode,
+        grant: DeviceGrant,
+    ) -> Result<(), DeviceCodeStoreError> {
+        match self.codes.get_mut(code.as_ref().expose_secret()) {
+            Some((_, stored)) => {
+                *stored = grant;
+                Ok(())
+            }
+            None => Err(DeviceCodeStoreError::CodeNotFound),
+        }
+    }
+
+    async fn remove_code(
+        &mut self,
+        code: &DeviceCode,
+    ) -> Result<(), DeviceCodeStoreError> {
+        self.codes.remove(code.as_ref().expose_secret());
+        Ok(())
+    }
+
+    async fn find_user_code(
+        &self,
+        user_code: &UserCode,
+    ) -> Result<DeviceCode, DeviceCodeStoreError> {
+        self.codes
+            .values()
+            .find(|(_, grant)| &grant.user_code == user_code)
+            .map(|(code, _)| code.clone())
+            .ok_or(DeviceCodeStoreError::CodeNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{DeviceGrantStatus, Email, Scopes};
+    use secrecy::Secret;
+
+    fn get_test_grant() -> DeviceGrant {
+        DeviceGrant::new(
+            "client".to_owned(),
+            Scopes::parse("read").unwrap(),
+            1_700_000_000,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_add_and_get_code() {
+        let mut store = HashmapDeviceCodeStore::default();
+        let code = DeviceCode::default();
+        let grant = get_test_grant();
+
+        store.add_code(code.clone(), grant.clone()).await.unwrap();
+        assert_eq!(store.get_code(&code).await, Ok(grant));
+        assert_eq!(
+            store.get_code(&DeviceCode::default()).await,
+            Err(DeviceCodeStoreError::CodeNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_user_code() {
+        let mut store = HashmapDeviceCodeStore::default();
+        let code = DeviceCode::default();
+        let grant = get_test_grant();
+
+        store.add_code(code.clone(), grant.clone()).await.unwrap();
+        assert_eq!(store.find_user_code(&grant.user_code).await, Ok(code));
+        assert_eq!(
+            store
+                .find_user_code(&UserCode::parse("BCDF-GHJK").unwrap())
+                .await,
+            Err(DeviceCodeStoreError::CodeNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_and_remove_code() {
+        let mut store = HashmapDeviceCodeStore::default();
+        let code = DeviceCode::default();
+        let mut grant = get_test_grant();
+        store.add_code(code.clone(), grant.clone()).await.unwrap();
+
+        grant.status = DeviceGrantStatus::Approved {
+            email: Email::parse(Secret::new("test@example.com".to_owned()))
+                .unwrap(),
+            auth_time: 1_700_000_000,
+        };
+        store.update_code(&code, grant.clone()).await.unwrap();
+        assert_eq!(store.get_code(&code).await, Ok(grant.clone()));
+
+        store.remove_code(&code).await.unwrap();
+        assert_eq!(
+            store.update_code(&code, grant).await,
+            Err(DeviceCodeStoreError::CodeNotFound)
+        );
+    }
+}
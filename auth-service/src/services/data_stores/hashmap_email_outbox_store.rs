@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use crate::domain::{EmailOutboxStore, EmailOutboxStoreError, OutboxEmail};
+
+#[derive(Default)]
+pub struct HashmapEmailOutboxStore {
+    emails: HashMap<String, OutboxEmail>,
+    failed: Vec<OutboxEmail>,
+}
+
+#[async_trait::async_trait]
+impl EmailOutboxStore for HashmapEmailOutboxStore {
+    async fn add_email(
+        &mut self,
+        email: OutboxEmail,
+    ) -> Result<(), EmailOutboxStoreError> {
+        self.emails.insert(email.id.clone(), email);
+        Ok(())
+    }
+
+    async fn claim_due_emails(
+        &mut self,
+        now: u64,
+        claimed_until: u64,
+        limit: usize,
+    ) -> Result<Vec<OutboxEmail>, EmailOutboxStoreError> {
+        let mut emails: Vec<&mut OutboxEmail> = self
+            .emails
+            .values_mut()
+            .filter(|email| email.next_attempt_at <= now)
+            .collect();
+        emails.sort_by_key(|email| email.next_attempt_at);
+        emails.truncate(limit);
+        Ok(emails
+            .into_iter()
+            .map(|email| {
+                email.next_attempt_at = claimed_until;
+                email.clone()
+            })
+            .collect())
+    }
+
+    async fn update_email(
+        &mut self,
+        email: &OutboxEmail,
+    ) -> Result<(), EmailOutboxStoreError> {
+        match self.emails.get_mut(&email.id) {
+            Some(stored) => {
+                *stored = email.clone();
+                Ok(())
+            }
+            None => Err(EmailOutboxStoreError::EmailNotFound),
+        }
+    }
+
+    async fn remove_email(
+        &mut self,
+        id: &str,
+    ) -> Result<(), EmailOutboxStoreError> {
+        match self.emails.remove(id) {
+            Some(_) => Ok(()),
+            None => Err(EmailOutboxStoreError::EmailNotFound),
+        }
+    }
+
+    async fn mark_failed(
+        &mut self,
+        email: &OutboxEmail,
+        failed_at: u64,
+    ) -> Result<(), EmailOutboxStoreError> {
+        self.remove_email(&email.id).await?;
+        self.failed.push(OutboxEmail {
+            next_attempt_at: failed_at,
+            ..email.clone()
+        });
+        Ok(())
+    }
+
+    async fn get_failed_emails(
+        &self,
+    ) -> Result<Vec<OutboxEmail>, EmailOutboxStoreError> {
+        Ok(self.failed.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+    use crate::domain::Email;
+
+    fn get_test_email(next_attempt_at: u64) -> OutboxEmail {
+        OutboxEmail::new(
+            Email::parse(Secret::new("test@example.com".to_owned())).unwrap(),
+            "Subject".to_owned(),
+            "<p>Content</p>".to_owned(),
+            "Content".to_owned(),
+            next_attempt_at,
+        )
+    }
+
+    fn claimed(email: &OutboxEmail, claimed_until: u64) -> OutboxEmail {
+        OutboxEmail {
+            next_attempt_at: claimed_until,
+            ..email.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_claim_due_emails() {
+        let mut store = HashmapEmailOutboxStore::default();
+        let first = get_test_email(1);
+        let second = get_test_email(2);
+        let later = get_test_email(10);
+
+        store.add_email(second.clone()).await.unwrap();
+        store.add_email(later).await.unwrap();
+        store.add_email(first.clone()).await.unwrap();
+
+        assert_eq!(
+            store.claim_due_emails(5, 20, 1).await,
+            Ok(vec![claimed(&first, 20)])
+        );
+        assert_eq!(
+            store.claim_due_emails(5, 20, 10).await,
+            Ok(vec![claimed(&second, 20)])
+        );
+        // Claimed emails are due again once the claim runs out
+        assert_eq!(store.claim_due_emails(5, 20, 10).await, Ok(vec![]));
+        assert_eq!(store.claim_due_emails(20, 30, 10).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_update_and_remove_email() {
+        let mut store = HashmapEmailOutboxStore::default();
+        let mut email = get_test_email(1);
+        store.add_email(email.clone()).await.unwrap();
+
+        email.attempts = 1;
+        email.next_attempt_at = 10;
+        assert_eq!(store.update_email(&email).await, Ok(()));
+        assert_eq!(store.claim_due_emails(5, 20, 10).await, Ok(vec![]));
+
+        assert_eq!(store.remove_email(&email.id).await, Ok(()));
+        assert_eq!(
+            store.update_email(&email).await,
+            Err(EmailOutboxStoreError::EmailNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mark_failed() {
+        let mut store = HashmapEmailOutboxStore::default();
+        let mut email = get_test_email(1);
+        store.add_email(email.clone()).await.unwrap();
+
+        assert_eq!(store.mark_failed(&email, 3).await, Ok(()));
+        assert_eq!(store.claim_due_emails(5, 20, 10).await, Ok(vec![]));
+        email.next_attempt_at = 3;
+        assert_eq!(store.get_failed_emails().await, Ok(vec![email]));
+    }
+}
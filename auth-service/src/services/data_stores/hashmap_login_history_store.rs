@@ -0,0 +1,239 @@
+use color_eyre::eyre::eyre;
+
+use crate::{
+    app_state::EmailOutboxStoreType,
+    domain::{
+        Email, LoginEvent, LoginHistoryStore, LoginHistoryStoreError,
+        OutboxEmail,
+    },
+};
+
+pub struct HashmapLoginHistoryStore {
+    events: Vec<LoginEvent>,
+    outbox_store: EmailOutboxStoreType,
+}
+
+impl HashmapLoginHistoryStore {
+    pub fn new(outbox_store: EmailOutboxStoreType) -> Self {
+        Self {
+            events: Vec::new(),
+            outbox_store,
+        }
+    }
+
+    fn successful_logins<'a>(
+        &'a self,
+        email: &'a Email,
+    ) -> impl Iterator<Item = &'a LoginEvent> {
+        self.events
+            .iter()
+            .filter(move |event| &event.email == email && event.success)
+    }
+}
+
+#[async_trait::async_trait]
+impl LoginHistoryStore for HashmapLoginHistoryStore {
+    async fn add_event(
+        &mut self,
+        event: LoginEvent,
+        notice: Option<OutboxEmail>,
+    ) -> Result<(), LoginHistoryStoreError> {
+        if let Some(notice) = notice {
+            self.outbox_store
+                .write()
+                .await
+                .add_email(notice)
+                .await
+                .map_err(|e| {
+                    LoginHistoryStoreError::UnexpectedError(eyre!(e))
+                })?;
+        }
+        self.events.push(event);
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        email: &Email,
+        limit: usize,
+    ) -> Result<Vec<LoginEvent>, LoginHistoryStoreError> {
+        let mut events: Vec<LoginEvent> = self
+            .events
+            .iter()
+            .rev()
+            .filter(|event| &event.email == email)
+            .cloned()
+            .collect();
+        // Stable, so events with the same timestamp stay newest first
+        events.sort_by_key(|event| std::cmp::Reverse(event.timestamp));
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    async fn is_new_device(
+        &self,
+        email: &Email,
+        ip: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<bool, LoginHistoryStoreError> {
+        if self.successful_logins(email).next().is_none() {
+            return Ok(false);
+        }
+
+        let seen_ip = self
+            .successful_logins(email)
+            .any(|event| event.ip.as_deref() == ip);
+        let seen_user_agent = self
+            .successful_logins(email)
+            .any(|event| event.user_agent.as_deref() == user_agent);
+
+        Ok(!seen_ip || !seen_user_agent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{EmailOutboxStore, LoginMethod},
+        services::data_stores::HashmapEmailOutboxStore,
+    };
+    use secrecy::Secret;
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    fn get_test_store() -> HashmapLoginHistoryStore {
+        HashmapLoginHistoryStore::new(Arc::new(RwLock::new(
+            HashmapEmailOutboxStore::default(),
+        )))
+    }
+
+    fn get_test_email(email: &str) -> Email {
+        Email::parse(Secret::new(email.to_owned())).unwrap()
+    }
+
+    fn get_test_event(
+        email: &Email,
+        timestamp: usize,
+        ip: &str,
+        user_agent: &str,
+        success: bool,
+    ) -> LoginEvent {
+        LoginEvent::new(
+            email.clone(),
+            timestamp,
+            Some(ip.to_owned()),
+            Some(user_agent.to_owned()),
+            LoginMethod::Password,
+            success,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_get_events() {
+        let mut store = get_test_store();
+        let email = get_test_email("test@example.com");
+        let first = get_test_event(&email, 1, "10.0.0.1", "Firefox", false);
+        let second = get_test_event(&email, 2, "10.0.0.1", "Firefox", true);
+        let third = get_test_event(&email, 2, "10.0.0.2", "Safari", true);
+        let other = get_test_event(
+            &get_test_email("other@example.com"),
+            3,
+            "10.0.0.1",
+            "Firefox",
+            true,
+        );
+
+        store.add_event(first.clone(), None).await.unwrap();
+        store.add_event(second.clone(), None).await.unwrap();
+        store.add_event(third.clone(), None).await.unwrap();
+        store.add_event(other, None).await.unwrap();
+
+        assert_eq!(
+            store.get_events(&email, 10).await,
+            Ok(vec![third.clone(), second, first])
+        );
+        assert_eq!(store.get_events(&email, 1).await, Ok(vec![third]));
+    }
+
+    #[tokio::test]
+    async fn test_is_new_device() {
+        let mut store = get_test_store();
+        let email = get_test_email("test@example.com");
+
+        // Nothing to compare the first login against
+        assert_eq!(
+            store
+                .is_new_device(&email, Some("10.0.0.1"), Some("Firefox"))
+                .await,
+            Ok(false)
+        );
+
+        store
+            .add_event(
+                get_test_event(&email, 1, "10.0.0.9", "Chrome", false),
+                None,
+            )
+            .await
+            .unwrap();
+        store
+            .add_event(
+                get_test_event(&email, 2, "10.0.0.1", "Firefox", true),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store
+                .is_new_device(&email, Some("10.0.0.1"), Some("Firefox"))
+                .await,
+            Ok(false)
+        );
+        assert_eq!(
+            store
+                .is_new_device(&email, Some("10.0.0.2"), Some("Firefox"))
+                .await,
+            Ok(true)
+        );
+        // Failed logins don't make a device known
+        assert_eq!(
+            store
+                .is_new_device(&email, Some("10.0.0.1"), Some("Chrome"))
+                .await,
+            Ok(true)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_event_queues_notice() {
+        let outbox_store =
+            Arc::new(RwLock::new(HashmapEmailOutboxStore::default()));
+        let mut store = HashmapLoginHistoryStore::new(outbox_store.clone());
+        let email = get_test_email("test@example.com");
+        let notice = OutboxEmail::new(
+            email.clone(),
+            "New sign-in".to_owned(),
+            "<p>New sign-in</p>".to_owned(),
+            "New sign-in".to_owned(),
+            0,
+        );
+
+        store
+            .add_event(
+                get_test_event(&email, 1, "10.0.0.1", "Firefox", true),
+                Some(notice.clone()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            outbox_store.read().await.get_failed_emails().await,
+            Ok(vec![])
+        );
+        assert_eq!(
+            outbox_store.write().await.claim_due_emails(0, 0, 10).await,
+            Ok(vec![notice])
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    ClientSecret, OAuthClient, OAuthClientStore, OAuthClientStoreError,
+};
+
+#[derive(Default)]
+pub struct HashmapOAuthClientStore {
+    clients: HashMap<String, OAuthClient>,
+}
+
+#[async_trait::async_trait]
+impl OAuthClientStore for HashmapOAuthClientStore {
+    async fn add_client(
+        &mut self,
+        client: OAuthClient,
+    ) -> Result<(), OAuthClientStoreError> {
+        if self.clients.contains_key(&client.client_id) {
+            return Err(OAuthClientStoreError::ClientAlreadyExists);
+        }
+
+        self.clients.insert(client.client_id.clone(), client);
+        Ok(())
+    }
+
+    async fn get_client(
+        &self,
+        client_id: &str,
+    ) -> Result<OAuthClient, OAuthClientStoreError> {
+        match self.clients.get(client_id) {
+            Some(client) => Ok(client.clone()),
+            None => Err(OAuthClientStoreError::ClientNotFound),
+        }
+    }
+
+    async fn validate_client(
+        &self,
+        client_id: &str,
+        client_secret: Option<&ClientSecret>,
+    ) -> Result<OAuthClient, OAuthClientStoreError> {
+        let client = self.get_client(client_id).await?;
+        if client.client_secret.as_ref() == client_secret {
+            Ok(client)
+        } else {
+            Err(OAuthClientStoreError::InvalidCredentials)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Scopes;
+
+    fn get_test_client(client_secret: Option<ClientSecret>) -> OAuthClient {
+        OAuthClient::new(
+            "Test client".to_owned(),
+            vec!["http://localhost:8000/callback".to_owned()],
+            Scopes::parse("read write").unwrap(),
+            client_secret,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_add_client() {
+        let mut store = HashmapOAuthClientStore::default();
+        let client = get_test_client(None);
+
+        assert_eq!(store.add_client(client.clone()).await, Ok(()));
+        assert_eq!(
+            store.add_client(client).await,
+            Err(OAuthClientStoreError::ClientAlreadyExists)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_client() {
+        let mut store = HashmapOAuthClientStore::default();
+        let client = get_test_client(None);
+        store.add_client(client.clone()).await.unwrap();
+
+        let retrieved = store.get_client(&client.client_id).await.unwrap();
+        assert_eq!(retrieved.name, client.name);
+        assert_eq!(
+            store.get_client("unknown").await.unwrap_err(),
+            OAuthClientStoreError::ClientNotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_confidential_client() {
+        let mut store = HashmapOAuthClientStore::default();
+        let secret = ClientSecret::default();
+        let client = get_test_client(Some(secret.clone()));
+        store.add_client(client.clone()).await.unwrap();
+
+        assert!(store
+            .validate_client(&client.client_id, Some(&secret))
+            .await
+            .is_ok());
+        assert_eq!(
+            store
+                .validate_client(
+                    &client.client_id,
+                    Some(&ClientSecret::default())
+                )
+                .await
+                .unwrap_err(),
+            OAuthClientStoreError::InvalidCredentials
+        );
+        assert_eq!(
+            store
+                .validate_client(&client.client_id, None)
+                .await
+                .unwrap_err(),
+            OAuthClientStoreError::InvalidCredentials
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_public_client() {
+        let mut store = HashmapOAuthClientStore::default();
+        let client = get_test_client(None);
+        store.add_client(client.clone()).await.unwrap();
+
+        assert!(store.validate_client(&client.client_id, None).await.is_ok());
+        assert_eq!(
+            store
+                .validate_client(
+                    &client.client_id,
+                    Some(&ClientSecret::default())
+                )
+                .await
+                .unwrap_err(),
+            OAuthClientStoreError::InvalidCredentials
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use secrecy::ExposeSecret;
+use std::collections::HashMap;
+
+use crate::domain::{
+    RefreshGrant, RefreshToken, RefreshTokenStore, RefreshTokenStoreError,
+};
+
+#[derive(Default)]
+pub struct HashmapRefreshTokenStore {
+    tokens: HashMap<String, RefreshGrant>,
+}
+
+#[async_trait::async_trait]
+impl RefreshTokenStore for HashmapRefreshTokenStore {
+    async fn add_token(
+        &mut self,
+        token: RefreshToken,
+        grant: RefreshGrant,
+    ) -> Result<(), RefreshTokenStoreError> {
+        self.tokens
+            .insert(token.as_ref().expose_secret().to_owned(), grant);
+        Ok(())
+    }
+
+    async fn take_token(
+        &mut self,
+        token: &RefreshToken,
+    ) -> Result<RefreshGrant, RefreshTokenStoreError> {
+        self.tokens
+            .remove(token.as_ref().expose_secret())
+            .ok_or(RefreshTokenStoreError::TokenNotFound)
+    }
+
+    async fn get_token(
+        &self,
+        token: &RefreshToken,
+    ) -> Result<RefreshGrant, RefreshTokenStoreError> {
+        self.tokens
+            .get(token.as_ref().expose_secret())
+            .cloned()
+            .ok_or(RefreshTokenStoreError::TokenNotFound)
+    }
+
+    async fn remove_token(
+        &mut self,
+        token: &RefreshToken,
+    ) -> Result<(), RefreshTokenStoreError> {
+        self.tokens.remove(token.as_ref().expose_secret());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Email, Scopes};
+    use secrecy::Secret;
+
+    fn get_test_grant() -> RefreshGrant {
+        RefreshGrant {
+            client_id: "client".to_owned(),
+            email: Email::parse(Secret::new("foo@bar.com".to_owned())).unwrap(),
+            scopes: Scopes::parse("read write").unwrap(),
+            auth_time: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn take_token() {
+        let mut store = HashmapRefreshTokenStore::default();
+        let token = RefreshToken::default();
+        let grant = get_test_grant();
+
+        assert_eq!(store.add_token(token.clone(), grant.clone()).await, Ok(()));
+        assert_eq!(store.take_token(&token).await, Ok(grant));
+    }
+
+    #[tokio::test]
+    async fn token_can_only_be_taken_once() {
+        let mut store = HashmapRefreshTokenStore::default();
+        let token = RefreshToken::default();
+        store
+            .add_token(token.clone(), get_test_grant())
+            .await
+            .unwrap();
+
+        assert!(store.take_token(&token).await.is_ok());
+        assert_eq!(
+            store.take_token(&token).await,
+            Err(RefreshTokenStoreError::TokenNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_token_leaves_token_in_store() {
+        let mut store = HashmapRefreshTokenStore::default();
+        let token = RefreshToken::default();
+        let grant = get_test_grant();
+        store.add_token(token.clone(), grant.clone()).await.unwrap();
+
+        assert_eq!(store.get_token(&token).await, Ok(grant.clone()));
+        assert_eq!(store.take_token(&token).await, Ok(grant));
+    }
+
+    #[tokio::test]
+    async fn remove_token() {
+        let mut store = HashmapRefreshTokenStore::default();
+        let token = RefreshToken::default();
+        store
+            .add_token(token.clone(), get_test_grant())
+            .await
+            .unwrap();
+
+        assert_eq!(store.remove_token(&token).await, Ok(()));
+        assert_eq!(
+            store.get_token(&token).await,
+            Err(RefreshTokenStoreError::TokenNotFound)
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    ClientSecret, ServiceAccount, ServiceAccountStore, ServiceAccountStoreError,
+};
+
+#[derive(Default)]
+pub struct HashmapServiceAccountStore {
+    accounts: HashMap<String, ServiceAccount>,
+}
+
+#[async_trait::async_trait]
+impl ServiceAccountStore for HashmapServiceAccountStore {
+    async fn add_account(
+        &mut self,
+        account: ServiceAccount,
+    ) -> Result<(), ServiceAccountStoreError> {
+        if self.accounts.contains_key(&account.client_id) {
+            return Err(ServiceAccountStoreError::AccountAlreadyExists);
+        }
+
+        self.accounts.insert(account.client_id.clone(), account);
+        Ok(())
+    }
+
+    async fn get_accounts(
+        &self,
+    ) -> Result<Vec<ServiceAccount>, ServiceAccountStoreError> {
+        Ok(self.accounts.values().cloned().collect())
+    }
+
+    async fn validate_account(
+        &self,
+        client_id: &str,
+        client_secret: &ClientSecret,
+    ) -> Result<ServiceAccount, ServiceAccountStoreError> {
+        match self.accounts.get(client_id) {
+            Some(account) if &account.client_secret == client_secret => {
+                Ok(account.clone())
+            }
+            Some(_) => Err(ServiceAccountStoreError::InvalidCredentials),
+            None => Err(ServiceAccountStoreError::AccountNotFound),
+        }
+    }
+
+    async fn delete_account(
+        &mut self,
+        client_id: &str,
+    ) -> Result<(), ServiceAccountStoreError> {
+        match self.accounts.remove(client_id) {
+            Some(_) => Ok(()),
+            None => Err(ServiceAccountStoreError::AccountNotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Scopes;
+
+    fn get_test_account(client_secret: ClientSecret) -> ServiceAccount {
+        ServiceAccount::new(
+            "Test service".to_owned(),
+            Scopes::parse("tokens:verify").unwrap(),
+            client_secret,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_add_account() {
+        let mut store = HashmapServiceAccountStore::default();
+        let account = get_test_account(ClientSecret::default());
+
+        assert_eq!(store.add_account(account.clone()).await, Ok(()));
+        assert_eq!(
+            store.add_account(account).await,
+            Err(ServiceAccountStoreError::AccountAlreadyExists)
+        );
+        assert_eq!(store.get_accounts().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_validate_account() {
+        let mut store = HashmapServiceAccountStore::default();
+        let secret = ClientSecret::default();
+        let account = get_test_account(secret.clone());
+        store.add_account(account.clone()).await.unwrap();
+
+        let validated = store
+            .validate_account(&account.client_id, &secret)
+            .await
+            .unwrap();
+        assert_eq!(validated.scopes, account.scopes);
+        assert_eq!(
+            store
+                .validate_account(&account.client_id, &ClientSecret::default())
+                .await
+                .unwrap_err(),
+            ServiceAccountStoreError::InvalidCredentials
+        );
+        assert_eq!(
+            store
+                .validate_account("unknown", &secret)
+                .await
+                .unwrap_err(),
+            ServiceAccountStoreError::AccountNotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_account() {
+        let mut store = HashmapServiceAccountStore::default();
+        let secret = ClientSecret::default();
+        let account = get_test_account(secret.clone());
+        store.add_account(account.clone()).await.unwrap();
+
+        assert_eq!(store.delete_account(&account.client_id).await, Ok(()));
+        assert_eq!(
+            store.delete_account(&account.client_id).await,
+            Err(ServiceAccountStoreError::AccountNotFound)
+        );
+        assert_eq!(
+            store
+                .validate_account(&account.client_id, &secret)
+                .await
+                .unwrap_err(),
+            ServiceAccountStoreError::AccountNotFound
+        );
+    }
+}
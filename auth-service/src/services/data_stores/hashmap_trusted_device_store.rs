@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    Email, TrustedDevice, TrustedDeviceStore, TrustedDeviceStoreError,
+};
+
+#[derive(Default)]
+pub struct HashmapTrustedDeviceStore {
+    devices: HashMap<String, TrustedDevice>,
+}
+
+#[async_trait::async_trait]
+impl TrustedDeviceStore for HashmapTrustedDeviceStore {
+    async fn add_device(
+        &mut self,
+        device: TrustedDevice,
+    ) -> Result<(), TrustedDeviceStoreError> {
+        self.devices.insert(device.device_id.clone(), device);
+        Ok(())
+    }
+
+    async fn get_device(
+        &self,
+        email: &Email,
+        device_id: &str,
+    ) -> Result<TrustedDevice, TrustedDeviceStoreError> {
+        match self.devices.get(device_id) {
+            Some(device) if &device.email == email => Ok(device.clone()),
+            _ => Err(TrustedDeviceStoreError::DeviceNotFound),
+        }
+    }
+
+    async fn get_devices(
+        &self,
+        email: &Email,
+    ) -> Result<Vec<TrustedDevice>, TrustedDeviceStoreError> {
+        let mut devices: Vec<TrustedDevice> = self
+            .devices
+            .values()
+            .filter(|device| &device.email == email)
+            .cloned()
+            .collect();
+        devices.sort_by_key(|device| device.created_at);
+        Ok(devices)
+    }
+
+    async fn delete_device(
+        &mut self,
+        email: &Email,
+        device_id: &str,
+    ) -> Result<(), TrustedDeviceStoreError> {
+        self.get_device(email, device_id).await?;
+        self.devices.remove(device_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::Secret;
+
+    fn get_test_email(email: &str) -> Email {
+        Email::parse(Secret::new(email.to_owned())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_add_and_get_device() {
+        let mut store = HashmapTrustedDeviceStore::default();
+        let email = get_test_email("test@example.com");
+        let device = TrustedDevice::new(email.clone(), "Firefox".to_owned(), 1);
+
+        store.add_device(device.clone()).await.unwrap();
+        assert_eq!(
+            store.get_device(&email, &device.device_id).await,
+            Ok(device.clone())
+        );
+        assert_eq!(
+            store
+                .get_device(
+                    &get_test_email("other@example.com"),
+                    &device.device_id
+                )
+                .await,
+            Err(TrustedDeviceStoreError::DeviceNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_devices() {
+        let mut store = HashmapTrustedDeviceStore::default();
+        let email = get_test_email("test@example.com");
+        let first = TrustedDevice::new(email.clone(), "Firefox".to_owned(), 1);
+        let second = TrustedDevice::new(email.clone(), "Safari".to_owned(), 2);
+        let other = TrustedDevice::new(
+            get_test_email("other@example.com"),
+            "Chrome".to_owned(),
+            3,
+        );
+
+        store.add_device(second.clone()).await.unwrap();
+        store.add_device(first.clone()).await.unwrap();
+        store.add_device(other).await.unwrap();
+        assert_eq!(store.get_devices(&email).await, Ok(vec![first, second]));
+    }
+
+    #[tokio::test]
+    async fn test_delete_device() {
+        let mut store = HashmapTrustedDeviceStore::default();
+        let email = get_test_email("test@example.com");
+        let device = TrustedDevice::new(email.clone(), "Firefox".to_owned(), 1);
+        store.add_device(device.clone()).await.unwrap();
+
+        assert_eq!(
+            store
+                .delete_device(
+                    &get_test_email("other@example.com"),
+                    &device.device_id
+                )
+                .await,
+            Err(TrustedDeviceStoreError::DeviceNotFound)
+        );
+        assert_eq!(
+            store.delete_device(&email, &device.device_id).await,
+            Ok(())
+        );
+        assert_eq!(
+            store.get_device(&email, &device.device_id).await,
+            Err(TrustedDeviceStoreError::DeviceNotFound)
+        );
+    }
+}
@@ -1,9 +1,40 @@
-use crate::domain::{Email, Password, User, UserStore, UserStoreError};
+use crate::{
+    domain::{
+        Email, ImportedUser, Password, UndeliverableEmail, User, UserStore,
+        UserStoreError,
+    },
+    utils::constants::DEFAULT_PASSWORD_HISTORY_SIZE,
+};
 use std::collections::HashMap;
 
-#[derive(Default)]
 pub struct HashmapUserStore {
     users: HashMap<Email, User>,
+    // Each user's most recent passwords, newest last
+    password_history: HashMap<Email, Vec<Password>>,
+    password_history_size: usize,
+}
+
+impl HashmapUserStore {
+    pub fn new(password_history_size: usize) -> Self {
+        Self {
+            users: HashMap::new(),
+            password_history: HashMap::new(),
+            password_history_size,
+        }
+    }
+
+    fn record_password(&mut self, email: &Email, password: Password) {
+        let history = self.password_history.entry(email.clone()).or_default();
+        history.push(password);
+        let excess = history.len().saturating_sub(self.password_history_size);
+        history.drain(..excess);
+    }
+}
+
+impl Default for HashmapUserStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_PASSWORD_HISTORY_SIZE)
+    }
 }
 
 #[async_trait::async_trait]
@@ -15,12 +46,24 @@ impl UserStore for HashmapUserStore {
             return Err(UserStoreError::UserAlreadyExists);
         }
 
+        self.record_password(email, user.password.clone());
         self.users.insert(email.clone(), user);
         Ok(())
     }
 
+    // Passwords aren't hashed here, so the imported hash becomes the password
+    async fn import_user(
+        &mut self,
+        user: ImportedUser,
+    ) -> Result<(), UserStoreError> {
+        let password = Password::parse(user.password_hash)
+            .map_err(|_| UserStoreError::UnrecognisedPasswordHash)?;
+        self.add_user(User::new(user.email, password, user.requires_2fa))
+            .await
+    }
+
     async fn get_user(&self, email: &Email) -> Result<User, UserStoreError> {
-        match self.users.get(&email) {
+        match self.users.get(email) {
             Some(user) => Ok(user.clone()),
             None => Err(UserStoreError::UserNotFound),
         }
@@ -31,8 +74,10 @@ impl UserStore for HashmapUserStore {
         email: &Email,
         password: &Password,
     ) -> Result<(), UserStoreError> {
-        let user = self.get_user(&email).await?;
-        if password.eq(&user.password) {
+        let user = self.get_user(email).await?;
+        // Passwords set before they were normalised are kept as typed
+        let normalised = password.normalised();
+        if normalised == user.password || *password == user.password {
             Ok(())
         } else {
             Err(UserStoreError::InvalidCredentials)
@@ -43,11 +88,74 @@ impl UserStore for HashmapUserStore {
         &mut self,
         email: &Email,
     ) -> Result<(), UserStoreError> {
+        self.password_history.remove(email);
         match self.users.remove(email) {
             Some(_) => Ok(()),
             None => Err(UserStoreError::UserNotFound),
         }
     }
+
+    async fn set_requires_2fa(
+        &mut self,
+        email: &Email,
+        requires_2fa: bool,
+    ) -> Result<(), UserStoreError> {
+        match self.users.get_mut(email) {
+            Some(user) => {
+                user.requires_2fa = requires_2fa;
+                Ok(())
+            }
+            None => Err(UserStoreError::UserNotFound),
+        }
+    }
+
+    async fn update_password(
+        &mut self,
+        email: &Email,
+        password: Password,
+    ) -> Result<(), UserStoreError> {
+        if !self.users.contains_key(email) {
+            return Err(UserStoreError::UserNotFound);
+        }
+        if self
+            .password_history
+            .get(email)
+            .is_some_and(|history| history.contains(&password))
+        {
+            return Err(UserStoreError::PasswordReused);
+        }
+
+        self.record_password(email, password.clone());
+        if let Some(user) = self.users.get_mut(email) {
+            user.password = password;
+        }
+        Ok(())
+    }
+
+    async fn set_email_undeliverable(
+        &mut self,
+        email: &Email,
+        undeliverable: Option<UndeliverableEmail>,
+    ) -> Result<(), UserStoreError> {
+        match self.users.get_mut(email) {
+            Some(user) => {
+                user.email_undeliverable = undeliverable;
+                Ok(())
+            }
+            None => Err(UserStoreError::UserNotFound),
+        }
+    }
+
+    async fn get_undeliverable_users(
+        &self,
+    ) -> Result<Vec<User>, UserStoreError> {
+        Ok(self
+            .users
+            .values()
+            .filter(|user| user.email_undeliverable.is_some())
+            .cloned()
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -160,6 +268,46 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_validate_user_normalises_password() {
+        let mut users = HashmapUserStore::default();
+        let email =
+            Email::parse(Secret::new("foo@bar.com".to_string())).unwrap();
+        let password =
+            |s: &str| Password::parse(Secret::new(s.to_string())).unwrap();
+        let normalised_email =
+            Email::parse(Secret::new("bar@foo.com".to_string())).unwrap();
+        users
+            .add_user(User::new(email.clone(), password("cafe\u{301}"), true))
+            .await
+            .unwrap();
+        users
+            .add_user(User::new(
+                normalised_email.clone(),
+                password("cafe\u{301}").normalised(),
+                true,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            users.validate_user(&email, &password("cafe\u{301}")).await,
+            Ok(())
+        );
+        assert_eq!(
+            users
+                .validate_user(&normalised_email, &password("caf\u{e9}"))
+                .await,
+            Ok(())
+        );
+        assert_eq!(
+            users
+                .validate_user(&normalised_email, &password("cafe\u{301}"))
+                .await,
+            Ok(())
+        );
+    }
+
     #[tokio::test]
     async fn test_delete_user() {
         let mut users = HashmapUserStore::default();
@@ -172,10 +320,9 @@ mod tests {
 
         // Should be able to re-add and re-delete
         for _ in 0..2 {
-            users
-                .add_user(user.clone())
-                .await
-                .expect(user.email.as_ref().expose_secret());
+            users.add_user(user.clone()).await.unwrap_or_else(|_| {
+                panic!("{}", user.email.as_ref().expose_secret())
+            });
 
             assert_eq!(
                 users.delete_user(&user.email).await,
@@ -189,4 +336,105 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_set_requires_2fa() {
+        let mut users = HashmapUserStore::default();
+        let user = get_test_users().remove(0);
+        users.add_user(user.clone()).await.unwrap();
+
+        assert_eq!(users.set_requires_2fa(&user.email, false).await, Ok(()));
+        assert!(!users.get_user(&user.email).await.unwrap().requires_2fa);
+
+        let non_existent_email =
+            Email::parse(Secret::new("no@email.com".to_string())).unwrap();
+        assert_eq!(
+            users.set_requires_2fa(&non_existent_email, true).await,
+            Err(UserStoreError::UserNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_email_undeliverable() {
+        let mut users = HashmapUserStore::default();
+        for user in get_test_users() {
+            users.add_user(user).await.unwrap();
+        }
+        let email = get_test_users().remove(0).email;
+        let undeliverable = UndeliverableEmail {
+            reason: "HardBounce".to_owned(),
+            marked_at: 1_700_000_000,
+        };
+
+        assert_eq!(
+            users
+                .set_email_undeliverable(&email, Some(undeliverable.clone()))
+                .await,
+            Ok(())
+        );
+        assert_eq!(
+            users.get_user(&email).await.unwrap().email_undeliverable,
+            Some(undeliverable)
+        );
+        let undeliverable_users =
+            users.get_undeliverable_users().await.unwrap();
+        assert_eq!(undeliverable_users.len(), 1);
+        assert_eq!(undeliverable_users[0].email, email);
+
+        assert_eq!(users.set_email_undeliverable(&email, None).await, Ok(()));
+        assert!(users.get_undeliverable_users().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_set_email_undeliverable_for_unknown_user() {
+        let mut users = HashmapUserStore::default();
+        let non_existent_email =
+            Email::parse(Secret::new("no@email.com".to_string())).unwrap();
+
+        assert_eq!(
+            users
+                .set_email_undeliverable(&non_existent_email, None)
+                .await,
+            Err(UserStoreError::UserNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_password_refuses_recent_passwords() {
+        let mut users = HashmapUserStore::new(2);
+        let email =
+            Email::parse(Secret::new("test@example.com".to_string())).unwrap();
+        let password =
+            |s: &str| Password::parse(Secret::new(s.to_string())).unwrap();
+        users
+            .add_user(User::new(email.clone(), password("first-pass"), false))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            users.update_password(&email, password("first-pass")).await,
+            Err(UserStoreError::PasswordReused)
+        );
+        assert_eq!(
+            users.update_password(&email, password("second-pass")).await,
+            Ok(())
+        );
+        assert_eq!(
+            users.update_password(&email, password("first-pass")).await,
+            Err(UserStoreError::PasswordReused)
+        );
+        assert_eq!(
+            users.update_password(&email, password("third-pass")).await,
+            Ok(())
+        );
+        // Only the last two are kept
+        assert_eq!(
+            users.update_password(&email, password("first-pass")).await,
+            Ok(())
+        );
+        assert_eq!(
+            users.validate_user(&email, &password("first-pass")).await,
+            Ok(())
+        );
+    }
 }
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use crate::domain::{WebhookDelivery, WebhookStore, WebhookStoreError};
+
+#[derive(Default)]
+pub struct HashmapWebhookStore {
+    deliveries: HashMap<String, WebhookDelivery>,
+    dead_letters: Vec<WebhookDelivery>,
+}
+
+#[async_trait::async_trait]
+impl WebhookStore for HashmapWebhookStore {
+    async fn add_delivery(
+        &mut self,
+        delivery: WebhookDelivery,
+    ) -> Result<(), WebhookStoreError> {
+        self.deliveries
+            .insert(delivery.delivery_id.clone(), delivery);
+        Ok(())
+    }
+
+    async fn claim_due_deliveries(
+        &mut self,
+        now: u64,
+        claimed_until: u64,
+        limit: usize,
+    ) -> Result<Vec<WebhookDelivery>, WebhookStoreError> {
+        let mut deliveries: Vec<&mut WebhookDelivery> = self
+            .deliveries
+            .values_mut()
+            .filter(|delivery| delivery.next_attempt_at <= now)
+            .collect();
+        deliveries.sort_by_key(|delivery| delivery.next_attempt_at);
+        deliveries.truncate(limit);
+        Ok(deliveries
+            .into_iter()
+            .map(|delivery| {
+                delivery.next_attempt_at = claimed_until;
+                delivery.clone()
+            })
+            .collect())
+    }
+
+    async fn update_delivery(
+        &mut self,
+        delivery: &WebhookDelivery,
+    ) -> Result<(), WebhookStoreError> {
+        match self.deliveries.get_mut(&delivery.delivery_id) {
+            Some(stored) => {
+                *stored = delivery.clone();
+                Ok(())
+            }
+            None => Err(WebhookStoreError::DeliveryNotFound),
+        }
+    }
+
+    async fn remove_delivery(
+        &mut self,
+        delivery_id: &str,
+    ) -> Result<(), WebhookStoreError> {
+        match self.deliveries.remove(delivery_id) {
+            Some(_) => Ok(()),
+            None => Err(WebhookStoreError::DeliveryNotFound),
+        }
+    }
+
+    async fn dead_letter(
+        &mut self,
+        delivery: &WebhookDelivery,
+        failed_at: u64,
+    ) -> Result<(), WebhookStoreError> {
+        self.remove_delivery(&delivery.delivery_id).await?;
+        self.dead_letters.push(WebhookDelivery {
+            next_attempt_at: failed_at,
+            ..delivery.clone()
+        });
+        Ok(())
+    }
+
+    async fn get_dead_letters(
+        &self,
+    ) -> Result<Vec<WebhookDelivery>, WebhookStoreError> {
+        Ok(self.dead_letters.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_delivery(next_attempt_at: u64) -> WebhookDelivery {
+        WebhookDelivery::new(
+            "http://localhost/webhooks".to_owned(),
+            "{}".to_owned(),
+            next_attempt_at,
+        )
+    }
+
+    fn claimed(
+        delivery: &WebhookDelivery,
+        claimed_until: u64,
+    ) -> WebhookDelivery {
+        WebhookDelivery {
+            next_attempt_at: claimed_until,
+            ..delivery.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_claim_due_deliveries() {
+        let mut store = HashmapWebhookStore::default();
+        let first = get_test_delivery(1);
+        let second = get_test_delivery(2);
+        let later = get_test_delivery(10);
+
+        store.add_delivery(second.clone()).await.unwrap();
+        store.add_delivery(later).await.unwrap();
+        store.add_delivery(first.clone()).await.unwrap();
+
+        assert_eq!(
+            store.claim_due_deliveries(5, 20, 1).await,
+            Ok(vec![claimed(&first, 20)])
+        );
+        assert_eq!(
+            store.claim_due_deliveries(5, 20, 10).await,
+            Ok(vec![claimed(&second, 20)])
+        );
+        // Claimed deliveries are due again once the claim runs out
+        assert_eq!(store.claim_due_deliveries(5, 20, 10).await, Ok(vec![]));
+        assert_eq!(
+            store.claim_due_deliveries(20, 30, 10).await.unwrap().len(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_and_remove_delivery() {
+        let mut store = HashmapWebhookStore::default();
+        let mut delivery = get_test_delivery(1);
+        store.add_delivery(delivery.clone()).await.unwrap();
+
+        delivery.attempts = 1;
+        delivery.next_attempt_at = 10;
+        assert_eq!(store.update_delivery(&delivery).await, Ok(()));
+        assert_eq!(store.claim_due_deliveries(5, 20, 10).await, Ok(vec![]));
+
+        assert_eq!(store.remove_delivery(&delivery.delivery_id).await, Ok(()));
+        assert_eq!(
+            store.update_delivery(&delivery).await,
+            Err(WebhookStoreError::DeliveryNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter() {
+        let mut store = HashmapWebhookStore::default();
+        let mut delivery = get_test_delivery(1);
+        store.add_delivery(delivery.clone()).await.unwrap();
+
+        assert_eq!(store.dead_letter(&delivery, 3).await, Ok(()));
+        assert_eq!(store.claim_due_deliveries(5, 20, 10).await, Ok(vec![]));
+        delivery.next_attempt_at = 3;
+        assert_eq!(store.get_dead_letters().await, Ok(vec![delivery]));
+    }
+}
@@ -1,13 +1,49 @@
+mod hashmap_authorization_code_store;
+mod hashmap_device_code_store;
+mod hashmap_email_outbox_store;
+mod hashmap_login_history_store;
+mod hashmap_oauth_client_store;
+mod hashmap_refresh_token_store;
+mod hashmap_service_account_store;
+mod hashmap_trusted_device_store;
 mod hashmap_two_fa_code_store;
 mod hashmap_user_store;
+mod hashmap_webhook_store;
 mod hashset_banned_token_store;
+mod postgres_email_outbox_store;
+mod postgres_login_history_store;
+mod postgres_oauth_client_store;
+mod postgres_service_account_store;
+mod postgres_trusted_device_store;
 mod postgres_user_store;
+mod postgres_webhook_store;
+mod redis_authorization_code_store;
 mod redis_banned_token_store;
+mod redis_device_code_store;
+mod redis_refresh_token_store;
 mod redis_two_fa_code_store;
 
+pub use hashmap_authorization_code_store::*;
+pub use hashmap_device_code_store::*;
+pub use hashmap_email_outbox_store::*;
+pub use hashmap_login_history_store::*;
+pub use hashmap_oauth_client_store::*;
+pub use hashmap_refresh_token_store::*;
+pub use hashmap_service_account_store::*;
+pub use hashmap_trusted_device_store::*;
 pub use hashmap_two_fa_code_store::*;
 pub use hashmap_user_store::*;
+pub use hashmap_webhook_store::*;
 pub use hashset_banned_token_store::*;
+pub use postgres_email_outbox_store::*;
+pub use postgres_login_history_store::*;
+pub use postgres_oauth_client_store::*;
+pub use postgres_service_account_store::*;
+pub use postgres_trusted_device_store::*;
 pub use postgres_user_store::*;
+pub use postgres_webhook_store::*;
+pub use redis_authorization_code_store::*;
 pub use redis_banned_token_store::*;
+pub use redis_device_code_store::*;
+pub use redis_refresh_token_store::*;
 pub use redis_two_fa_code_store::*;
@@ -0,0 +1,274 @@
+use color_eyre::eyre::{eyre, Result};
+use secrecy::{ExposeSecret, Secret};
+use sqlx::{PgConnection, PgPool};
+
+use crate::domain::{
+    Email, EmailOutboxStore, EmailOutboxStoreError, OutboxEmail,
+};
+
+pub struct PostgresEmailOutboxStore {
+    pool: PgPool,
+}
+
+impl PostgresEmailOutboxStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // Queues an email on the caller's connection, so other stores can write
+    // it in the same transaction as the change it's about
+    #[tracing::instrument(
+        name = "Inserting email into PostgreSQL outbox",
+        skip_all
+    )]
+    pub async fn insert_email(
+        connection: &mut PgConnection,
+        email: &OutboxEmail,
+    ) -> Result<(), EmailOutboxStoreError> {
+        let (attempts, next_attempt_at) = to_columns(email)?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO email_outbox
+                (id, recipient, subject, html_body, text_body, attempts,
+                 next_attempt_at, last_error)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            email.id,
+            email.recipient.as_ref().expose_secret(),
+            email.subject,
+            email.html_body,
+            email.text_body,
+            attempts,
+            next_attempt_at,
+            email.last_error
+        )
+        .execute(&mut *connection)
+        .await
+        .map_err(|e| EmailOutboxStoreError::UnexpectedError(e.into()))?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl EmailOutboxStore for PostgresEmailOutboxStore {
+    #[tracing::instrument(name = "Adding email to PostgreSQL outbox", skip_all)]
+    async fn add_email(
+        &mut self,
+        email: OutboxEmail,
+    ) -> Result<(), EmailOutboxStoreError> {
+        let mut connection =
+            self.pool.acquire().await.map_err(|e| {
+                EmailOutboxStoreError::UnexpectedError(e.into())
+            })?;
+        Self::insert_email(&mut connection, &email).await
+    }
+
+    // Rows locked by another worker's claim are skipped rather than waited
+    // on, and claimed rows aren't due again until the claim runs out
+    #[tracing::instrument(
+        name = "Claiming due emails from PostgreSQL outbox",
+        skip_all
+    )]
+    async fn claim_due_emails(
+        &mut self,
+        now: u64,
+        claimed_until: u64,
+        limit: usize,
+    ) -> Result<Vec<OutboxEmail>, EmailOutboxStoreError> {
+        let now = i64::try_from(now)
+            .map_err(|e| EmailOutboxStoreError::UnexpectedError(eyre!(e)))?;
+        let claimed_until = i64::try_from(claimed_until)
+            .map_err(|e| EmailOutboxStoreError::UnexpectedError(eyre!(e)))?;
+        let limit = i64::try_from(limit)
+            .map_err(|e| EmailOutboxStoreError::UnexpectedError(eyre!(e)))?;
+
+        let rows = sqlx::query!(
+            r#"
+            WITH due AS (
+                SELECT id, next_attempt_at
+                FROM email_outbox
+                WHERE failed_at IS NULL AND next_attempt_at <= $1
+                ORDER BY next_attempt_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            ), claimed AS (
+                UPDATE email_outbox
+                SET next_attempt_at = $2
+                FROM due
+                WHERE email_outbox.id = due.id
+                RETURNING email_outbox.id, recipient, subject, html_body,
+                    text_body, attempts, email_outbox.next_attempt_at,
+                    last_error, due.next_attempt_at AS due_at
+            )
+            SELECT id AS "id!", recipient AS "recipient!",
+                subject AS "subject!", html_body AS "html_body!",
+                text_body AS "text_body!", attempts AS "attempts!",
+                next_attempt_at AS "next_attempt_at!", last_error
+            FROM claimed
+            ORDER BY due_at
+            "#,
+            now,
+            claimed_until,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| EmailOutboxStoreError::UnexpectedError(e.into()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(OutboxEmail {
+                    id: row.id,
+                    recipient: Email::parse(Secret::new(row.recipient))?,
+                    subject: row.subject,
+                    html_body: row.html_body,
+                    text_body: row.text_body,
+                    attempts: row.attempts.try_into()?,
+                    next_attempt_at: row.next_attempt_at.try_into()?,
+                    last_error: row.last_error,
+                })
+            })
+            .collect::<Result<_>>()
+            .map_err(EmailOutboxStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(
+        name = "Updating email in PostgreSQL outbox",
+        skip_all
+    )]
+    async fn update_email(
+        &mut self,
+        email: &OutboxEmail,
+    ) -> Result<(), EmailOutboxStoreError> {
+        let (attempts, next_attempt_at) = to_columns(email)?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE email_outbox
+            SET attempts = $2, next_attempt_at = $3, last_error = $4
+            WHERE id = $1 AND failed_at IS NULL
+            "#,
+            email.id,
+            attempts,
+            next_attempt_at,
+            email.last_error
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| EmailOutboxStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(EmailOutboxStoreError::EmailNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Removing email from PostgreSQL outbox",
+        skip_all
+    )]
+    async fn remove_email(
+        &mut self,
+        id: &str,
+    ) -> Result<(), EmailOutboxStoreError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM email_outbox WHERE id = $1 AND failed_at IS NULL
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| EmailOutboxStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(EmailOutboxStoreError::EmailNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Marking email failed in PostgreSQL outbox",
+        skip_all
+    )]
+    async fn mark_failed(
+        &mut self,
+        email: &OutboxEmail,
+        failed_at: u64,
+    ) -> Result<(), EmailOutboxStoreError> {
+        let (attempts, _) = to_columns(email)?;
+        let failed_at = i64::try_from(failed_at)
+            .map_err(|e| EmailOutboxStoreError::UnexpectedError(eyre!(e)))?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE email_outbox
+            SET attempts = $2, last_error = $3, failed_at = $4
+            WHERE id = $1 AND failed_at IS NULL
+            "#,
+            email.id,
+            attempts,
+            email.last_error,
+            failed_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| EmailOutboxStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(EmailOutboxStoreError::EmailNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving failed emails from PostgreSQL outbox",
+        skip_all
+    )]
+    async fn get_failed_emails(
+        &self,
+    ) -> Result<Vec<OutboxEmail>, EmailOutboxStoreError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, recipient, subject, html_body, text_body, attempts,
+                last_error, failed_at AS "failed_at!"
+            FROM email_outbox
+            WHERE failed_at IS NOT NULL
+            ORDER BY failed_at
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| EmailOutboxStoreError::UnexpectedError(e.into()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(OutboxEmail {
+                    id: row.id,
+                    recipient: Email::parse(Secret::new(row.recipient))?,
+                    subject: row.subject,
+                    html_body: row.html_body,
+                    text_body: row.text_body,
+                    attempts: row.attempts.try_into()?,
+                    next_attempt_at: row.failed_at.try_into()?,
+                    last_error: row.last_error,
+                })
+            })
+            .collect::<Result<_>>()
+            .map_err(EmailOutboxStoreError::UnexpectedError)
+    }
+}
+
+fn to_columns(
+    email: &OutboxEmail,
+) -> Result<(i32, i64), EmailOutboxStoreError> {
+    let attempts = i32::try_from(email.attempts)
+        .map_err(|e| EmailOutboxStoreError::UnexpectedError(eyre!(e)))?;
+    let next_attempt_at = i64::try_from(email.next_attempt_at)
+        .map_err(|e| EmailOutboxStoreError::UnexpectedError(eyre!(e)))?;
+    Ok((attempts, next_attempt_at))
+}
@@ -0,0 +1,148 @@
+use color_eyre::eyre::{eyre, Result};
+use secrecy::{ExposeSecret, Secret};
+use sqlx::PgPool;
+
+use super::PostgresEmailOutboxStore;
+use crate::domain::{
+    Email, LoginEvent, LoginHistoryStore, LoginHistoryStoreError, LoginMethod,
+    OutboxEmail,
+};
+
+pub struct PostgresLoginHistoryStore {
+    pool: PgPool,
+}
+
+impl PostgresLoginHistoryStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl LoginHistoryStore for PostgresLoginHistoryStore {
+    #[tracing::instrument(name = "Adding login event to PostgreSQL", skip_all)]
+    async fn add_event(
+        &mut self,
+        event: LoginEvent,
+        notice: Option<OutboxEmail>,
+    ) -> Result<(), LoginHistoryStoreError> {
+        let created_at = i64::try_from(event.timestamp)
+            .map_err(|e| LoginHistoryStoreError::UnexpectedError(eyre!(e)))?;
+
+        let mut transaction =
+            self.pool.begin().await.map_err(|e| {
+                LoginHistoryStoreError::UnexpectedError(e.into())
+            })?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO login_events
+                (email, created_at, ip, user_agent, method, success)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            event.email.as_ref().expose_secret(),
+            created_at,
+            event.ip,
+            event.user_agent,
+            event.method.as_ref(),
+            event.success
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| LoginHistoryStoreError::UnexpectedError(e.into()))?;
+
+        if let Some(notice) = notice {
+            PostgresEmailOutboxStore::insert_email(&mut transaction, &notice)
+                .await
+                .map_err(|e| {
+                    LoginHistoryStoreError::UnexpectedError(eyre!(e))
+                })?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| LoginHistoryStoreError::UnexpectedError(e.into()))
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving login events from PostgreSQL",
+        skip_all
+    )]
+    async fn get_events(
+        &self,
+        email: &Email,
+        limit: usize,
+    ) -> Result<Vec<LoginEvent>, LoginHistoryStoreError> {
+        let limit = i64::try_from(limit)
+            .map_err(|e| LoginHistoryStoreError::UnexpectedError(eyre!(e)))?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT email, created_at, ip, user_agent, method, success
+            FROM login_events
+            WHERE email = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#,
+            email.as_ref().expose_secret(),
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| LoginHistoryStoreError::UnexpectedError(e.into()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(LoginEvent {
+                    email: Email::parse(Secret::new(row.email))?,
+                    timestamp: row.created_at.try_into()?,
+                    ip: row.ip,
+                    user_agent: row.user_agent,
+                    method: LoginMethod::parse(&row.method)?,
+                    success: row.success,
+                })
+            })
+            .collect::<Result<_>>()
+            .map_err(LoginHistoryStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(
+        name = "Checking for new device in PostgreSQL",
+        skip_all
+    )]
+    async fn is_new_device(
+        &self,
+        email: &Email,
+        ip: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<bool, LoginHistoryStoreError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT 1 FROM login_events
+                    WHERE email = $1 AND success
+                ) AS "has_logged_in!",
+                EXISTS (
+                    SELECT 1 FROM login_events
+                    WHERE email = $1 AND success
+                        AND ip IS NOT DISTINCT FROM $2::TEXT
+                ) AS "seen_ip!",
+                EXISTS (
+                    SELECT 1 FROM login_events
+                    WHERE email = $1 AND success
+                        AND user_agent IS NOT DISTINCT FROM $3::TEXT
+                ) AS "seen_user_agent!"
+            "#,
+            email.as_ref().expose_secret(),
+            ip,
+            user_agent
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| LoginHistoryStoreError::UnexpectedError(e.into()))?;
+
+        Ok(row.has_logged_in && !(row.seen_ip && row.seen_user_agent))
+    }
+}
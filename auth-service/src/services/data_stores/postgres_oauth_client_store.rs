@@ -0,0 +1,125 @@
+use color_eyre::eyre::eyre;
+use secrecy::{ExposeSecret, Secret};
+use sqlx::PgPool;
+
+use super::postgres_user_store::{compute_password_hash, verify_password_hash};
+use crate::domain::{
+    ClientSecret, OAuthClient, OAuthClientStore, OAuthClientStoreError, Scopes,
+};
+use crate::services::password_hashers::PasswordHashers;
+
+pub struct PostgresOAuthClientStore {
+    pool: PgPool,
+}
+
+impl PostgresOAuthClientStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl OAuthClientStore for PostgresOAuthClientStore {
+    #[tracing::instrument(name = "Adding OAuth client to PostgreSQL", skip_all)]
+    async fn add_client(
+        &mut self,
+        client: OAuthClient,
+    ) -> Result<(), OAuthClientStoreError> {
+        let client_secret_hash = match client.client_secret {
+            Some(secret) => Some(
+                compute_password_hash(
+                    secret.as_ref().to_owned(),
+                    PasswordHashers::for_client_secrets(),
+                )
+                .await
+                .map_err(OAuthClientStoreError::UnexpectedError)?,
+            ),
+            None => None,
+        };
+
+        let scopes: Vec<String> = client.scopes.as_ref().to_vec();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO oauth_clients (client_id, client_secret_hash, name, redirect_uris, scopes)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            client.client_id,
+            client_secret_hash.as_ref().map(|hash| hash.expose_secret()),
+            client.name,
+            &client.redirect_uris,
+            &scopes
+        ).execute(&self.pool).await.map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => OAuthClientStoreError::ClientAlreadyExists,
+            err => OAuthClientStoreError::UnexpectedError(err.into())
+        })?;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving OAuth client from PostgreSQL",
+        skip_all
+    )]
+    async fn get_client(
+        &self,
+        client_id: &str,
+    ) -> Result<OAuthClient, OAuthClientStoreError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT client_id, client_secret_hash, name, redirect_uris, scopes
+            FROM oauth_clients
+            WHERE client_id = $1
+            "#,
+            client_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => OAuthClientStoreError::ClientNotFound,
+            err => OAuthClientStoreError::UnexpectedError(err.into()),
+        })?;
+
+        // As with users, the secret field carries the hash once stored
+        let client_secret = row
+            .client_secret_hash
+            .map(|hash| ClientSecret::parse(Secret::new(hash)))
+            .transpose()
+            .map_err(|e| OAuthClientStoreError::UnexpectedError(eyre!(e)))?;
+
+        Ok(OAuthClient {
+            client_id: row.client_id,
+            name: row.name,
+            redirect_uris: row.redirect_uris,
+            scopes: Scopes::parse(&row.scopes.join(" ")).map_err(|e| {
+                OAuthClientStoreError::UnexpectedError(eyre!(e))
+            })?,
+            client_secret,
+        })
+    }
+
+    #[tracing::instrument(
+        name = "Validating OAuth client credentials in PostgreSQL",
+        skip_all
+    )]
+    async fn validate_client(
+        &self,
+        client_id: &str,
+        client_secret: Option<&ClientSecret>,
+    ) -> Result<OAuthClient, OAuthClientStoreError> {
+        let client = self.get_client(client_id).await?;
+
+        match (&client.client_secret, client_secret) {
+            (None, None) => (),
+            (Some(hash), Some(candidate)) => verify_password_hash(
+                hash.as_ref().to_owned(),
+                candidate.as_ref().to_owned(),
+                PasswordHashers::for_client_secrets(),
+            )
+            .await
+            .map_err(|_| OAuthClientStoreError::InvalidCredentials)?,
+            _ => return Err(OAuthClientStoreError::InvalidCredentials),
+        }
+
+        Ok(client)
+    }
+}
@@ -0,0 +1,162 @@
+use color_eyre::eyre::eyre;
+use secrecy::{ExposeSecret, Secret};
+use sqlx::PgPool;
+
+use super::postgres_user_store::{compute_password_hash, verify_password_hash};
+use crate::domain::{
+    ClientSecret, Scopes, ServiceAccount, ServiceAccountStore,
+    ServiceAccountStoreError,
+};
+use crate::services::password_hashers::PasswordHashers;
+
+pub struct PostgresServiceAccountStore {
+    pool: PgPool,
+}
+
+impl PostgresServiceAccountStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl ServiceAccountStore for PostgresServiceAccountStore {
+    #[tracing::instrument(
+        name = "Adding service account to PostgreSQL",
+        skip_all
+    )]
+    async fn add_account(
+        &mut self,
+        account: ServiceAccount,
+    ) -> Result<(), ServiceAccountStoreError> {
+        let client_secret_hash = compute_password_hash(
+            account.client_secret.as_ref().to_owned(),
+            PasswordHashers::for_client_secrets(),
+        )
+        .await
+        .map_err(ServiceAccountStoreError::UnexpectedError)?;
+
+        let scopes: Vec<String> = account.scopes.as_ref().to_vec();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO service_accounts (client_id, client_secret_hash, name, scopes)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            account.client_id,
+            client_secret_hash.expose_secret(),
+            account.name,
+            &scopes
+        ).execute(&self.pool).await.map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ServiceAccountStoreError::AccountAlreadyExists,
+            err => ServiceAccountStoreError::UnexpectedError(err.into())
+        })?;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving service accounts from PostgreSQL",
+        skip_all
+    )]
+    async fn get_accounts(
+        &self,
+    ) -> Result<Vec<ServiceAccount>, ServiceAccountStoreError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT client_id, client_secret_hash, name, scopes
+            FROM service_accounts
+            ORDER BY created_at
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ServiceAccountStoreError::UnexpectedError(e.into()))?;
+
+        // As with users, the secret field carries the hash once stored
+        rows.into_iter()
+            .map(|row| {
+                Ok(ServiceAccount {
+                    client_id: row.client_id,
+                    name: row.name,
+                    scopes: Scopes::parse(&row.scopes.join(" "))?,
+                    client_secret: ClientSecret::parse(Secret::new(
+                        row.client_secret_hash,
+                    ))?,
+                })
+            })
+            .collect::<color_eyre::eyre::Result<_>>()
+            .map_err(|e| ServiceAccountStoreError::UnexpectedError(eyre!(e)))
+    }
+
+    #[tracing::instrument(
+        name = "Validating service account credentials in PostgreSQL",
+        skip_all
+    )]
+    async fn validate_account(
+        &self,
+        client_id: &str,
+        client_secret: &ClientSecret,
+    ) -> Result<ServiceAccount, ServiceAccountStoreError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT client_id, client_secret_hash, name, scopes
+            FROM service_accounts
+            WHERE client_id = $1
+            "#,
+            client_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => {
+                ServiceAccountStoreError::AccountNotFound
+            }
+            err => ServiceAccountStoreError::UnexpectedError(err.into()),
+        })?;
+
+        verify_password_hash(
+            Secret::new(row.client_secret_hash.clone()),
+            client_secret.as_ref().to_owned(),
+            PasswordHashers::for_client_secrets(),
+        )
+        .await
+        .map_err(|_| ServiceAccountStoreError::InvalidCredentials)?;
+
+        Ok(ServiceAccount {
+            client_id: row.client_id,
+            name: row.name,
+            scopes: Scopes::parse(&row.scopes.join(" ")).map_err(|e| {
+                ServiceAccountStoreError::UnexpectedError(eyre!(e))
+            })?,
+            client_secret: ClientSecret::parse(Secret::new(
+                row.client_secret_hash,
+            ))
+            .map_err(|e| ServiceAccountStoreError::UnexpectedError(eyre!(e)))?,
+        })
+    }
+
+    #[tracing::instrument(
+        name = "Deleting service account from PostgreSQL",
+        skip_all
+    )]
+    async fn delete_account(
+        &mut self,
+        client_id: &str,
+    ) -> Result<(), ServiceAccountStoreError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM service_accounts
+            WHERE client_id = $1
+            "#,
+            client_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ServiceAccountStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ServiceAccountStoreError::AccountNotFound);
+        }
+        Ok(())
+    }
+}
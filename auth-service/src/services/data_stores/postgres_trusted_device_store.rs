@@ -0,0 +1,155 @@
+use color_eyre::eyre::{eyre, Result};
+use secrecy::{ExposeSecret, Secret};
+use sqlx::PgPool;
+
+use crate::domain::{
+    Email, TrustedDevice, TrustedDeviceStore, TrustedDeviceStoreError,
+};
+
+pub struct PostgresTrustedDeviceStore {
+    pool: PgPool,
+}
+
+impl PostgresTrustedDeviceStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl TrustedDeviceStore for PostgresTrustedDeviceStore {
+    #[tracing::instrument(
+        name = "Adding trusted device to PostgreSQL",
+        skip_all
+    )]
+    async fn add_device(
+        &mut self,
+        device: TrustedDevice,
+    ) -> Result<(), TrustedDeviceStoreError> {
+        let created_at = i64::try_from(device.created_at)
+            .map_err(|e| TrustedDeviceStoreError::UnexpectedError(eyre!(e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO trusted_devices (device_id, email, name, created_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            device.device_id,
+            device.email.as_ref().expose_secret(),
+            device.name,
+            created_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| TrustedDeviceStoreError::UnexpectedError(e.into()))?;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving trusted device from PostgreSQL",
+        skip_all
+    )]
+    async fn get_device(
+        &self,
+        email: &Email,
+        device_id: &str,
+    ) -> Result<TrustedDevice, TrustedDeviceStoreError> {
+        sqlx::query!(
+            r#"
+            SELECT device_id, email, name, created_at
+            FROM trusted_devices
+            WHERE email = $1 AND device_id = $2
+            "#,
+            email.as_ref().expose_secret(),
+            device_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| TrustedDeviceStoreError::UnexpectedError(e.into()))?
+        .map(|row| {
+            to_trusted_device(
+                row.device_id,
+                row.email,
+                row.name,
+                row.created_at,
+            )
+        })
+        .ok_or(TrustedDeviceStoreError::DeviceNotFound)?
+        .map_err(TrustedDeviceStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving trusted devices from PostgreSQL",
+        skip_all
+    )]
+    async fn get_devices(
+        &self,
+        email: &Email,
+    ) -> Result<Vec<TrustedDevice>, TrustedDeviceStoreError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT device_id, email, name, created_at
+            FROM trusted_devices
+            WHERE email = $1
+            ORDER BY created_at
+            "#,
+            email.as_ref().expose_secret()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| TrustedDeviceStoreError::UnexpectedError(e.into()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                to_trusted_device(
+                    row.device_id,
+                    row.email,
+                    row.name,
+                    row.created_at,
+                )
+            })
+            .collect::<Result<_>>()
+            .map_err(TrustedDeviceStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(
+        name = "Deleting trusted device from PostgreSQL",
+        skip_all
+    )]
+    async fn delete_device(
+        &mut self,
+        email: &Email,
+        device_id: &str,
+    ) -> Result<(), TrustedDeviceStoreError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM trusted_devices WHERE email = $1 AND device_id = $2
+            "#,
+            email.as_ref().expose_secret(),
+            device_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| TrustedDeviceStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(TrustedDeviceStoreError::DeviceNotFound);
+        }
+
+        Ok(())
+    }
+}
+
+fn to_trusted_device(
+    device_id: String,
+    email: String,
+    name: String,
+    created_at: i64,
+) -> Result<TrustedDevice> {
+    Ok(TrustedDevice {
+        device_id,
+        email: Email::parse(Secret::new(email))?,
+        name,
+        created_at: created_at.try_into()?,
+    })
+}
@@ -1,51 +1,193 @@
-use argon2::{
-    password_hash::SaltString, Algorithm, Argon2, Params, PasswordHash,
-    PasswordHasher, PasswordVerifier, Version,
-};
-use color_eyre::eyre::{eyre, Context, Result};
+use color_eyre::eyre::{eyre, Result};
 use secrecy::{ExposeSecret, Secret};
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 
-use crate::domain::{Email, Password, User, UserStore, UserStoreError};
+use crate::{
+    domain::{
+        Email, ImportedUser, Password, UndeliverableEmail, User, UserStore,
+        UserStoreError,
+    },
+    services::password_hashers::PasswordHashers,
+};
 
 pub struct PostgresUserStore {
     pool: PgPool,
+    // New password hashes are Argon2id. Weaker or legacy hashes are upgraded
+    // to it when their user next signs in
+    hashers: PasswordHashers,
+    // How many of each user's passwords, including the current one, can't be
+    // used again. 0 turns the history off
+    password_history_size: usize,
 }
 
 impl PostgresUserStore {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(
+        pool: PgPool,
+        hashers: PasswordHashers,
+        password_history_size: usize,
+    ) -> Self {
+        Self {
+            pool,
+            hashers,
+            password_history_size,
+        }
     }
-}
 
-#[async_trait::async_trait]
-impl UserStore for PostgresUserStore {
-    #[tracing::instrument(name = "Adding user to PostgreSQL", skip_all)]
-    async fn add_user(&mut self, user: User) -> Result<(), UserStoreError> {
-        let password_hash =
-            compute_password_hash(user.password.as_ref().to_owned())
-                .await
-                .map_err(UserStoreError::UnexpectedError)?;
+    async fn insert_user(
+        &self,
+        email: &Email,
+        password_hash: &Secret<String>,
+        requires_2fa: bool,
+    ) -> Result<(), UserStoreError> {
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
 
         sqlx::query!(
             r#"
             INSERT INTO users (email, password_hash, requires_2fa) VALUES ($1, $2, $3)
             "#,
-            user.email.as_ref().expose_secret(),
+            email.as_ref().expose_secret(),
             &password_hash.expose_secret(),
-            user.requires_2fa
-        ).execute(&self.pool).await.map_err(|e| match e {
+            requires_2fa
+        ).execute(&mut *transaction).await.map_err(|e| match e {
             sqlx::Error::Database(db_err) if db_err.is_unique_violation() => UserStoreError::UserAlreadyExists,
             err => UserStoreError::UnexpectedError(err.into())
         })?;
+        self.record_password(&mut transaction, email, password_hash)
+            .await
+            .map_err(UserStoreError::UnexpectedError)?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| UserStoreError::UnexpectedError(e.into()))
+    }
+
+    // Adds the hash to the user's history and forgets any older than the
+    // history keeps
+    async fn record_password(
+        &self,
+        connection: &mut PgConnection,
+        email: &Email,
+        password_hash: &Secret<String>,
+    ) -> Result<()> {
+        if self.password_history_size == 0 {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO password_history (email, password_hash) VALUES ($1, $2)
+            "#,
+            email.as_ref().expose_secret(),
+            password_hash.expose_secret()
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM password_history
+            WHERE email = $1 AND id NOT IN (
+                SELECT id FROM password_history
+                WHERE email = $1
+                ORDER BY id DESC
+                LIMIT $2
+            )
+            "#,
+            email.as_ref().expose_secret(),
+            i64::try_from(self.password_history_size)?
+        )
+        .execute(&mut *connection)
+        .await?;
         Ok(())
     }
 
+    // Only replaces the hash it was computed from, so a password changed in
+    // the meantime isn't overwritten. The history is rehashed too, or the
+    // password could be reused once the old hash's pepper is retired
+    #[tracing::instrument(name = "Rehashing password in PostgreSQL", skip_all)]
+    async fn rehash_password(
+        &self,
+        email: &Email,
+        password: &Password,
+        old_hash: &Secret<String>,
+    ) -> Result<()> {
+        let new_hash = compute_password_hash(
+            password.as_ref().to_owned(),
+            self.hashers.clone(),
+        )
+        .await?;
+
+        let mut transaction = self.pool.begin().await?;
+        let result = sqlx::query!(
+            r#"
+            UPDATE users SET password_hash = $1
+            WHERE email = $2 AND password_hash = $3
+            "#,
+            new_hash.expose_secret(),
+            email.as_ref().expose_secret(),
+            old_hash.expose_secret()
+        )
+        .execute(&mut *transaction)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE password_history SET password_hash = $1
+            WHERE email = $2 AND password_hash = $3
+            "#,
+            new_hash.expose_secret(),
+            email.as_ref().expose_secret(),
+            old_hash.expose_secret()
+        )
+        .execute(&mut *transaction)
+        .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl UserStore for PostgresUserStore {
+    #[tracing::instrument(name = "Adding user to PostgreSQL", skip_all)]
+    async fn add_user(&mut self, user: User) -> Result<(), UserStoreError> {
+        let password_hash = compute_password_hash(
+            user.password.as_ref().to_owned(),
+            self.hashers.clone(),
+        )
+        .await
+        .map_err(UserStoreError::UnexpectedError)?;
+
+        self.insert_user(&user.email, &password_hash, user.requires_2fa)
+            .await
+    }
+
+    #[tracing::instrument(name = "Importing user to PostgreSQL", skip_all)]
+    async fn import_user(
+        &mut self,
+        user: ImportedUser,
+    ) -> Result<(), UserStoreError> {
+        if !self.hashers.recognises(user.password_hash.expose_secret()) {
+            return Err(UserStoreError::UnrecognisedPasswordHash);
+        }
+
+        self.insert_user(&user.email, &user.password_hash, user.requires_2fa)
+            .await
+    }
+
     #[tracing::instrument(name = "Retrieving user from PostgreSQL", skip_all)]
     async fn get_user(&self, email: &Email) -> Result<User, UserStoreError> {
         sqlx::query!(
             r#"
-                    SELECT email, password_hash, requires_2fa
+                    SELECT email, password_hash, requires_2fa,
+                        email_undeliverable_reason, email_undeliverable_at
                     FROM users
                     WHERE email = $1
                     "#,
@@ -64,6 +206,11 @@ impl UserStore for PostgresUserStore {
                 password: Password::parse(Secret::new(row.password_hash))
                     .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?,
                 requires_2fa: row.requires_2fa,
+                email_undeliverable: undeliverable_email(
+                    row.email_undeliverable_reason,
+                    row.email_undeliverable_at,
+                )
+                .map_err(UserStoreError::UnexpectedError)?,
             })
         })?
     }
@@ -77,13 +224,52 @@ impl UserStore for PostgresUserStore {
         email: &Email,
         password: &Password,
     ) -> Result<(), UserStoreError> {
-        let user = self.get_user(&email).await?;
-        verify_password_hash(
-            user.password.as_ref().to_owned(),
-            password.as_ref().to_owned(),
+        let user = self.get_user(email).await?;
+        let password_hash = user.password.as_ref();
+        // Hashes from before passwords were normalised are of the password as
+        // it was typed, so that's tried too, and rehashed normalised below
+        let normalised = password.normalised();
+        let typed_matched = match verify_password_hash(
+            password_hash.to_owned(),
+            normalised.as_ref().to_owned(),
+            self.hashers.clone(),
         )
         .await
-        .map_err(|_| UserStoreError::InvalidCredentials)
+        {
+            Ok(()) => false,
+            Err(_) if normalised != *password => {
+                verify_password_hash(
+                    password_hash.to_owned(),
+                    password.as_ref().to_owned(),
+                    self.hashers.clone(),
+                )
+                .await
+                .map_err(|_| UserStoreError::InvalidCredentials)?;
+                true
+            }
+            Err(_) => return Err(UserStoreError::InvalidCredentials),
+        };
+
+        // The password is right, so this is the one chance to upgrade its
+        // hash. Failing to doesn't stop the user signing in
+        let needs_rehash = if typed_matched {
+            Ok(true)
+        } else {
+            self.hashers.needs_rehash(password_hash)
+        };
+        match needs_rehash {
+            Ok(false) => {}
+            Ok(true) => {
+                if let Err(e) = self
+                    .rehash_password(email, &normalised, password_hash)
+                    .await
+                {
+                    tracing::warn!("Failed to rehash password: {:?}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to read password hash: {:?}", e),
+        }
+        Ok(())
     }
 
     async fn delete_user(
@@ -106,51 +292,240 @@ impl UserStore for PostgresUserStore {
 
         Ok(())
     }
+
+    #[tracing::instrument(name = "Updating user 2FA in PostgreSQL", skip_all)]
+    async fn set_requires_2fa(
+        &mut self,
+        email: &Email,
+        requires_2fa: bool,
+    ) -> Result<(), UserStoreError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users SET requires_2fa = $2 WHERE email = $1
+            "#,
+            email.as_ref().expose_secret(),
+            requires_2fa
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Updating password in PostgreSQL", skip_all)]
+    async fn update_password(
+        &mut self,
+        email: &Email,
+        password: Password,
+    ) -> Result<(), UserStoreError> {
+        let history_size = i64::try_from(self.password_history_size)
+            .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+        let recent_hashes = sqlx::query!(
+            r#"
+            SELECT password_hash FROM password_history
+            WHERE email = $1
+            ORDER BY id DESC
+            LIMIT $2
+            "#,
+            email.as_ref().expose_secret(),
+            history_size
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(|row| Secret::new(row.password_hash))
+        .collect();
+
+        let reused = matches_any_hash(
+            recent_hashes,
+            password.as_ref().to_owned(),
+            self.hashers.clone(),
+        )
+        .await
+        .map_err(UserStoreError::UnexpectedError)?;
+        if reused {
+            return Err(UserStoreError::PasswordReused);
+        }
+
+        let password_hash = compute_password_hash(
+            password.as_ref().to_owned(),
+            self.hashers.clone(),
+        )
+        .await
+        .map_err(UserStoreError::UnexpectedError)?;
+
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+        let result = sqlx::query!(
+            r#"
+            UPDATE users SET password_hash = $1 WHERE email = $2
+            "#,
+            password_hash.expose_secret(),
+            email.as_ref().expose_secret()
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        self.record_password(&mut transaction, email, &password_hash)
+            .await
+            .map_err(UserStoreError::UnexpectedError)?;
+        transaction
+            .commit()
+            .await
+            .map_err(|e| UserStoreError::UnexpectedError(e.into()))
+    }
+
+    #[tracing::instrument(
+        name = "Updating user email deliverability in PostgreSQL",
+        skip_all
+    )]
+    async fn set_email_undeliverable(
+        &mut self,
+        email: &Email,
+        undeliverable: Option<UndeliverableEmail>,
+    ) -> Result<(), UserStoreError> {
+        let (reason, marked_at) =
+            match undeliverable {
+                Some(undeliverable) => (
+                    Some(undeliverable.reason),
+                    Some(i64::try_from(undeliverable.marked_at).map_err(
+                        |e| UserStoreError::UnexpectedError(eyre!(e)),
+                    )?),
+                ),
+                None => (None, None),
+            };
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET email_undeliverable_reason = $2, email_undeliverable_at = $3
+            WHERE email = $1
+            "#,
+            email.as_ref().expose_secret(),
+            reason,
+            marked_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving undeliverable users from PostgreSQL",
+        skip_all
+    )]
+    async fn get_undeliverable_users(
+        &self,
+    ) -> Result<Vec<User>, UserStoreError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT email, password_hash, requires_2fa,
+                email_undeliverable_reason, email_undeliverable_at
+            FROM users
+            WHERE email_undeliverable_reason IS NOT NULL
+            ORDER BY email_undeliverable_at DESC, email
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(User {
+                    email: Email::parse(Secret::new(row.email))?,
+                    password: Password::parse(Secret::new(row.password_hash))?,
+                    requires_2fa: row.requires_2fa,
+                    email_undeliverable: undeliverable_email(
+                        row.email_undeliverable_reason,
+                        row.email_undeliverable_at,
+                    )?,
+                })
+            })
+            .collect::<Result<_>>()
+            .map_err(UserStoreError::UnexpectedError)
+    }
+}
+
+fn undeliverable_email(
+    reason: Option<String>,
+    marked_at: Option<i64>,
+) -> Result<Option<UndeliverableEmail>> {
+    match (reason, marked_at) {
+        (Some(reason), Some(marked_at)) => Ok(Some(UndeliverableEmail {
+            reason,
+            marked_at: marked_at.try_into()?,
+        })),
+        _ => Ok(None),
+    }
 }
 
 #[tracing::instrument(name = "Verify password hash", skip_all)]
-async fn verify_password_hash(
+pub(crate) async fn verify_password_hash(
     expected_password_hash: Secret<String>,
     password_candidate: Secret<String>,
+    hashers: PasswordHashers,
 ) -> Result<()> {
     let current_span: tracing::Span = tracing::Span::current();
 
     tokio::task::spawn_blocking(move || {
         current_span.in_scope(|| {
-            let expected_password_hash: PasswordHash<'_> =
-                PasswordHash::new(&expected_password_hash.expose_secret())?;
-
-            Argon2::default()
-                .verify_password(
-                    password_candidate.expose_secret().as_bytes(),
-                    &expected_password_hash,
-                )
-                .wrap_err("failed to verify password hash")
+            hashers.verify(&expected_password_hash, &password_candidate)
         })
     })
     .await?
 }
 
+// Whether the password matches any of the hashes. A hash that can't be
+// checked, e.g. one with a retired pepper, doesn't match
+#[tracing::instrument(name = "Checking password history", skip_all)]
+async fn matches_any_hash(
+    password_hashes: Vec<Secret<String>>,
+    password: Secret<String>,
+    hashers: PasswordHashers,
+) -> Result<bool> {
+    let current_span: tracing::Span = tracing::Span::current();
+
+    let matches = tokio::task::spawn_blocking(move || {
+        current_span.in_scope(|| {
+            password_hashes.iter().any(|password_hash| {
+                hashers.verify(password_hash, &password).is_ok()
+            })
+        })
+    })
+    .await?;
+    Ok(matches)
+}
+
 #[tracing::instrument(name = "Computing password hash", skip_all)]
-async fn compute_password_hash(
+pub(crate) async fn compute_password_hash(
     password: Secret<String>,
+    hashers: PasswordHashers,
 ) -> Result<Secret<String>> {
     let current_span: tracing::Span = tracing::Span::current();
 
     tokio::task::spawn_blocking(move || {
-        current_span.in_scope(|| {
-            let salt: SaltString =
-                SaltString::generate(&mut rand::thread_rng());
-            let password_hash = Argon2::new(
-                Algorithm::Argon2id,
-                Version::V0x13,
-                Params::new(15000, 2, 1, None)?,
-            )
-            .hash_password(password.expose_secret().as_bytes(), &salt)?
-            .to_string();
-
-            Ok(Secret::new(password_hash))
-        })
+        current_span.in_scope(|| hashers.hash(&password))
     })
     .await?
 }
@@ -0,0 +1,268 @@
+use color_eyre::eyre::{eyre, Result};
+use sqlx::PgPool;
+
+use crate::domain::{WebhookDelivery, WebhookStore, WebhookStoreError};
+
+pub struct PostgresWebhookStore {
+    pool: PgPool,
+}
+
+impl PostgresWebhookStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookStore for PostgresWebhookStore {
+    #[tracing::instrument(
+        name = "Adding webhook delivery to PostgreSQL",
+        skip_all
+    )]
+    async fn add_delivery(
+        &mut self,
+        delivery: WebhookDelivery,
+    ) -> Result<(), WebhookStoreError> {
+        let (attempts, next_attempt_at) = to_columns(&delivery)?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_deliveries
+                (delivery_id, endpoint, payload, attempts, next_attempt_at,
+                 last_error)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            delivery.delivery_id,
+            delivery.endpoint,
+            delivery.payload,
+            attempts,
+            next_attempt_at,
+            delivery.last_error
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Claiming due webhook deliveries in PostgreSQL",
+        skip_all
+    )]
+    async fn claim_due_deliveries(
+        &mut self,
+        now: u64,
+        claimed_until: u64,
+        limit: usize,
+    ) -> Result<Vec<WebhookDelivery>, WebhookStoreError> {
+        let now = i64::try_from(now)
+            .map_err(|e| WebhookStoreError::UnexpectedError(eyre!(e)))?;
+        let claimed_until = i64::try_from(claimed_until)
+            .map_err(|e| WebhookStoreError::UnexpectedError(eyre!(e)))?;
+        let limit = i64::try_from(limit)
+            .map_err(|e| WebhookStoreError::UnexpectedError(eyre!(e)))?;
+
+        let rows = sqlx::query!(
+            r#"
+            WITH due AS (
+                SELECT delivery_id, next_attempt_at
+                FROM webhook_deliveries
+                WHERE next_attempt_at <= $1
+                ORDER BY next_attempt_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            ), claimed AS (
+                UPDATE webhook_deliveries
+                SET next_attempt_at = $2
+                FROM due
+                WHERE webhook_deliveries.delivery_id = due.delivery_id
+                RETURNING webhook_deliveries.delivery_id, endpoint, payload,
+                    attempts, webhook_deliveries.next_attempt_at, last_error,
+                    due.next_attempt_at AS due_at
+            )
+            SELECT delivery_id AS "delivery_id!", endpoint AS "endpoint!",
+                payload AS "payload!", attempts AS "attempts!",
+                next_attempt_at AS "next_attempt_at!", last_error
+            FROM claimed
+            ORDER BY due_at
+            "#,
+            now,
+            claimed_until,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(WebhookDelivery {
+                    delivery_id: row.delivery_id,
+                    endpoint: row.endpoint,
+                    payload: row.payload,
+                    attempts: row.attempts.try_into()?,
+                    next_attempt_at: row.next_attempt_at.try_into()?,
+                    last_error: row.last_error,
+                })
+            })
+            .collect::<Result<_>>()
+            .map_err(WebhookStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(
+        name = "Updating webhook delivery in PostgreSQL",
+        skip_all
+    )]
+    async fn update_delivery(
+        &mut self,
+        delivery: &WebhookDelivery,
+    ) -> Result<(), WebhookStoreError> {
+        let (attempts, next_attempt_at) = to_columns(delivery)?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET attempts = $2, next_attempt_at = $3, last_error = $4
+            WHERE delivery_id = $1
+            "#,
+            delivery.delivery_id,
+            attempts,
+            next_attempt_at,
+            delivery.last_error
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(WebhookStoreError::DeliveryNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Removing webhook delivery from PostgreSQL",
+        skip_all
+    )]
+    async fn remove_delivery(
+        &mut self,
+        delivery_id: &str,
+    ) -> Result<(), WebhookStoreError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM webhook_deliveries WHERE delivery_id = $1
+            "#,
+            delivery_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(WebhookStoreError::DeliveryNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Dead-lettering webhook delivery in PostgreSQL",
+        skip_all
+    )]
+    async fn dead_letter(
+        &mut self,
+        delivery: &WebhookDelivery,
+        failed_at: u64,
+    ) -> Result<(), WebhookStoreError> {
+        let (attempts, _) = to_columns(delivery)?;
+        let failed_at = i64::try_from(failed_at)
+            .map_err(|e| WebhookStoreError::UnexpectedError(eyre!(e)))?;
+
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM webhook_deliveries WHERE delivery_id = $1
+            "#,
+            delivery.delivery_id
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+        if result.rows_affected() == 0 {
+            return Err(WebhookStoreError::DeliveryNotFound);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_dead_letters
+                (delivery_id, endpoint, payload, attempts, last_error,
+                 failed_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            delivery.delivery_id,
+            delivery.endpoint,
+            delivery.payload,
+            attempts,
+            delivery.last_error,
+            failed_at
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving webhook dead letters from PostgreSQL",
+        skip_all
+    )]
+    async fn get_dead_letters(
+        &self,
+    ) -> Result<Vec<WebhookDelivery>, WebhookStoreError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT delivery_id, endpoint, payload, attempts, last_error,
+                failed_at
+            FROM webhook_dead_letters
+            ORDER BY failed_at
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(WebhookDelivery {
+                    delivery_id: row.delivery_id,
+                    endpoint: row.endpoint,
+                    payload: row.payload,
+                    attempts: row.attempts.try_into()?,
+                    next_attempt_at: row.failed_at.try_into()?,
+                    last_error: row.last_error,
+                })
+            })
+            .collect::<Result<_>>()
+            .map_err(WebhookStoreError::UnexpectedError)
+    }
+}
+
+fn to_columns(
+    delivery: &WebhookDelivery,
+) -> Result<(i32, i64), WebhookStoreError> {
+    let attempts = i32::try_from(delivery.attempts)
+        .map_err(|e| WebhookStoreError::UnexpectedError(eyre!(e)))?;
+    let next_attempt_at = i64::try_from(delivery.next_attempt_at)
+        .map_err(|e| WebhookStoreError::UnexpectedError(eyre!(e)))?;
+    Ok((attempts, next_attempt_at))
+}
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::{eyre, WrapErr};
+use redis::{Commands, Connection};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::domain::{
+    AuthorizationCode, AuthorizationCodeStore, AuthorizationCodeStoreError,
+    AuthorizationGrant, CodeChallenge, Email, Scopes,
+};
+
+pub struct RedisAuthorizationCodeStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisAuthorizationCodeStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthorizationCodeStore for RedisAuthorizationCodeStore {
+    #[tracing::instrument(
+        name = "Adding code to Redis authorization code store",
+        skip_all
+    )]
+    async fn add_code(
+        &mut self,
+        code: AuthorizationCode,
+        grant: AuthorizationGrant,
+    ) -> Result<(), AuthorizationCodeStoreError> {
+        let key = get_key(&code);
+
+        let grant = StoredGrant {
+            client_id: grant.client_id,
+            redirect_uri: grant.redirect_uri,
+            email: grant.email.as_ref().expose_secret().to_owned(),
+            scope: grant.scopes.to_string(),
+            code_challenge: grant.code_challenge.as_ref().to_owned(),
+            nonce: grant.nonce,
+            auth_time: grant.auth_time,
+        };
+
+        let grant = serde_json::to_string(&grant)
+            .wrap_err("failed to serialise authorization grant")
+            .map_err(AuthorizationCodeStoreError::UnexpectedError)?;
+
+        self.conn
+            .write()
+            .await
+            .set_ex::<_, _, ()>(key, grant, AUTHORIZATION_CODE_TTL_SECONDS)
+            .wrap_err("failed to set authorization code in Redis")
+            .map_err(AuthorizationCodeStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Taking code from Redis authorization code store",
+        skip_all
+    )]
+    async fn take_code(
+        &mut self,
+        code: &AuthorizationCode,
+    ) -> Result<AuthorizationGrant, AuthorizationCodeStoreError> {
+        let key = get_key(code);
+
+        let grant = self
+            .conn
+            .write()
+            .await
+            .get_del::<_, Option<String>>(key)
+            .wrap_err("failed to take authorization code from Redis")
+            .map_err(AuthorizationCodeStoreError::UnexpectedError)?
+            .ok_or(AuthorizationCodeStoreError::CodeNotFound)?;
+
+        let grant = serde_json::from_str::<StoredGrant>(&grant)
+            .wrap_err("failed to deserialise authorization grant")
+            .map_err(AuthorizationCodeStoreError::UnexpectedError)?;
+
+        Ok(AuthorizationGrant {
+            client_id: grant.client_id,
+            redirect_uri: grant.redirect_uri,
+            email: Email::parse(Secret::new(grant.email)).map_err(|e| {
+                AuthorizationCodeStoreError::UnexpectedError(eyre!(e))
+            })?,
+            scopes: Scopes::parse(&grant.scope).map_err(|e| {
+                AuthorizationCodeStoreError::UnexpectedError(eyre!(e))
+            })?,
+            code_challenge: CodeChallenge::parse(&grant.code_challenge)
+                .map_err(|e| {
+                    AuthorizationCodeStoreError::UnexpectedError(eyre!(e))
+                })?,
+            nonce: grant.nonce,
+            auth_time: grant.auth_time,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredGrant {
+    client_id: String,
+    redirect_uri: String,
+    email: String,
+    scope: String,
+    code_challenge: String,
+    nonce: Option<String>,
+    auth_time: usize,
+}
+
+// RFC 6749 recommends authorization codes live no longer than 10 minutes
+const AUTHORIZATION_CODE_TTL_SECONDS: u64 = 300;
+const AUTHORIZATION_CODE_PREFIX: &str = "authorization_code:";
+
+fn get_key(code: &AuthorizationCode) -> String {
+    format!(
+        "{}{}",
+        AUTHORIZATION_CODE_PREFIX,
+        code.as_ref().expose_secret()
+    )
+}
@@ -6,7 +6,7 @@ use tokio::sync::RwLock;
 
 use crate::{
     domain::{BannedTokenStore, BannedTokenStoreError},
-    utils::auth::TOKEN_TTL_SECONDS,
+    utils::{auth::SERVICE_TOKEN_TTL_SECONDS, constants::TOKEN_TTL_SECONDS},
 };
 
 pub struct RedisBannedTokenStore {
@@ -27,7 +27,9 @@ impl BannedTokenStore for RedisBannedTokenStore {
     )]
     async fn add_token(&mut self, token: &Secret<String>) -> Result<()> {
         let key = get_key(token);
-        let token_ttl_seconds: u64 = TOKEN_TTL_SECONDS
+        // Keep the token banned for as long as any kind of token lives
+        let token_ttl_seconds: u64 = (*TOKEN_TTL_SECONDS)
+            .max(SERVICE_TOKEN_TTL_SECONDS)
             .try_into()
             .wrap_err("failed to cast TOKEN_TTL_SECONDS to u64")
             .map_err(BannedTokenStoreError::UnexpectedError)?;
@@ -47,15 +49,13 @@ impl BannedTokenStore for RedisBannedTokenStore {
         &self,
         token: &Secret<String>,
     ) -> Result<(), BannedTokenStoreError> {
-        let key = get_key(&token);
+        let key = get_key(token);
         match self.conn.write().await.exists(&key) {
             Ok(true) => Err(BannedTokenStoreError::BannedToken),
             Ok(false) => Ok(()),
-            Err(e) => {
-                Err(eyre!(e)
-                    .wrap_err("failed to check if token exists in Redis"))
-                .map_err(BannedTokenStoreError::UnexpectedError)
-            }
+            Err(e) => Err(BannedTokenStoreError::UnexpectedError(
+                eyre!(e).wrap_err("failed to check if token exists in Redis"),
+            )),
         }
     }
 }
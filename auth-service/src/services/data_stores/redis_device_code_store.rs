@@ -0,0 +1,225 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use color_eyre::eyre::WrapErr;
+use redis::{Commands, Connection};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::domain::{
+    DeviceCode, DeviceCodeStore, DeviceCodeStoreError, DeviceGrant,
+    DeviceGrantStatus, Email, Scopes, UserCode,
+};
+
+pub struct RedisDeviceCodeStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisDeviceCodeStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl DeviceCodeStore for RedisDeviceCodeStore {
+    #[tracing::instrument(
+        name = "Adding code to Redis device code store",
+        skip_all
+    )]
+    async fn add_code(
+        &mut self,
+        code: DeviceCode,
+        grant: DeviceGrant,
+    ) -> Result<(), DeviceCodeStoreError> {
+        let ttl = get_ttl(&grant);
+        let user_code_key = get_user_code_key(&grant.user_code);
+
+        self.update_code(&code, grant).await?;
+        self.conn
+            .write()
+            .await
+            .set_ex::<_, _, ()>(
+                user_code_key,
+                code.as_ref().expose_secret(),
+                ttl,
+            )
+            .wrap_err("failed to set user code in Redis")
+            .map_err(DeviceCodeStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving code from Redis device code store",
+        skip_all
+    )]
+    async fn get_code(
+        &self,
+        code: &DeviceCode,
+    ) -> Result<DeviceGrant, DeviceCodeStoreError> {
+        let grant = self
+            .conn
+            .write()
+            .await
+            .get::<_, Option<String>>(get_key(code))
+            .wrap_err("failed to get device code from Redis")
+            .map_err(DeviceCodeStoreError::UnexpectedError)?
+            .ok_or(DeviceCodeStoreError::CodeNotFound)?;
+
+        let grant = serde_json::from_str::<StoredGrant>(&grant)
+            .wrap_err("failed to deserialise device grant")
+            .map_err(DeviceCodeStoreError::UnexpectedError)?;
+
+        DeviceGrant::try_from(grant)
+            .map_err(DeviceCodeStoreError::UnexpectedError)
+    }
+
+    // Grants are rewritten whole, keeping the original expiry
+    #[tracing::instrument(
+        name = "Updating code in Redis device code store",
+        skip_all
+    )]
+    async fn update_code(
+        &mut self,
+        code: &DeviceCode,
+        grant: DeviceGrant,
+    ) -> Result<(), DeviceCodeStoreError> {
+        let ttl = get_ttl(&grant);
+        let grant = serde_json::to_string(&StoredGrant::from(grant))
+            .wrap_err("failed to serialise device grant")
+            .map_err(DeviceCodeStoreError::UnexpectedError)?;
+
+        self.conn
+            .write()
+            .await
+            .set_ex::<_, _, ()>(get_key(code), grant, ttl)
+            .wrap_err("failed to set device code in Redis")
+            .map_err(DeviceCodeStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Removing code from Redis device code store",
+        skip_all
+    )]
+    async fn remove_code(
+        &mut self,
+        code: &DeviceCode,
+    ) -> Result<(), DeviceCodeStoreError> {
+        let grant = self.get_code(code).await?;
+
+        self.conn
+            .write()
+            .await
+            .del::<_, ()>(&[get_key(code), get_user_code_key(&grant.user_code)])
+            .wrap_err("failed to delete device code from Redis")
+            .map_err(DeviceCodeStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Finding user code in Redis device code store",
+        skip_all
+    )]
+    async fn find_user_code(
+        &self,
+        user_code: &UserCode,
+    ) -> Result<DeviceCode, DeviceCodeStoreError> {
+        let code = self
+            .conn
+            .write()
+            .await
+            .get::<_, Option<String>>(get_user_code_key(user_code))
+            .wrap_err("failed to get user code from Redis")
+            .map_err(DeviceCodeStoreError::UnexpectedError)?
+            .ok_or(DeviceCodeStoreError::CodeNotFound)?;
+
+        DeviceCode::parse(Secret::new(code))
+            .map_err(DeviceCodeStoreError::UnexpectedError)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredGrant {
+    client_id: String,
+    scope: String,
+    user_code: String,
+    status: StoredStatus,
+    expires_at: usize,
+    interval: usize,
+    last_polled_at: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum StoredStatus {
+    Pending,
+    Approved { email: String, auth_time: usize },
+    Denied,
+}
+
+impl From<DeviceGrant> for StoredGrant {
+    fn from(grant: DeviceGrant) -> Self {
+        Self {
+            client_id: grant.client_id,
+            scope: grant.scopes.to_string(),
+            user_code: grant.user_code.as_ref().to_owned(),
+            status: match grant.status {
+                DeviceGrantStatus::Pending => StoredStatus::Pending,
+                DeviceGrantStatus::Approved { email, auth_time } => {
+                    StoredStatus::Approved {
+                        email: email.as_ref().expose_secret().to_owned(),
+                        auth_time,
+                    }
+                }
+                DeviceGrantStatus::Denied => StoredStatus::Denied,
+            },
+            expires_at: grant.expires_at,
+            interval: grant.interval,
+            last_polled_at: grant.last_polled_at,
+        }
+    }
+}
+
+impl TryFrom<StoredGrant> for DeviceGrant {
+    type Error = color_eyre::eyre::Report;
+
+    fn try_from(grant: StoredGrant) -> Result<Self, Self::Error> {
+        Ok(Self {
+            client_id: grant.client_id,
+            scopes: Scopes::parse(&grant.scope)?,
+            user_code: UserCode::parse(&grant.user_code)?,
+            status: match grant.status {
+                StoredStatus::Pending => DeviceGrantStatus::Pending,
+                StoredStatus::Approved { email, auth_time } => {
+                    DeviceGrantStatus::Approved {
+                        email: Email::parse(Secret::new(email))?,
+                        auth_time,
+                    }
+                }
+                StoredStatus::Denied => DeviceGrantStatus::Denied,
+            },
+            expires_at: grant.expires_at,
+            interval: grant.interval,
+            last_polled_at: grant.last_polled_at,
+        })
+    }
+}
+
+const DEVICE_CODE_PREFIX: &str = "device_code:";
+const USER_CODE_PREFIX: &str = "device_user_code:";
+
+fn get_key(code: &DeviceCode) -> String {
+    format!("{}{}", DEVICE_CODE_PREFIX, code.as_ref().expose_secret())
+}
+
+fn get_user_code_key(user_code: &UserCode) -> String {
+    format!("{}{}", USER_CODE_PREFIX, user_code.as_ref())
+}
+
+// Redis drops the grant once it expires, which SETEX needs to be at least 1s
+fn get_ttl(grant: &DeviceGrant) -> u64 {
+    let now = Utc::now().timestamp() as usize;
+    grant.expires_at.saturating_sub(now).max(1) as u64
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::{eyre, WrapErr};
+use redis::{Commands, Connection};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::domain::{
+    Email, RefreshGrant, RefreshToken, RefreshTokenStore,
+    RefreshTokenStoreError, Scopes,
+};
+
+pub struct RedisRefreshTokenStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisRefreshTokenStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl RefreshTokenStore for RedisRefreshTokenStore {
+    #[tracing::instrument(
+        name = "Adding token to Redis refresh token store",
+        skip_all
+    )]
+    async fn add_token(
+        &mut self,
+        token: RefreshToken,
+        grant: RefreshGrant,
+    ) -> Result<(), RefreshTokenStoreError> {
+        let key = get_key(&token);
+
+        let grant = StoredGrant {
+            client_id: grant.client_id,
+            email: grant.email.as_ref().expose_secret().to_owned(),
+            scope: grant.scopes.to_string(),
+            auth_time: grant.auth_time,
+        };
+
+        let grant = serde_json::to_string(&grant)
+            .wrap_err("failed to serialise refresh grant")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+
+        self.conn
+            .write()
+            .await
+            .set_ex::<_, _, ()>(key, grant, REFRESH_TOKEN_TTL_SECONDS)
+            .wrap_err("failed to set refresh token in Redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Taking token from Redis refresh token store",
+        skip_all
+    )]
+    async fn take_token(
+        &mut self,
+        token: &RefreshToken,
+    ) -> Result<RefreshGrant, RefreshTokenStoreError> {
+        let key = get_key(token);
+
+        let grant = self
+            .conn
+            .write()
+            .await
+            .get_del::<_, Option<String>>(key)
+            .wrap_err("failed to take refresh token from Redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?
+            .ok_or(RefreshTokenStoreError::TokenNotFound)?;
+
+        parse_grant(&grant)
+    }
+
+    #[tracing::instrument(
+        name = "Getting token from Redis refresh token store",
+        skip_all
+    )]
+    async fn get_token(
+        &self,
+        token: &RefreshToken,
+    ) -> Result<RefreshGrant, RefreshTokenStoreError> {
+        let key = get_key(token);
+
+        let grant = self
+            .conn
+            .write()
+            .await
+            .get::<_, Option<String>>(key)
+            .wrap_err("failed to get refresh token from Redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?
+            .ok_or(RefreshTokenStoreError::TokenNotFound)?;
+
+        parse_grant(&grant)
+    }
+
+    #[tracing::instrument(
+        name = "Removing token from Redis refresh token store",
+        skip_all
+    )]
+    async fn remove_token(
+        &mut self,
+        token: &RefreshToken,
+    ) -> Result<(), RefreshTokenStoreError> {
+        let key = get_key(token);
+
+        self.conn
+            .write()
+            .await
+            .del::<_, ()>(key)
+            .wrap_err("failed to delete refresh token from Redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)
+    }
+}
+
+fn parse_grant(grant: &str) -> Result<RefreshGrant, RefreshTokenStoreError> {
+    let grant = serde_json::from_str::<StoredGrant>(grant)
+        .wrap_err("failed to deserialise refresh grant")
+        .map_err(RefreshTokenStoreError::UnexpectedError)?;
+
+    Ok(RefreshGrant {
+        client_id: grant.client_id,
+        email: Email::parse(Secret::new(grant.email))
+            .map_err(|e| RefreshTokenStoreError::UnexpectedError(eyre!(e)))?,
+        scopes: Scopes::parse(&grant.scope)
+            .map_err(|e| RefreshTokenStoreError::UnexpectedError(eyre!(e)))?,
+        auth_time: grant.auth_time,
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredGrant {
+    client_id: String,
+    email: String,
+    scope: String,
+    auth_time: usize,
+}
+
+const REFRESH_TOKEN_TTL_SECONDS: u64 = 60 * 60 * 24 * 30; // 30 days
+const REFRESH_TOKEN_PREFIX: &str = "refresh_token:";
+
+fn get_key(token: &RefreshToken) -> String {
+    format!("{}{}", REFRESH_TOKEN_PREFIX, token.as_ref().expose_secret())
+}
@@ -1,4 +1,4 @@
-use std::{sync::Arc, u64};
+use std::sync::Arc;
 
 use color_eyre::eyre::{eyre, WrapErr};
 use redis::{Commands, Connection};
@@ -7,8 +7,11 @@ use serde::{Deserialize, Serialize};
 use serde_json;
 use tokio::sync::RwLock;
 
-use crate::domain::{
-    Email, LoginAttemptId, TwoFACode, TwoFACodeStore, TwoFACodeStoreError,
+use crate::{
+    domain::{
+        Email, LoginAttemptId, TwoFACode, TwoFACodeStore, TwoFACodeStoreError,
+    },
+    utils::constants::TWO_FA_CODE_TTL_SECONDS,
 };
 
 pub struct RedisTwoFACodeStore {
@@ -34,6 +37,10 @@ impl TwoFACodeStore for RedisTwoFACodeStore {
         code: TwoFACode,
     ) -> Result<(), TwoFACodeStoreError> {
         let key = get_key(&email);
+        let ttl: u64 = (*TWO_FA_CODE_TTL_SECONDS)
+            .try_into()
+            .wrap_err("failed to cast TWO_FA_CODE_TTL_SECONDS to u64")
+            .map_err(TwoFACodeStoreError::UnexpectedError)?;
 
         let two_fa_details = TwoFATuple(
             login_attempt_id.as_ref().expose_secret().to_owned(),
@@ -47,7 +54,7 @@ impl TwoFACodeStore for RedisTwoFACodeStore {
         self.conn
             .write()
             .await
-            .set_ex::<_, _, ()>(key, two_fa_details, TEN_MINUTES_IN_SECONDS)
+            .set_ex::<_, _, ()>(key, two_fa_details, ttl)
             .wrap_err("failed to set 2FA code in Redis")
             .map_err(TwoFACodeStoreError::UnexpectedError)?;
         Ok(())
@@ -61,7 +68,7 @@ impl TwoFACodeStore for RedisTwoFACodeStore {
         &mut self,
         email: &Email,
     ) -> Result<(), TwoFACodeStoreError> {
-        let key = get_key(&email);
+        let key = get_key(email);
 
         self.conn
             .write()
@@ -80,7 +87,7 @@ impl TwoFACodeStore for RedisTwoFACodeStore {
         &self,
         email: &Email,
     ) -> Result<(LoginAttemptId, TwoFACode), TwoFACodeStoreError> {
-        let key = get_key(&email);
+        let key = get_key(email);
 
         let two_fa_details =
             self.conn.write().await.get::<_, String>(key).map_err(
@@ -117,7 +124,6 @@ impl TwoFACodeStore for RedisTwoFACodeStore {
 #[derive(Serialize, Deserialize)]
 struct TwoFATuple(pub String, pub String);
 
-const TEN_MINUTES_IN_SECONDS: u64 = 600;
 const TWO_FA_CODE_PREFIX: &str = "two_fa_code:";
 
 #[tracing::instrument(name = "building key for Redis 2FA code store", skip_all)]
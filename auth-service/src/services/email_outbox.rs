@@ -0,0 +1,162 @@
+use std::{sync::Arc, time::Duration};
+
+use color_eyre::eyre::{eyre, Result};
+use tokio::sync::Notify;
+
+use super::retry_policy::RetryPolicy;
+use crate::{
+    app_state::{EmailClientType, EmailOutboxStoreType},
+    domain::{Email, EmailClient, OutboxEmail},
+    utils::auth::current_timestamp_millis,
+};
+
+// How many due emails are sent per pass of the worker
+const BATCH_SIZE: usize = 10;
+
+// How long a worker has to send the emails it claimed, long enough for every
+// provider to time out on the whole batch. If the worker dies, its emails are
+// sent by another once the claim runs out
+const CLAIM_DURATION: Duration = Duration::from_secs(5 * 60);
+
+// An email client that only writes to the outbox, so requests don't wait on
+// the email provider. A background worker sends the emails with the wrapped
+// client, retrying failures until they're marked as failed
+pub struct EmailOutbox {
+    outbox_store: EmailOutboxStoreType,
+    email_client: EmailClientType,
+    retry_policy: RetryPolicy,
+    notify: Notify,
+}
+
+impl EmailOutbox {
+    pub fn new(
+        outbox_store: EmailOutboxStoreType,
+        email_client: EmailClientType,
+        retry_policy: RetryPolicy,
+    ) -> Self {
+        Self {
+            outbox_store,
+            email_client,
+            retry_policy,
+            notify: Notify::new(),
+        }
+    }
+
+    // The worker. Runs until the process exits, waking up when an email is
+    // queued or a retry may be due
+    pub async fn run(self: Arc<Self>) {
+        loop {
+            if let Err(e) = self.send_due().await {
+                tracing::error!("Failed to send outbox emails: {:?}", e);
+            }
+
+            tokio::select! {
+                _ = self.notify.notified() => {}
+                _ = tokio::time::sleep(self.retry_policy.poll_interval) => {}
+            }
+        }
+    }
+
+    #[tracing::instrument(name = "Sending due outbox emails", skip_all)]
+    async fn send_due(&self) -> Result<()> {
+        let now = current_timestamp_millis()?;
+        let claimed_until = now + u64::try_from(CLAIM_DURATION.as_millis())?;
+        let emails = self
+            .outbox_store
+            .write()
+            .await
+            .claim_due_emails(now, claimed_until, BATCH_SIZE)
+            .await
+            .map_err(|e| eyre!(e))?;
+
+        // One email's store error shouldn't hold up the rest of the batch,
+        // it's retried once its claim runs out
+        for email in emails {
+            let id = email.id.clone();
+            if let Err(e) = self.attempt(email).await {
+                tracing::error!("Failed to send outbox email {}: {:?}", id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Sending outbox email", skip_all, fields(email_id = %email.id))]
+    async fn attempt(&self, mut email: OutboxEmail) -> Result<()> {
+        let error = match self
+            .email_client
+            .send_email(
+                &email.id,
+                &email.recipient,
+                &email.subject,
+                &email.html_body,
+                &email.text_body,
+            )
+            .await
+        {
+            Ok(()) => {
+                return self
+                    .outbox_store
+                    .write()
+                    .await
+                    .remove_email(&email.id)
+                    .await
+                    .map_err(|e| eyre!(e));
+            }
+            Err(e) => e,
+        };
+
+        let now = current_timestamp_millis()?;
+        email.attempts += 1;
+        email.last_error = Some(error.to_string());
+        let mut outbox_store = self.outbox_store.write().await;
+
+        if email.attempts >= self.retry_policy.max_attempts {
+            tracing::error!("Giving up on email {}: {}", email.id, error);
+            return outbox_store
+                .mark_failed(&email, now)
+                .await
+                .map_err(|e| eyre!(e));
+        }
+
+        let delay = self.retry_policy.delay_after(email.attempts);
+        email.next_attempt_at = now + u64::try_from(delay.as_millis())?;
+        outbox_store
+            .update_email(&email)
+            .await
+            .map_err(|e| eyre!(e))
+    }
+}
+
+#[async_trait::async_trait]
+impl EmailClient for EmailOutbox {
+    #[tracing::instrument(name = "Queueing email", skip_all)]
+    async fn send_email(
+        &self,
+        idempotency_key: &str,
+        recipient: &Email,
+        subject: &str,
+        html_body: &str,
+        text_body: &str,
+    ) -> Result<()> {
+        let email = OutboxEmail {
+            id: idempotency_key.to_owned(),
+            ..OutboxEmail::new(
+                recipient.clone(),
+                subject.to_owned(),
+                html_body.to_owned(),
+                text_body.to_owned(),
+                current_timestamp_millis()?,
+            )
+        };
+        self.outbox_store
+            .write()
+            .await
+            .add_email(email)
+            .await
+            .map_err(|e| eyre!(e))?;
+
+        self.notify.notify_one();
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use color_eyre::eyre::Result;
+
+use crate::{
+    app_state::BreachedPasswordCheckerType,
+    domain::{BreachedPasswordChecker, Password},
+};
+
+// Lets a password through when the wrapped check fails, e.g. the range API is
+// down, so an outage doesn't stop anyone signing up. Only used when
+// BREACHED_PASSWORD_CHECK_FAIL_OPEN is set
+pub struct FailOpenBreachedPasswordChecker {
+    checker: BreachedPasswordCheckerType,
+}
+
+impl FailOpenBreachedPasswordChecker {
+    pub fn new(checker: BreachedPasswordCheckerType) -> Self {
+        Self { checker }
+    }
+}
+
+#[async_trait::async_trait]
+impl BreachedPasswordChecker for FailOpenBreachedPasswordChecker {
+    async fn is_breached(&self, password: &Password) -> Result<bool> {
+        match self.checker.is_breached(password).await {
+            Ok(breached) => Ok(breached),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to check for a breached password: {:?}",
+                    e
+                );
+                Ok(false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use color_eyre::eyre::eyre;
+    use secrecy::Secret;
+
+    use super::*;
+
+    struct FixedChecker(Option<bool>);
+
+    #[async_trait::async_trait]
+    impl BreachedPasswordChecker for FixedChecker {
+        async fn is_breached(&self, _password: &Password) -> Result<bool> {
+            self.0.ok_or_else(|| eyre!("Check failed"))
+        }
+    }
+
+    async fn is_breached(result: Option<bool>) -> Result<bool> {
+        FailOpenBreachedPasswordChecker::new(Arc::new(FixedChecker(result)))
+            .is_breached(
+                &Password::parse(Secret::new("password123".to_owned()))
+                    .unwrap(),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_failed_check_lets_password_through() {
+        assert!(!is_breached(None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_results_are_passed_on() {
+        assert!(is_breached(Some(true)).await.unwrap());
+        assert!(!is_breached(Some(false)).await.unwrap());
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use color_eyre::eyre::{eyre, Result};
+
+use super::circuit_breaker::CircuitBreaker;
+use crate::{
+    app_state::EmailClientType,
+    domain::{Email, EmailClient, EmailProviderMetrics},
+};
+
+// Sends through the first provider that works, in order. A provider that
+// errors or takes longer than timeout is skipped for that email, and one
+// that keeps failing is skipped for a while by its circuit breaker, so an
+// outage doesn't hold up every email
+pub struct FailoverEmailClient {
+    providers: Vec<EmailProvider>,
+    timeout: Duration,
+}
+
+struct EmailProvider {
+    name: String,
+    email_client: EmailClientType,
+    circuit_breaker: Mutex<CircuitBreaker>,
+    sent: AtomicU64,
+    failed: AtomicU64,
+    skipped: AtomicU64,
+}
+
+impl FailoverEmailClient {
+    pub fn new(
+        providers: Vec<(String, EmailClientType)>,
+        timeout: Duration,
+        failure_threshold: u32,
+        open_duration: Duration,
+    ) -> Self {
+        let providers = providers
+            .into_iter()
+            .map(|(name, email_client)| EmailProvider {
+                name,
+                email_client,
+                circuit_breaker: Mutex::new(CircuitBreaker::new(
+                    failure_threshold,
+                    open_duration,
+                )),
+                sent: AtomicU64::new(0),
+                failed: AtomicU64::new(0),
+                skipped: AtomicU64::new(0),
+            })
+            .collect();
+
+        Self { providers, timeout }
+    }
+
+    pub fn delivery_metrics(&self) -> Vec<EmailProviderMetrics> {
+        self.providers
+            .iter()
+            .map(|provider| EmailProviderMetrics {
+                provider: provider.name.clone(),
+                sent: provider.sent.load(Ordering::Relaxed),
+                failed: provider.failed.load(Ordering::Relaxed),
+                skipped: provider.skipped.load(Ordering::Relaxed),
+                circuit: provider.circuit_breaker().state_name().to_owned(),
+            })
+            .collect()
+    }
+}
+
+impl EmailProvider {
+    fn circuit_breaker(&self) -> std::sync::MutexGuard<'_, CircuitBreaker> {
+        // The breaker's state is always valid, even if a holder panicked
+        self.circuit_breaker
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[async_trait::async_trait]
+impl EmailClient for FailoverEmailClient {
+    #[tracing::instrument(name = "Sending email with failover", skip_all)]
+    async fn send_email(
+        &self,
+        idempotency_key: &str,
+        recipient: &Email,
+        subject: &str,
+        html_body: &str,
+        text_body: &str,
+    ) -> Result<()> {
+        let mut errors = Vec::new();
+
+        for provider in &self.providers {
+            if !provider.circuit_breaker().allows(Instant::now()) {
+                provider.skipped.fetch_add(1, Ordering::Relaxed);
+                errors.push(format!("{}: circuit open", provider.name));
+                continue;
+            }
+
+            let result = tokio::time::timeout(
+                self.timeout,
+                provider.email_client.send_email(
+                    idempotency_key,
+                    recipient,
+                    subject,
+                    html_body,
+                    text_body,
+                ),
+            )
+            .await
+            .unwrap_or_else(|_| {
+                Err(eyre!("timed out after {:?}", self.timeout))
+            });
+
+            match result {
+                Ok(()) => {
+                    provider.circuit_breaker().record_success();
+                    provider.sent.fetch_add(1, Ordering::Relaxed);
+                    tracing::info!(
+                        provider = %provider.name,
+                        "Email sent by {}",
+                        provider.name
+                    );
+                    return Ok(());
+                }
+                Err(e) => {
+                    provider.circuit_breaker().record_failure(Instant::now());
+                    provider.failed.fetch_add(1, Ordering::Relaxed);
+                    tracing::warn!(
+                        provider = %provider.name,
+                        "Email provider {} failed: {:?}",
+                        provider.name,
+                        e
+                    );
+                    errors.push(format!("{}: {}", provider.name, e));
+                }
+            }
+        }
+
+        Err(eyre!("Every email provider failed ({})", errors.join("; ")))
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::eyre::{eyre, Result};
+use mail_parser::{Address, MessageParser};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::smtp_email_client::build_message;
+use crate::domain::{Email, EmailClient};
+
+// Writes each email to `directory` as an RFC 5322 `.eml` file, the same
+// message that would be sent over SMTP. For local development, where there
+// is no email provider to send through
+pub struct FileEmailClient {
+    directory: PathBuf,
+    sender: Email,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct MailboxMessage {
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub date: String,
+    pub text_body: String,
+    pub html_body: String,
+}
+
+impl FileEmailClient {
+    pub fn new(directory: PathBuf, sender: Email) -> Self {
+        Self { directory, sender }
+    }
+
+    // Newest first
+    pub async fn list_messages(&self) -> Result<Vec<MailboxMessage>> {
+        let mut ids = Vec::new();
+        let mut entries = match tokio::fs::read_dir(&self.directory).await {
+            Ok(entries) => entries,
+            // Nothing has been sent yet
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Vec::new())
+            }
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let file_name = entry.file_name();
+            let Some(id) = file_name
+                .to_str()
+                .and_then(|name| name.strip_suffix(".eml"))
+            else {
+                continue;
+            };
+            if is_valid_id(id) {
+                ids.push(id.to_owned());
+            }
+        }
+        // Ids start with the send time, so they sort in the order sent
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut messages = Vec::with_capacity(ids.len());
+        for id in ids {
+            messages.push(self.read_message(id).await?);
+        }
+        Ok(messages)
+    }
+
+    pub async fn get_message(
+        &self,
+        id: &str,
+    ) -> Result<Option<MailboxMessage>> {
+        if !is_valid_id(id) || !self.path(id).exists() {
+            return Ok(None);
+        }
+        self.read_message(id.to_owned()).await.map(Some)
+    }
+
+    async fn read_message(&self, id: String) -> Result<MailboxMessage> {
+        let raw = tokio::fs::read(self.path(&id)).await?;
+        let message = MessageParser::default()
+            .parse(&raw)
+            .ok_or_else(|| eyre!("Failed to parse email {}", id))?;
+
+        Ok(MailboxMessage {
+            from: first_address(message.from()),
+            to: first_address(message.to()),
+            subject: message.subject().unwrap_or_default().to_owned(),
+            date: message
+                .date()
+                .map(|date| date.to_rfc3339())
+                .unwrap_or_default(),
+            text_body: message.body_text(0).unwrap_or_default().into_owned(),
+            html_body: message.body_html(0).unwrap_or_default().into_owned(),
+            id,
+        })
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.directory.join(format!("{}.eml", id))
+    }
+}
+
+// Ids are only ever made of these, so anything else can't name a message,
+// nor escape the directory
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn first_address(address: Option<&Address>) -> String {
+    address
+        .and_then(|address| address.first())
+        .and_then(|addr| addr.address())
+        .unwrap_or_default()
+        .to_owned()
+}
+
+#[async_trait::async_trait]
+impl EmailClient for FileEmailClient {
+    #[tracing::instrument(name = "Writing email to file", skip_all)]
+    async fn send_email(
+        &self,
+        idempotency_key: &str,
+        recipient: &Email,
+        subject: &str,
+        html_body: &str,
+        text_body: &str,
+    ) -> Result<()> {
+        let message = build_message(
+            &self.sender,
+            idempotency_key,
+            recipient,
+            subject,
+            html_body,
+            text_body,
+        )?;
+
+        let sent_at = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        let id =
+            format!("{:020}-{}", sent_at.as_nanos(), Uuid::new_v4().simple());
+
+        // Renamed into place once written, so a half-written file is never
+        // listed
+        tokio::fs::create_dir_all(&self.directory).await?;
+        let tmp_path = self.directory.join(format!(".{}.tmp", id));
+        tokio::fs::write(&tmp_path, message.formatted()).await?;
+        tokio::fs::rename(&tmp_path, self.path(&id)).await?;
+
+        tracing::info!("Wrote email to {}", self.path(&id).display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+
+    fn email(address: &str) -> Email {
+        Email::parse(Secret::new(address.to_owned())).unwrap()
+    }
+
+    fn email_client() -> FileEmailClient {
+        FileEmailClient::new(
+            std::env::temp_dir().join(Uuid::new_v4().to_string()),
+            email("sender@example.com"),
+        )
+    }
+
+    #[tokio::test]
+    async fn send_email_writes_a_readable_eml_file() {
+        let client = email_client();
+
+        client
+            .send_email(
+                "message-1",
+                &email("user@example.com"),
+                "Subject",
+                "<p>Hello there</p>",
+                "Hello there",
+            )
+            .await
+            .unwrap();
+
+        let mut entries = std::fs::read_dir(&client.directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 1);
+        let file_name = entries.pop().unwrap();
+        assert!(file_name.ends_with(".eml"));
+        let raw =
+            std::fs::read_to_string(client.directory.join(&file_name)).unwrap();
+        assert!(raw.contains("From: sender@example.com"));
+        assert!(raw.contains("To: user@example.com"));
+        assert!(raw.contains("Content-Type: multipart/alternative"));
+        assert!(raw.contains("Message-ID: <message-1@example.com>"));
+
+        let messages = client.list_messages().await.unwrap();
+        assert_eq!(messages.len(), 1);
+        let message = &messages[0];
+        assert_eq!(format!("{}.eml", message.id), file_name);
+        assert_eq!(message.from, "sender@example.com");
+        assert_eq!(message.to, "user@example.com");
+        assert_eq!(message.subject, "Subject");
+        assert!(!message.date.is_empty());
+        assert_eq!(message.text_body.trim_end(), "Hello there");
+        assert_eq!(message.html_body.trim_end(), "<p>Hello there</p>");
+
+        std::fs::remove_dir_all(&client.directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn list_messages_returns_the_newest_first() {
+        let client = email_client();
+        for subject in ["First", "Second", "Third"] {
+            client
+                .send_email(
+                    subject,
+                    &email("user@example.com"),
+                    subject,
+                    "",
+                    "",
+                )
+                .await
+                .unwrap();
+        }
+
+        let subjects = client
+            .list_messages()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|message| message.subject)
+            .collect::<Vec<_>>();
+        assert_eq!(subjects, ["Third", "Second", "First"]);
+
+        std::fs::remove_dir_all(&client.directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn list_messages_is_empty_before_anything_is_sent() {
+        let client = email_client();
+
+        assert!(client.list_messages().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_message_rejects_ids_outside_the_directory() {
+        let client = email_client();
+        client
+            .send_email(
+                "message-1",
+                &email("user@example.com"),
+                "Subject",
+                "",
+                "",
+            )
+            .await
+            .unwrap();
+        let id = client.list_messages().await.unwrap().remove(0).id;
+
+        assert!(client.get_message(&id).await.unwrap().is_some());
+        assert!(client.get_message("unknown").await.unwrap().is_none());
+        assert!(client
+            .get_message(&format!("../{}", id))
+            .await
+            .unwrap()
+            .is_none());
+
+        std::fs::remove_dir_all(&client.directory).unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+use color_eyre::eyre::Result;
+use reqwest::{Client, Url};
+
+use super::range_file_breached_password_checker::{hash_range, range_contains};
+use crate::domain::{BreachedPasswordChecker, Password};
+
+// Looks passwords up with the Pwned Passwords range API. Only the first 5
+// characters of the password's SHA-1 are sent (k-anonymity), and padding is
+// asked for so the response size doesn't give the range away either
+pub struct HibpBreachedPasswordChecker {
+    http_client: Client,
+    base_url: String,
+}
+
+impl HibpBreachedPasswordChecker {
+    pub fn new(base_url: String, http_client: Client) -> Self {
+        Self {
+            http_client,
+            base_url,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BreachedPasswordChecker for HibpBreachedPasswordChecker {
+    #[tracing::instrument(name = "Checking password with HIBP", skip_all)]
+    async fn is_breached(&self, password: &Password) -> Result<bool> {
+        let (prefix, suffix) = hash_range(password);
+        let url =
+            Url::parse(&self.base_url)?.join(&format!("range/{}", prefix))?;
+
+        let range = self
+            .http_client
+            .get(url)
+            .header(ADD_PADDING_HEADER, "true")
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        Ok(range_contains(&range, &suffix))
+    }
+}
+
+const ADD_PADDING_HEADER: &str = "Add-Padding";
+const USER_AGENT: &str = "auth-service";
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::utils::constants::test;
+
+    fn password() -> Password {
+        Password::parse(Secret::new("password123".to_owned())).unwrap()
+    }
+
+    fn checker(base_url: String) -> HibpBreachedPasswordChecker {
+        let http_client = Client::builder()
+            .timeout(test::breached_passwords::TIMEOUT)
+            .build()
+            .unwrap();
+        HibpBreachedPasswordChecker::new(base_url, http_client)
+    }
+
+    #[tokio::test]
+    async fn is_breached_sends_only_the_prefix_and_asks_for_padding() {
+        let mock_server = MockServer::start().await;
+        let checker = checker(mock_server.uri());
+
+        Mock::given(method("GET"))
+            .and(path("/range/CBFDA"))
+            .and(header("Add-Padding", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "0018A45C4D1DEF81644B54AB7F969B88D65:0\r\n\
+                 C6008F9CAB4083784CBD1874F76618D2A97:2254650\r\n",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert!(checker.is_breached(&password()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn is_breached_is_false_if_the_suffix_is_not_in_the_range() {
+        let mock_server = MockServer::start().await;
+        let checker = checker(mock_server.uri());
+
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(
+                    "C6008F9CAB4083784CBD1874F76618D2A97:0\r\n",
+                ),
+            )
+            .mount(&mock_server)
+            .await;
+
+        assert!(!checker.is_breached(&password()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn is_breached_fails_if_the_api_returns_an_error() {
+        let mock_server = MockServer::start().await;
+        let checker = checker(mock_server.uri());
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&mock_server)
+            .await;
+
+        assert!(checker.is_breached(&password()).await.is_err());
+    }
+}
@@ -0,0 +1,24 @@
+use color_eyre::eyre::Result;
+use tokio::sync::RwLock;
+
+use crate::domain::{AuditEvent, AuditSink};
+
+// Keeps events in memory, so tests can check what was recorded
+#[derive(Default)]
+pub struct InMemoryAuditSink {
+    events: RwLock<Vec<AuditEvent>>,
+}
+
+impl InMemoryAuditSink {
+    pub async fn events(&self) -> Vec<AuditEvent> {
+        self.events.read().await.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditSink for InMemoryAuditSink {
+    async fn record(&self, event: AuditEvent) -> Result<()> {
+        self.events.write().await.push(event);
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use color_eyre::eyre::Result;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+    sync::Mutex,
+};
+
+use crate::domain::{AuditEvent, AuditSink};
+
+// Appends each event to a file as a line of JSON, for shipping to a log
+// pipeline
+pub struct JsonLinesAuditSink {
+    file: Mutex<File>,
+}
+
+impl JsonLinesAuditSink {
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditSink for JsonLinesAuditSink {
+    #[tracing::instrument(name = "Writing audit event to file", skip_all)]
+    async fn record(&self, event: AuditEvent) -> Result<()> {
+        let mut line = serde_json::to_vec(&event)?;
+        line.push(b'\n');
+
+        // Held across the write, so lines from concurrent requests don't
+        // interleave
+        let mut file = self.file.lock().await;
+        file.write_all(&line).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AuditAction, AuditOutcome};
+
+    fn get_test_event(action: AuditAction) -> AuditEvent {
+        AuditEvent {
+            timestamp: 1,
+            action,
+            outcome: AuditOutcome::Success,
+            actor: Some("test@example.com".to_owned()),
+            target: Some("test@example.com".to_owned()),
+            ip: Some("127.0.0.1".to_owned()),
+            request_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_appends_one_line_per_event() {
+        let path = std::env::temp_dir()
+            .join(format!("audit-{}.jsonl", uuid::Uuid::new_v4()));
+        let first = get_test_event(AuditAction::Login);
+        let second = get_test_event(AuditAction::Logout);
+
+        let sink = JsonLinesAuditSink::open(&path).await.unwrap();
+        sink.record(first.clone()).await.unwrap();
+        drop(sink);
+        // Reopening keeps what was already written
+        let sink = JsonLinesAuditSink::open(&path).await.unwrap();
+        sink.record(second.clone()).await.unwrap();
+
+        let contents = tokio::fs::read_to_string(&path).await.unwrap();
+        let events: Vec<AuditEvent> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events, vec![first, second]);
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+}
@@ -8,15 +8,17 @@ pub struct MockEmailClient;
 impl EmailClient for MockEmailClient {
     async fn send_email(
         &self,
+        _idempotency_key: &str,
         recipient: &Email,
         subject: &str,
-        content: &str,
+        _html_body: &str,
+        text_body: &str,
     ) -> Result<()> {
         tracing::info!(
             "Sending email to {} with subject: {} and content: {}",
             recipient.as_ref().expose_secret(),
             subject,
-            content
+            text_body
         );
         Ok(())
     }
@@ -1,3 +1,23 @@
+pub mod argon2_password_hasher;
+pub mod bcrypt_password_hasher;
+pub mod circuit_breaker;
 pub mod data_stores;
+pub mod email_outbox;
+pub mod fail_open_breached_password_checker;
+pub mod failover_email_client;
+pub mod file_email_client;
+pub mod hibp_breached_password_checker;
+pub mod in_memory_audit_sink;
+pub mod json_lines_audit_sink;
 pub mod mock_email_client;
+pub mod no_op_breached_password_checker;
+pub mod no_op_claims_enricher;
+pub mod password_hashers;
+pub mod pbkdf2_password_hasher;
+pub mod postgres_audit_sink;
 pub mod postmark_email_client;
+pub mod range_file_breached_password_checker;
+pub mod retry_policy;
+pub mod scrypt_password_hasher;
+pub mod smtp_email_client;
+pub mod webhook_dispatcher;
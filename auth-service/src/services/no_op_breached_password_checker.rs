@@ -0,0 +1,13 @@
+use color_eyre::eyre::Result;
+
+use crate::domain::{BreachedPasswordChecker, Password};
+
+#[derive(Default)]
+pub struct NoOpBreachedPasswordChecker;
+
+#[async_trait::async_trait]
+impl BreachedPasswordChecker for NoOpBreachedPasswordChecker {
+    async fn is_breached(&self, _password: &Password) -> Result<bool> {
+        Ok(false)
+    }
+}
@@ -0,0 +1,18 @@
+use color_eyre::eyre::Result;
+use serde_json::{Map, Value};
+
+use crate::domain::{ClaimsEnricher, Email};
+
+#[derive(Default)]
+pub struct NoOpClaimsEnricher;
+
+#[async_trait::async_trait]
+impl ClaimsEnricher for NoOpClaimsEnricher {
+    async fn enrich(
+        &self,
+        _email: &Email,
+        _two_fa_verified: bool,
+    ) -> Result<Map<String, Value>> {
+        Ok(Map::new())
+    }
+}
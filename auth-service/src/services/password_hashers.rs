@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::{eyre, Result};
+use secrecy::{ExposeSecret, Secret};
+
+use super::{
+    argon2_password_hasher::Argon2PasswordHasher,
+    bcrypt_password_hasher::BcryptPasswordHasher,
+    pbkdf2_password_hasher::Pbkdf2PasswordHasher,
+    scrypt_password_hasher::ScryptPasswordHasher,
+};
+use crate::domain::PasswordHasher;
+
+// New hashes are always the preferred Argon2id. Legacy hashers only verify,
+// for hashes imported from other systems, which are replaced with Argon2id
+// once their user signs in
+#[derive(Clone)]
+pub struct PasswordHashers {
+    preferred: Argon2PasswordHasher,
+    legacy: Vec<Arc<dyn PasswordHasher>>,
+}
+
+impl PasswordHashers {
+    pub fn new(
+        preferred: Argon2PasswordHasher,
+        legacy: Vec<Arc<dyn PasswordHasher>>,
+    ) -> Self {
+        Self { preferred, legacy }
+    }
+
+    // bcrypt, PBKDF2 and scrypt, as well as Argon2
+    pub fn with_legacy_formats(preferred: Argon2PasswordHasher) -> Self {
+        Self::new(
+            preferred,
+            vec![
+                Arc::new(BcryptPasswordHasher),
+                Arc::new(Pbkdf2PasswordHasher),
+                Arc::new(ScryptPasswordHasher),
+            ],
+        )
+    }
+
+    // Client secrets are only ever generated here, so they can only be
+    // Argon2, and a hash in any other format is refused
+    pub fn for_client_secrets() -> Self {
+        Self::new(Argon2PasswordHasher::default(), Vec::new())
+    }
+
+    pub fn recognises(&self, password_hash: &str) -> bool {
+        self.hasher_for(password_hash).is_some()
+    }
+
+    pub fn hash(&self, password: &Secret<String>) -> Result<Secret<String>> {
+        self.preferred.hash(password)
+    }
+
+    pub fn verify(
+        &self,
+        password_hash: &Secret<String>,
+        password: &Secret<String>,
+    ) -> Result<()> {
+        self.hasher_for(password_hash.expose_secret())
+            .ok_or_else(|| eyre!("Unrecognised password hash format"))?
+            .verify(password_hash, password)
+    }
+
+    // Any hash that isn't the preferred Argon2id, with at least its cost
+    pub fn needs_rehash(&self, password_hash: &Secret<String>) -> Result<bool> {
+        if self.preferred.recognises(password_hash.expose_secret()) {
+            self.preferred.needs_rehash(password_hash)
+        } else {
+            Ok(true)
+        }
+    }
+
+    fn hasher_for(&self, password_hash: &str) -> Option<&dyn PasswordHasher> {
+        std::iter::once(&self.preferred as &dyn PasswordHasher)
+            .chain(self.legacy.iter().map(|hasher| hasher.as_ref()))
+            .find(|hasher| hasher.recognises(password_hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use argon2::Params;
+
+    use super::*;
+
+    const BCRYPT_HASH: &str =
+        "$2b$04$saltsaltsaltsaltsalt1uShJHmKw.Ovu4GAyULDoQiKPbIPiYlz2";
+
+    fn secret(s: &str) -> Secret<String> {
+        Secret::new(s.to_owned())
+    }
+
+    fn hashers() -> PasswordHashers {
+        PasswordHashers::with_legacy_formats(Argon2PasswordHasher::new(
+            Params::new(4096, 2, 1, None).unwrap(),
+            Vec::new(),
+        ))
+    }
+
+    #[test]
+    fn test_new_hashes_are_argon2id_and_current() {
+        let hashers = hashers();
+        let password_hash = hashers.hash(&secret("password123")).unwrap();
+
+        assert!(password_hash.expose_secret().starts_with("$argon2id$"));
+        assert!(hashers
+            .verify(&password_hash, &secret("password123"))
+            .is_ok());
+        assert!(!hashers.needs_rehash(&password_hash).unwrap());
+    }
+
+    #[test]
+    fn test_legacy_hashes_verify_and_need_rehashing() {
+        let hashers = hashers();
+        let password_hash = secret(BCRYPT_HASH);
+
+        assert!(hashers.recognises(BCRYPT_HASH));
+        assert!(hashers
+            .verify(&password_hash, &secret("password123"))
+            .is_ok());
+        assert!(hashers
+            .verify(&password_hash, &secret("password124"))
+            .is_err());
+        assert!(hashers.needs_rehash(&password_hash).unwrap());
+    }
+
+    #[test]
+    fn test_only_configured_formats_are_recognised() {
+        let argon2_only = PasswordHashers::new(
+            Argon2PasswordHasher::new(
+                Params::new(4096, 2, 1, None).unwrap(),
+                Vec::new(),
+            ),
+            Vec::new(),
+        );
+
+        assert!(!argon2_only.recognises(BCRYPT_HASH));
+        assert!(argon2_only
+            .verify(&secret(BCRYPT_HASH), &secret("password123"))
+            .is_err());
+        assert!(!hashers().recognises("$1$md5crypt$hash"));
+    }
+
+    #[test]
+    fn test_client_secrets_are_argon2_only() {
+        let hashers = PasswordHashers::for_client_secrets();
+        let password_hash = hashers.hash(&secret("secret")).unwrap();
+
+        assert!(hashers.verify(&password_hash, &secret("secret")).is_ok());
+        assert!(!hashers.recognises(BCRYPT_HASH));
+        assert!(hashers
+            .verify(&secret(BCRYPT_HASH), &secret("password123"))
+            .is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use argon2::password_hash::{Output, PasswordHash, PasswordVerifier};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use color_eyre::eyre::{eyre, Context, Result};
+use pbkdf2::{pbkdf2_hmac, Pbkdf2};
+use secrecy::{ExposeSecret, Secret};
+use sha1::Sha1;
+use sha2::Sha256;
+
+use crate::domain::PasswordHasher;
+
+// Reads PBKDF2 hashes in PHC format, e.g. `$pbkdf2-sha256$i=600000,l=32$...`,
+// and Django's, e.g. `pbkdf2_sha256$600000$salt$hash`
+#[derive(Debug, Default, Clone)]
+pub struct Pbkdf2PasswordHasher;
+
+const PHC_PREFIXES: [&str; 3] =
+    ["$pbkdf2$", "$pbkdf2-sha256$", "$pbkdf2-sha512$"];
+const DJANGO_PREFIXES: [&str; 2] = ["pbkdf2_sha256$", "pbkdf2_sha1$"];
+
+impl PasswordHasher for Pbkdf2PasswordHasher {
+    fn recognises(&self, password_hash: &str) -> bool {
+        PHC_PREFIXES
+            .iter()
+            .chain(&DJANGO_PREFIXES)
+            .any(|prefix| password_hash.starts_with(prefix))
+    }
+
+    fn verify(
+        &self,
+        password_hash: &Secret<String>,
+        password: &Secret<String>,
+    ) -> Result<()> {
+        let password_hash = password_hash.expose_secret();
+        let password = password.expose_secret().as_bytes();
+        if password_hash.starts_with('$') {
+            let password_hash = PasswordHash::new(password_hash)?;
+            Pbkdf2
+                .verify_password(password, &password_hash)
+                .wrap_err("failed to verify password hash")
+        } else {
+            verify_django(password_hash, password)
+        }
+    }
+}
+
+fn verify_django(password_hash: &str, password: &[u8]) -> Result<()> {
+    let malformed = || eyre!("Malformed Django PBKDF2 hash");
+    let [algorithm, iterations, salt, expected] = password_hash
+        .split('$')
+        .collect::<Vec<_>>()
+        .try_into()
+        .map_err(|_| malformed())?;
+    let iterations = iterations.parse::<u32>().map_err(|_| malformed())?;
+    let expected = STANDARD.decode(expected)?;
+
+    let mut actual = vec![0u8; expected.len()];
+    match algorithm {
+        "pbkdf2_sha256" => pbkdf2_hmac::<Sha256>(
+            password,
+            salt.as_bytes(),
+            iterations,
+            &mut actual,
+        ),
+        "pbkdf2_sha1" => pbkdf2_hmac::<Sha1>(
+            password,
+            salt.as_bytes(),
+            iterations,
+            &mut actual,
+        ),
+        _ => return Err(malformed()),
+    }
+
+    // Compared in constant time
+    if Output::new(&actual)? == Output::new(&expected)? {
+        Ok(())
+    } else {
+        Err(eyre!("Password does not match PBKDF2 hash"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use argon2::password_hash::{PasswordHasher as _, SaltString};
+    use pbkdf2::{Algorithm, Params};
+
+    use super::*;
+
+    fn verify(password_hash: &str, password: &str) -> Result<()> {
+        Pbkdf2PasswordHasher.verify(
+            &Secret::new(password_hash.to_owned()),
+            &Secret::new(password.to_owned()),
+        )
+    }
+
+    #[test]
+    fn test_verifies_django_hashes() {
+        for password_hash in [
+            "pbkdf2_sha256$1000$seasalt123$YzR5A63TtcprNwkafqZfuJ2ymncmHl07IsPj6CMT4gg=",
+            "pbkdf2_sha1$1000$seasalt123$0bKFUvhzfv3zx/VQrGNej5J0kKo=",
+        ] {
+            assert!(Pbkdf2PasswordHasher.recognises(password_hash));
+            assert!(verify(password_hash, "password123").is_ok());
+            assert!(verify(password_hash, "password124").is_err());
+        }
+    }
+
+    #[test]
+    fn test_verifies_phc_hashes() {
+        let salt = SaltString::generate(&mut rand::thread_rng());
+        for algorithm in [Algorithm::Pbkdf2Sha256, Algorithm::Pbkdf2Sha512] {
+            let params = Params {
+                rounds: 1000,
+                output_length: 32,
+            };
+            let password_hash = Pbkdf2
+                .hash_password_customized(
+                    b"password123",
+                    Some(algorithm.ident()),
+                    None,
+                    params,
+                    &salt,
+                )
+                .unwrap()
+                .to_string();
+
+            assert!(Pbkdf2PasswordHasher.recognises(&password_hash));
+            assert!(verify(&password_hash, "password123").is_ok());
+            assert!(verify(&password_hash, "password124").is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_malformed_django_hashes() {
+        for password_hash in [
+            "pbkdf2_sha256$many$seasalt123$YzR5A63TtcprNwkafqZfuJ2ymncmHl07IsPj6CMT4gg=",
+            "pbkdf2_sha256$1000$YzR5A63TtcprNwkafqZfuJ2ymncmHl07IsPj6CMT4gg=",
+            "pbkdf2_md5$1000$seasalt123$YzR5A63TtcprNwkafqZfuJ2ymncmHl07IsPj6CMT4gg=",
+        ] {
+            assert!(verify(password_hash, "password123").is_err());
+        }
+    }
+}
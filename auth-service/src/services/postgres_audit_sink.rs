@@ -0,0 +1,41 @@
+use color_eyre::eyre::Result;
+use sqlx::PgPool;
+
+use crate::domain::{AuditEvent, AuditSink};
+
+// Writes to the audit_events table, which refuses updates and deletes
+pub struct PostgresAuditSink {
+    pool: PgPool,
+}
+
+impl PostgresAuditSink {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditSink for PostgresAuditSink {
+    #[tracing::instrument(name = "Adding audit event to PostgreSQL", skip_all)]
+    async fn record(&self, event: AuditEvent) -> Result<()> {
+        let created_at = i64::try_from(event.timestamp)?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO audit_events
+                (created_at, action, outcome, actor, target, ip, request_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            created_at,
+            event.action.as_ref(),
+            event.outcome.as_ref(),
+            event.actor,
+            event.target,
+            event.ip,
+            event.request_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -32,9 +32,11 @@ impl EmailClient for PostmarkEmailClient {
     #[tracing::instrument(name = "Sending email", skip_all)] 
     async fn send_email(
         &self,
+        idempotency_key: &str,
         recipient: &Email,
         subject: &str,
-        content: &str,
+        html_body: &str,
+        text_body: &str,
     ) -> Result<()> {
         let base = Url::parse(&self.base_url)?;
         let url = base.join("/email")?;
@@ -43,9 +45,10 @@ impl EmailClient for PostmarkEmailClient {
             from: self.sender.as_ref().expose_secret(),
             to: recipient.as_ref().expose_secret(),
             subject,
-            html_body: content,
-            text_body: content,
+            html_body,
+            text_body,
             message_stream: MESSAGE_STREAM,
+            metadata: Metadata { idempotency_key },
         };
 
         let request = self
@@ -77,6 +80,14 @@ struct SendEmailRequest<'a> {
     html_body: &'a str,
     text_body: &'a str,
     message_stream: &'a str,
+    metadata: Metadata<'a>,
+}
+
+// Postmark has no idempotency keys of its own, so the key is recorded as
+// metadata, which shows up in its activity feed and webhooks
+#[derive(serde::Serialize, Debug)]
+struct Metadata<'a> {
+    idempotency_key: &'a str,
 }
 
 #[cfg(test)]
@@ -135,6 +146,7 @@ mod tests {
                     && body.get("HtmlBody").is_some()
                     && body.get("TextBody").is_some()
                     && body.get("MessageStream").is_some()
+                    && body["Metadata"]["idempotency_key"] == "message-1"
             } else {
                 false
             }
@@ -160,7 +172,13 @@ mod tests {
 
         // Execute the send_email function and check the outcome
         let outcome = email_client
-            .send_email(&email(), &subject(), &content())
+            .send_email(
+                "message-1",
+                &email(),
+                &subject(),
+                &content(),
+                &content(),
+            )
             .await;
 
         assert!(outcome.is_ok());
@@ -181,7 +199,13 @@ mod tests {
 
         // Execute the send_email function and check the outcome
         let outcome = email_client
-            .send_email(&email(), &subject(), &content())
+            .send_email(
+                "message-1",
+                &email(),
+                &subject(),
+                &content(),
+                &content(),
+            )
             .await;
 
         assert!(outcome.is_err());
@@ -204,7 +228,13 @@ mod tests {
 
         // Execute the send_email function and check the outcome
         let outcome = email_client
-            .send_email(&email(), &subject(), &content())
+            .send_email(
+                "message-1",
+                &email(),
+                &subject(),
+                &content(),
+                &content(),
+            )
             .await;
 
         assert!(outcome.is_err());
@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use color_eyre::eyre::Result;
+use secrecy::ExposeSecret;
+use sha1::{Digest, Sha1};
+
+use crate::domain::{BreachedPasswordChecker, Password};
+
+// Looks passwords up in a local copy of the Pwned Passwords dataset, split
+// into one file per 5 character SHA-1 prefix (`{directory}/{PREFIX}.txt`) in
+// the same `SUFFIX:COUNT` format as the range API. Only the file for the
+// password's prefix is read
+pub struct RangeFileBreachedPasswordChecker {
+    directory: PathBuf,
+}
+
+impl RangeFileBreachedPasswordChecker {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+}
+
+#[async_trait::async_trait]
+impl BreachedPasswordChecker for RangeFileBreachedPasswordChecker {
+    #[tracing::instrument(
+        name = "Checking password against range files",
+        skip_all
+    )]
+    async fn is_breached(&self, password: &Password) -> Result<bool> {
+        let (prefix, suffix) = hash_range(password);
+        let path = self.directory.join(format!("{}.txt", prefix));
+
+        let range = match tokio::fs::read_to_string(&path).await {
+            Ok(range) => range,
+            // A partial dataset has no file for prefixes it doesn't cover
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(false)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(range_contains(&range, &suffix))
+    }
+}
+
+// The uppercase hex SHA-1 of the password, split into the 5 character prefix
+// that is looked up and the rest, which is matched within that range
+pub fn hash_range(password: &Password) -> (String, String) {
+    let hash = hex::encode_upper(Sha1::digest(
+        password.as_ref().expose_secret().as_bytes(),
+    ));
+    let (prefix, suffix) = hash.split_at(5);
+    (prefix.to_owned(), suffix.to_owned())
+}
+
+// Whether a range lists the suffix. Padding entries, with a count of 0,
+// don't count
+pub fn range_contains(range: &str, suffix: &str) -> bool {
+    range.lines().any(|line| {
+        let Some((line_suffix, count)) = line.trim().split_once(':') else {
+            return false;
+        };
+        line_suffix.eq_ignore_ascii_case(suffix)
+            && count.trim().parse::<u64>().is_ok_and(|count| count > 0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+    use uuid::Uuid;
+
+    use super::*;
+
+    // SHA-1 of "password123" is CBFDAC6008F9CAB4083784CBD1874F76618D2A97
+    fn password() -> Password {
+        Password::parse(Secret::new("password123".to_owned())).unwrap()
+    }
+
+    #[test]
+    fn hash_range_splits_the_uppercase_sha1() {
+        let (prefix, suffix) = hash_range(&password());
+
+        assert_eq!(prefix, "CBFDA");
+        assert_eq!(suffix, "C6008F9CAB4083784CBD1874F76618D2A97");
+    }
+
+    #[test]
+    fn range_contains_ignores_padding_and_other_suffixes() {
+        let suffix = "C6008F9CAB4083784CBD1874F76618D2A97";
+
+        assert!(range_contains(
+            "0018A45C4D1DEF81644B54AB7F969B88D65:1\r\n\
+             c6008f9cab4083784cbd1874f76618d2a97:2254650\r\n",
+            suffix
+        ));
+        assert!(!range_contains(
+            "C6008F9CAB4083784CBD1874F76618D2A97:0\r\n",
+            suffix
+        ));
+        assert!(!range_contains(
+            "0018A45C4D1DEF81644B54AB7F969B88D65:1\r\n",
+            suffix
+        ));
+    }
+
+    #[tokio::test]
+    async fn is_breached_reads_the_file_for_the_prefix() {
+        let directory = std::env::temp_dir().join(Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&directory).unwrap();
+        let checker = RangeFileBreachedPasswordChecker::new(directory.clone());
+
+        assert!(!checker.is_breached(&password()).await.unwrap());
+
+        std::fs::write(
+            directory.join("CBFDA.txt"),
+            "C6008F9CAB4083784CBD1874F76618D2A97:2254650\n",
+        )
+        .unwrap();
+        assert!(checker.is_breached(&password()).await.unwrap());
+
+        let other =
+            Password::parse(Secret::new("a much longer one".to_owned()))
+                .unwrap();
+        assert!(!checker.is_breached(&other).await.unwrap());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
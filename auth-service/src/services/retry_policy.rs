@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+// How background workers retry: up to max_attempts tries, waiting
+// base_delay after the first failure and doubling it after each one after.
+// Workers also wake up every poll_interval to look for due retries
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub poll_interval: Duration,
+}
+
+impl RetryPolicy {
+    pub fn delay_after(&self, attempts: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_doubles_after_each_attempt() {
+        let retry_policy = RetryPolicy {
+            max_attempts: 8,
+            base_delay: Duration::from_secs(30),
+            poll_interval: Duration::from_secs(5),
+        };
+
+        assert_eq!(retry_policy.delay_after(1), Duration::from_secs(30));
+        assert_eq!(retry_policy.delay_after(2), Duration::from_secs(60));
+        assert_eq!(retry_policy.delay_after(4), Duration::from_secs(240));
+    }
+}
@@ -0,0 +1,64 @@
+use argon2::password_hash::{PasswordHash, PasswordVerifier};
+use color_eyre::eyre::{Context, Result};
+use scrypt::Scrypt;
+use secrecy::{ExposeSecret, Secret};
+
+use crate::domain::PasswordHasher;
+
+// Reads scrypt hashes in PHC format, e.g. `$scrypt$ln=17,r=8,p=1$...`
+#[derive(Debug, Default, Clone)]
+pub struct ScryptPasswordHasher;
+
+impl PasswordHasher for ScryptPasswordHasher {
+    fn recognises(&self, password_hash: &str) -> bool {
+        password_hash.starts_with("$scrypt$")
+    }
+
+    fn verify(
+        &self,
+        password_hash: &Secret<String>,
+        password: &Secret<String>,
+    ) -> Result<()> {
+        let password_hash = PasswordHash::new(password_hash.expose_secret())?;
+        Scrypt
+            .verify_password(
+                password.expose_secret().as_bytes(),
+                &password_hash,
+            )
+            .wrap_err("failed to verify password hash")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use argon2::password_hash::{PasswordHasher as _, SaltString};
+    use scrypt::Params;
+
+    use super::*;
+
+    #[test]
+    fn test_verifies_scrypt_hashes() {
+        let salt = SaltString::generate(&mut rand::thread_rng());
+        let password_hash = Scrypt
+            .hash_password_customized(
+                b"password123",
+                None,
+                None,
+                Params::new(4, 8, 1, 32).unwrap(),
+                &salt,
+            )
+            .unwrap()
+            .to_string();
+
+        assert!(ScryptPasswordHasher.recognises(&password_hash));
+        for (password, matches) in
+            [("password123", true), ("password124", false)]
+        {
+            let result = ScryptPasswordHasher.verify(
+                &Secret::new(password_hash.clone()),
+                &Secret::new(password.to_owned()),
+            );
+            assert_eq!(result.is_ok(), matches);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::{sync::Arc, time::Duration};
+
+use color_eyre::eyre::{eyre, Result};
+use hmac::{Hmac, Mac};
+use reqwest::{header::CONTENT_TYPE, Client};
+use secrecy::{ExposeSecret, Secret};
+use sha2::Sha256;
+use tokio::sync::Notify;
+
+use super::retry_policy::RetryPolicy;
+use crate::{
+    app_state::WebhookStoreType,
+    domain::{WebhookDelivery, WebhookEvent, WebhookPublisher},
+    utils::{
+        auth::current_timestamp_millis,
+        constants::{
+            WEBHOOK_ID_HEADER, WEBHOOK_SIGNATURE_HEADER,
+            WEBHOOK_TIMESTAMP_HEADER,
+        },
+    },
+};
+
+// How many due deliveries are sent per pass of the worker
+const BATCH_SIZE: usize = 50;
+
+// How long a worker has to send the deliveries it claimed, long enough for
+// every endpoint to time out on the whole batch. If the worker dies, its
+// deliveries are sent by another once the claim runs out
+const CLAIM_DURATION: Duration = Duration::from_secs(10 * 60);
+
+// Queues events in the webhook store, one delivery per endpoint, and sends
+// them from a background worker, retrying failures until they're dead-lettered
+pub struct WebhookDispatcher {
+    webhook_store: WebhookStoreType,
+    endpoints: Vec<String>,
+    signing_secret: Secret<String>,
+    retry_policy: RetryPolicy,
+    http_client: Client,
+    notify: Notify,
+}
+
+impl WebhookDispatcher {
+    pub fn new(
+        webhook_store: WebhookStoreType,
+        endpoints: Vec<String>,
+        signing_secret: Secret<String>,
+        retry_policy: RetryPolicy,
+        http_client: Client,
+    ) -> Self {
+        Self {
+            webhook_store,
+            endpoints,
+            signing_secret,
+            retry_policy,
+            http_client,
+            notify: Notify::new(),
+        }
+    }
+
+    // The worker. Runs until the process exits, waking up when an event is
+    // published or a retry may be due
+    pub async fn run(self: Arc<Self>) {
+        loop {
+            if let Err(e) = self.deliver_due().await {
+                tracing::error!("Failed to deliver webhooks: {:?}", e);
+            }
+
+            tokio::select! {
+                _ = self.notify.notified() => {}
+                _ = tokio::time::sleep(self.retry_policy.poll_interval) => {}
+            }
+        }
+    }
+
+    #[tracing::instrument(name = "Delivering due webhooks", skip_all)]
+    async fn deliver_due(&self) -> Result<()> {
+        let now = current_timestamp_millis()?;
+        let claimed_until = now + u64::try_from(CLAIM_DURATION.as_millis())?;
+        let deliveries = self
+            .webhook_store
+            .write()
+            .await
+            .claim_due_deliveries(now, claimed_until, BATCH_SIZE)
+            .await
+            .map_err(|e| eyre!(e))?;
+
+        // One delivery's store error shouldn't hold up the rest of the batch,
+        // it's retried once its claim runs out
+        for delivery in deliveries {
+            let delivery_id = delivery.delivery_id.clone();
+            if let Err(e) = self.attempt(delivery).await {
+                tracing::error!(
+                    "Failed to deliver webhook {}: {:?}",
+                    delivery_id,
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn attempt(&self, mut delivery: WebhookDelivery) -> Result<()> {
+        let error = match self.send(&delivery).await {
+            Ok(()) => {
+                return self
+                    .webhook_store
+                    .write()
+                    .await
+                    .remove_delivery(&delivery.delivery_id)
+                    .await
+                    .map_err(|e| eyre!(e));
+            }
+            Err(e) => e,
+        };
+
+        let now = current_timestamp_millis()?;
+        delivery.attempts += 1;
+        delivery.last_error = Some(error.to_string());
+        let mut webhook_store = self.webhook_store.write().await;
+
+        if delivery.attempts >= self.retry_policy.max_attempts {
+            tracing::error!(
+                "Giving up on webhook {} to {}: {}",
+                delivery.delivery_id,
+                delivery.endpoint,
+                error
+            );
+            return webhook_store
+                .dead_letter(&delivery, now)
+                .await
+                .map_err(|e| eyre!(e));
+        }
+
+        let delay = self.retry_policy.delay_after(delivery.attempts);
+        delivery.next_attempt_at = now + u64::try_from(delay.as_millis())?;
+        webhook_store
+            .update_delivery(&delivery)
+            .await
+            .map_err(|e| eyre!(e))
+    }
+
+    #[tracing::instrument(name = "Sending webhook", skip_all)]
+    async fn send(&self, delivery: &WebhookDelivery) -> Result<()> {
+        let timestamp = current_timestamp_millis()? / 1000;
+        let signature =
+            sign_webhook(&self.signing_secret, timestamp, &delivery.payload)?;
+
+        self.http_client
+            .post(&delivery.endpoint)
+            .header(CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_ID_HEADER, &delivery.delivery_id)
+            .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+            .header(WEBHOOK_SIGNATURE_HEADER, signature)
+            .body(delivery.payload.clone())
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookPublisher for WebhookDispatcher {
+    #[tracing::instrument(name = "Publishing webhook event", skip_all)]
+    async fn publish(&self, event: WebhookEvent) -> Result<()> {
+        if self.endpoints.is_empty() {
+            return Ok(());
+        }
+
+        let payload = serde_json::to_string(&event)?;
+        let now = current_timestamp_millis()?;
+
+        {
+            let mut webhook_store = self.webhook_store.write().await;
+            for endpoint in &self.endpoints {
+                let delivery = WebhookDelivery::new(
+                    endpoint.clone(),
+                    payload.clone(),
+                    now,
+                );
+                webhook_store
+                    .add_delivery(delivery)
+                    .await
+                    .map_err(|e| eyre!(e))?;
+            }
+        }
+
+        self.notify.notify_one();
+        Ok(())
+    }
+}
+
+// The value of the signature header for a payload sent at the given Unix time
+pub fn sign_webhook(
+    signing_secret: &Secret<String>,
+    timestamp: u64,
+    payload: &str,
+) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(
+        signing_secret.expose_secret().as_bytes(),
+    )?;
+    mac.update(format!("{}.{}", timestamp, payload).as_bytes());
+    Ok(format!(
+        "sha256={}",
+        hex::encode(mac.finalize().into_bytes())
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_webhook() {
+        // echo -n '1700000000.{}' | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            sign_webhook(&Secret::new("secret".to_owned()), 1700000000, "{}")
+                .unwrap(),
+            "sha256=b8569b78799ff9e3cbff0fc2d63a33a2b57f3282abd07c37ae5e8e7d79a5f163"
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use crate::{
+    app_state::AppState,
+    domain::{AuditAction, AuditEvent, AuditOutcome},
+};
+
+use super::{auth::current_timestamp, client_info::ClientInfo};
+
+// Record an audit event. Failing to record one is logged, but doesn't fail
+// the request being audited
+#[tracing::instrument(name = "Auditing", skip_all)]
+pub async fn audit(
+    state: &AppState,
+    client: &ClientInfo,
+    action: AuditAction,
+    outcome: AuditOutcome,
+    actor: Option<&str>,
+    target: Option<&str>,
+) {
+    let timestamp = match current_timestamp() {
+        Ok(timestamp) => timestamp,
+        Err(e) => {
+            tracing::error!("Failed to record audit event: {:?}", e);
+            return;
+        }
+    };
+
+    let event = AuditEvent {
+        timestamp,
+        action,
+        outcome,
+        actor: actor.map(str::to_owned),
+        target: target.map(str::to_owned),
+        ip: client.ip.clone(),
+        request_id: client.request_id.clone(),
+    };
+    if let Err(e) = state.audit_sink.record(event).await {
+        tracing::error!("Failed to record audit event: {:?}", e);
+    }
+}
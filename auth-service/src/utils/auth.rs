@@ -1,21 +1,41 @@
-use axum_extra::extract::cookie::{Cookie, SameSite};
+use axum::http::{header::AUTHORIZATION, HeaderMap};
+use axum_extra::extract::{
+    cookie::{Cookie, SameSite},
+    CookieJar,
+};
 use chrono::Utc;
 use color_eyre::eyre::{eyre, Context, ContextCompat, Result};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Validation};
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
 
 use crate::{
-    app_state::BannedTokenStoreType,
-    domain::{BannedTokenStoreError, Email},
+    app_state::{
+        BannedTokenStoreType, ClaimsEnricherType, TrustedDeviceStoreType,
+    },
+    domain::{
+        AuthAPIError, BannedTokenStoreError, Email, Scopes, ServiceAccount,
+        TrustedDevice, TrustedDeviceStoreError,
+    },
 };
 
-use super::constants::{JWT_COOKIE_NAME, JWT_SECRET};
+use super::constants::{
+    JWT_AUDIENCE, JWT_COOKIE_NAME, JWT_ISSUER, JWT_SECRET,
+    SESSION_MAX_AGE_SECONDS, TOKEN_TTL_SECONDS, TRUSTED_DEVICE_COOKIE_NAME,
+    TRUSTED_DEVICE_TTL_SECONDS,
+};
 
 // Create cookie with a new JWT auth token
 #[tracing::instrument(name = "Generating auth cookie", skip_all)]
-pub fn generate_auth_cookie(email: &Email) -> Result<Cookie<'static>> {
-    let token = generate_auth_token(email)?;
+pub async fn generate_auth_cookie(
+    email: &Email,
+    two_fa_verified: bool,
+    claims_enricher: &ClaimsEnricherType,
+) -> Result<Cookie<'static>> {
+    let extra = claims_enricher.enrich(email, two_fa_verified).await?;
+    let token = generate_auth_token(email, extra)?;
     Ok(create_auth_cookie(token))
 }
 
@@ -32,17 +52,121 @@ fn create_auth_cookie(token: Secret<String>) -> Cookie<'static> {
     cookie
 }
 
-// This value determines how long the JWT auth token is valid for
-pub const TOKEN_TTL_SECONDS: i64 = 600; // 10 minutes
+// Service tokens can't be refreshed, callers just ask for a new one
+pub const SERVICE_TOKEN_TTL_SECONDS: i64 = 300; // 5 minutes
+
+// Claims set by this service, which enrichers can't override
+const REGISTERED_CLAIMS: [&str; 12] = [
+    "iss",
+    "sub",
+    "aud",
+    "exp",
+    "nbf",
+    "iat",
+    "jti",
+    "scope",
+    "auth_time",
+    "client_id",
+    "amr",
+    "session_exp",
+];
 
 // Create JWT auth token
 #[tracing::instrument(name = "Generating auth token", skip_all)]
-fn generate_auth_token(email: &Email) -> Result<Secret<String>> {
-    let delta = chrono::Duration::try_seconds(TOKEN_TTL_SECONDS)
-        .wrap_err("Failed to create 10 minute time delta")?;
+fn generate_auth_token(
+    email: &Email,
+    mut extra: Map<String, Value>,
+) -> Result<Secret<String>> {
+    extra.retain(|name, _| !REGISTERED_CLAIMS.contains(&name.as_str()));
+
+    // Record when the user signed in, for the auth_time claim of ID tokens
+    let auth_time = current_timestamp()?;
+    let mut claims = Claims {
+        auth_time: Some(auth_time),
+        extra,
+        ..new_claims(email.as_ref().expose_secret(), *TOKEN_TTL_SECONDS)?
+    };
+
+    // Sliding sessions can only be renewed until their maximum age
+    if let Some(max_age) = *SESSION_MAX_AGE_SECONDS {
+        let max_age: usize = max_age
+            .try_into()
+            .wrap_err("failed to cast SESSION_MAX_AGE_SECONDS to usize")?;
+        let session_exp = auth_time + max_age;
+        claims.exp = claims.exp.min(session_exp);
+        claims.session_exp = Some(session_exp);
+    }
+
+    create_token(&claims)
+}
+
+// Re-issue a sliding session token once it is past half its lifetime, keeping
+// its claims. Returns None when the token doesn't need renewing, isn't a
+// sliding session or has reached the session's maximum age.
+#[tracing::instrument(name = "Renewing auth cookie", skip_all)]
+pub fn renew_auth_cookie(claims: Claims) -> Result<Option<Cookie<'static>>> {
+    let Some(session_exp) = claims.session_exp else {
+        return Ok(None);
+    };
+
+    let now = current_timestamp()?;
+    let ttl: usize = (*TOKEN_TTL_SECONDS)
+        .try_into()
+        .wrap_err("failed to cast TOKEN_TTL_SECONDS to usize")?;
+    if claims.exp.saturating_sub(now) > ttl / 2 || now >= session_exp {
+        return Ok(None);
+    }
+
+    let renewed = new_claims(&claims.sub, *TOKEN_TTL_SECONDS)?;
+    let claims = Claims {
+        exp: renewed.exp.min(session_exp),
+        auth_time: claims.auth_time,
+        session_exp: Some(session_exp),
+        extra: claims.extra,
+        ..renewed
+    };
+
+    Ok(Some(create_auth_cookie(create_token(&claims)?)))
+}
+
+// Create an OAuth access token, bound to the client it was issued to
+#[tracing::instrument(name = "Generating access token", skip_all)]
+pub fn generate_access_token(
+    email: &Email,
+    audience: &str,
+    scopes: &Scopes,
+) -> Result<Secret<String>> {
+    let mut claims = Claims {
+        scope: Some(scopes.to_string()),
+        ..new_claims(email.as_ref().expose_secret(), *TOKEN_TTL_SECONDS)?
+    };
+    claims.aud.push(audience.to_owned());
+    create_token(&claims)
+}
+
+// Create a token for a service account, identified by its client_id
+#[tracing::instrument(name = "Generating service token", skip_all)]
+pub fn generate_service_token(
+    account: &ServiceAccount,
+    scopes: &Scopes,
+) -> Result<Secret<String>> {
+    let claims = Claims {
+        sub: account.client_id.clone(),
+        client_id: Some(account.client_id.clone()),
+        scope: Some(scopes.to_string()),
+        ..new_claims(&account.client_id, SERVICE_TOKEN_TTL_SECONDS)?
+    };
+    create_token(&claims)
+}
+
+#[tracing::instrument(name = "Building token claims", skip_all)]
+fn new_claims(sub: &str, ttl_seconds: i64) -> Result<Claims> {
+    let delta = chrono::Duration::try_seconds(ttl_seconds)
+        .wrap_err("Failed to create token time delta")?;
 
     // Create JWT expiration time
-    let exp = Utc::now()
+    let now = Utc::now();
+    let exp = now
         .checked_add_signed(delta)
         .ok_or(eyre!("failed to add to current time"))?
         .timestamp();
@@ -53,11 +177,38 @@ fn generate_auth_token(email: &Email) -> Result<Secret<String>> {
         exp
     ))?;
 
-    let sub = email.as_ref().expose_secret().to_owned();
+    let iat: usize = now.timestamp().try_into().wrap_err(format!(
+        "failed to cast iat time to usize. iat time: {}",
+        now.timestamp()
+    ))?;
 
-    let claims = Claims { sub, exp };
+    Ok(Claims {
+        iss: JWT_ISSUER.to_owned(),
+        sub: sub.to_owned(),
+        aud: vec![JWT_AUDIENCE.to_owned()],
+        exp,
+        nbf: iat,
+        iat,
+        jti: Uuid::new_v4().to_string(),
+        scope: None,
+        auth_time: None,
+        client_id: None,
+        session_exp: None,
+        extra: Map::new(),
+    })
+}
 
-    create_token(&claims)
+pub fn current_timestamp() -> Result<usize> {
+    let now = Utc::now().timestamp();
+    now.try_into()
+        .wrap_err(format!("failed to cast timestamp to usize: {}", now))
+}
+
+// Used by the background workers, so retries can back off by less than a second
+pub fn current_timestamp_millis() -> Result<u64> {
+    let now = Utc::now().timestamp_millis();
+    now.try_into()
+        .wrap_err(format!("failed to cast timestamp to u64: {}", now))
 }
 
 // Check if JWT auth token is valid by decoding it using the JWT secret
@@ -83,15 +234,155 @@ pub async fn validate_token(
             ),
         })?;
 
+    // Access tokens also carry the client they were issued to as an audience,
+    // which is for the resource server to check rather than us
+    let mut validation = Validation::default();
+    validation.set_issuer(&[JWT_ISSUER.as_str()]);
+    validation.set_audience(&[JWT_AUDIENCE.as_str()]);
+    validation.set_required_spec_claims(&["iss", "sub", "aud", "exp", "nbf"]);
+    validation.validate_nbf = true;
+
     decode::<Claims>(
         token.expose_secret(),
         &DecodingKey::from_secret(JWT_SECRET.expose_secret().as_bytes()),
-        &Validation::default(),
+        &validation,
     )
     .map(|data| data.claims)
     .wrap_err("failed to decode token")
 }
 
+// Trust cookies are JWTs with their own audience, so they can never be
+// mistaken for a session
+const TRUSTED_DEVICE_AUDIENCE: &str = "trusted-device";
+
+// Create a long-lived cookie naming the device a user chose to trust
+#[tracing::instrument(name = "Generating trusted device cookie", skip_all)]
+pub fn generate_trusted_device_cookie(
+    device: &TrustedDevice,
+) -> Result<Cookie<'static>> {
+    let ttl: usize = (*TRUSTED_DEVICE_TTL_SECONDS)
+        .try_into()
+        .wrap_err("failed to cast TRUSTED_DEVICE_TTL_SECONDS to usize")?;
+    let iat = current_timestamp()?;
+    let claims = TrustedDeviceClaims {
+        iss: JWT_ISSUER.to_owned(),
+        sub: device.email.as_ref().expose_secret().to_owned(),
+        aud: TRUSTED_DEVICE_AUDIENCE.to_owned(),
+        exp: iat + ttl,
+        iat,
+        device_id: device.device_id.clone(),
+    };
+
+    let token = encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &EncodingKey::from_secret(JWT_SECRET.expose_secret().as_bytes()),
+    )
+    .wrap_err("failed to create trusted device token")?;
+
+    Ok(Cookie::build((TRUSTED_DEVICE_COOKIE_NAME, token))
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::seconds(*TRUSTED_DEVICE_TTL_SECONDS))
+        .build())
+}
+
+// Check the request carries a trust cookie for one of the user's devices
+// that hasn't been revoked
+#[tracing::instrument(name = "Validating trusted device", skip_all)]
+pub async fn validate_trusted_device(
+    jar: &CookieJar,
+    email: &Email,
+    trusted_device_store: &TrustedDeviceStoreType,
+) -> Result<bool> {
+    let Some(cookie) = jar.get(TRUSTED_DEVICE_COOKIE_NAME) else {
+        return Ok(false);
+    };
+
+    let mut validation = Validation::default();
+    validation.set_issuer(&[JWT_ISSUER.as_str()]);
+    validation.set_audience(&[TRUSTED_DEVICE_AUDIENCE]);
+    let Ok(data) = decode::<TrustedDeviceClaims>(
+        cookie.value(),
+        &DecodingKey::from_secret(JWT_SECRET.expose_secret().as_bytes()),
+        &validation,
+    ) else {
+        return Ok(false);
+    };
+    if data.claims.sub != *email.as_ref().expose_secret() {
+        return Ok(false);
+    }
+
+    match trusted_device_store
+        .read()
+        .await
+        .get_device(email, &data.claims.device_id)
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(TrustedDeviceStoreError::DeviceNotFound) => Ok(false),
+        Err(e) => Err(eyre!(e)),
+    }
+}
+
+// Check the JWT cookie belongs to a browser session, returning the signed-in user.
+// Access tokens issued to OAuth clients are not accepted as sessions.
+#[tracing::instrument(name = "Validating session", skip_all)]
+pub async fn validate_session(
+    jar: &CookieJar,
+    banned_token_store: BannedTokenStoreType,
+) -> Result<Session, AuthAPIError> {
+    let cookie = jar.get(JWT_COOKIE_NAME).ok_or(AuthAPIError::MissingToken)?;
+    let token = Secret::new(cookie.value().to_owned());
+
+    let claims = validate_token(&token, banned_token_store)
+        .await
+        .map_err(|_| AuthAPIError::InvalidToken)?;
+    if claims.client_audience().is_some() {
+        return Err(AuthAPIError::InvalidToken);
+    }
+
+    let email = Email::parse(Secret::new(claims.sub))
+        .map_err(|_| AuthAPIError::InvalidToken)?;
+    let auth_time = claims.auth_time.ok_or(AuthAPIError::InvalidToken)?;
+
+    Ok(Session { email, auth_time })
+}
+
+// Check the request carries a service token granted the given scope
+#[tracing::instrument(name = "Validating service token", skip_all)]
+pub async fn validate_service_token(
+    headers: &HeaderMap,
+    banned_token_store: BannedTokenStoreType,
+    scope: &str,
+) -> Result<Claims, AuthAPIError> {
+    let token = bearer_token(headers).ok_or(AuthAPIError::MissingToken)?;
+
+    let claims = validate_token(&token, banned_token_store)
+        .await
+        .map_err(|_| AuthAPIError::InvalidToken)?;
+    if claims.client_id.is_none() {
+        return Err(AuthAPIError::InvalidToken);
+    }
+
+    let scopes = Scopes::parse(claims.scope.as_deref().unwrap_or_default())
+        .map_err(|_| AuthAPIError::InvalidToken)?;
+    if !scopes.contains(scope) {
+        return Err(AuthAPIError::InsufficientScope);
+    }
+
+    Ok(claims)
+}
+
+pub fn bearer_token(headers: &HeaderMap) -> Option<Secret<String>> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| Secret::new(token.to_owned()))
+}
+
 // Create JWT auth token by encoding claims using the JWT secret
 #[tracing::instrument(name = "Creating auth token", skip_all)]
 fn create_token(claims: &Claims) -> Result<Secret<String>> {
@@ -107,15 +398,63 @@ fn create_token(claims: &Claims) -> Result<Secret<String>> {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
+    pub iss: String,
     pub sub: String,
+    pub aud: Vec<String>,
     pub exp: usize,
+    pub nbf: usize,
+    pub iat: usize,
+    pub jti: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<usize>,
+    // Only set on service tokens, see RFC 9068 section 2.2
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    // When a sliding session must end, however recently it was renewed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_exp: Option<usize>,
+    // Anything added by the ClaimsEnricher
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Claims {
+    // The OAuth client an access token was issued to, if any
+    pub fn client_audience(&self) -> Option<&str> {
+        self.aud
+            .iter()
+            .map(String::as_str)
+            .find(|aud| *aud != JWT_AUDIENCE.as_str())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TrustedDeviceClaims {
+    iss: String,
+    sub: String,
+    aud: String,
+    exp: usize,
+    iat: usize,
+    device_id: String,
+}
+
+// The signed-in user behind a browser session
+#[derive(Debug)]
+pub struct Session {
+    pub email: Email,
+    pub auth_time: usize,
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
         domain::BannedTokenStore,
-        services::data_stores::HashsetBannedTokenStore,
+        services::{
+            data_stores::HashsetBannedTokenStore,
+            no_op_claims_enricher::NoOpClaimsEnricher,
+        },
     };
     use secrecy::Secret;
     use std::sync::Arc;
@@ -127,7 +466,10 @@ mod tests {
     async fn test_generate_auth_cookie() {
         let email =
             Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
-        let cookie = generate_auth_cookie(&email).unwrap();
+        let claims_enricher: ClaimsEnricherType = Arc::new(NoOpClaimsEnricher);
+        let cookie = generate_auth_cookie(&email, false, &claims_enricher)
+            .await
+            .unwrap();
         assert_eq!(cookie.name(), JWT_COOKIE_NAME);
         assert_eq!(cookie.value().split('.').count(), 3);
         assert_eq!(cookie.path(), Some("/"));
@@ -150,7 +492,7 @@ mod tests {
     async fn test_generate_auth_token() {
         let email =
             Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
-        let result = generate_auth_token(&email).unwrap();
+        let result = generate_auth_token(&email, Map::new()).unwrap();
         assert_eq!(result.expose_secret().split('.').count(), 3);
     }
 
@@ -158,7 +500,7 @@ mod tests {
     async fn test_validate_token_with_valid_token() {
         let email =
             Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
-        let token = generate_auth_token(&email).unwrap();
+        let token = generate_auth_token(&email, Map::new()).unwrap();
         let banned_token_store =
             Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
         let result = validate_token(&token, banned_token_store).await.unwrap();
@@ -172,6 +514,154 @@ mod tests {
             .timestamp();
 
         assert!(result.exp > exp as usize);
+        assert!(result.iat < result.exp);
+    }
+
+    #[tokio::test]
+    async fn test_tokens_have_unique_ids() {
+        let email =
+            Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let banned_token_store =
+            Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+
+        let first = generate_auth_token(&email, Map::new()).unwrap();
+        let second = generate_auth_token(&email, Map::new()).unwrap();
+        let first = validate_token(&first, banned_token_store.clone())
+            .await
+            .unwrap();
+        let second = validate_token(&second, banned_token_store).await.unwrap();
+        assert_ne!(first.jti, second.jti);
+    }
+
+    #[tokio::test]
+    async fn test_validate_token_with_access_token() {
+        let email =
+            Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let scopes = Scopes::parse("read write").unwrap();
+        let token = generate_access_token(&email, "client", &scopes).unwrap();
+        let banned_token_store =
+            Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+        let result = validate_token(&token, banned_token_store).await.unwrap();
+        assert_eq!(result.sub, "test@example.com");
+        assert_eq!(result.aud, vec![JWT_AUDIENCE.as_str(), "client"]);
+        assert_eq!(result.client_audience(), Some("client"));
+        assert_eq!(result.scope.as_deref(), Some("read write"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_session_rejects_access_token() {
+        let email =
+            Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let token = generate_access_token(&email, "client", &Scopes::default())
+            .unwrap();
+        let jar = CookieJar::new().add(create_auth_cookie(token));
+        let banned_token_store =
+            Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+        assert!(matches!(
+            validate_session(&jar, banned_token_store).await,
+            Err(AuthAPIError::InvalidToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_validate_token_rejects_wrong_issuer_and_audience() {
+        let banned_token_store =
+            Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+
+        let mut claims = new_claims("test@example.com", 60).unwrap();
+        claims.iss = "https://other.example.com".to_owned();
+        let token = create_token(&claims).unwrap();
+        assert!(validate_token(&token, banned_token_store.clone())
+            .await
+            .is_err());
+
+        let mut claims = new_claims("test@example.com", 60).unwrap();
+        claims.aud = vec!["other-service".to_owned()];
+        let token = create_token(&claims).unwrap();
+        assert!(validate_token(&token, banned_token_store).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_token_rejects_token_not_yet_valid() {
+        let banned_token_store =
+            Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+
+        let mut claims = new_claims("test@example.com", 600).unwrap();
+        claims.nbf += 300;
+        let token = create_token(&claims).unwrap();
+        assert!(validate_token(&token, banned_token_store).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_generate_auth_token_keeps_registered_claims() {
+        let email =
+            Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let mut extra = Map::new();
+        extra.insert("roles".to_owned(), serde_json::json!(["admin"]));
+        extra.insert("sub".to_owned(), serde_json::json!("someone@else.com"));
+
+        let token = generate_auth_token(&email, extra).unwrap();
+        let banned_token_store =
+            Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+        let result = validate_token(&token, banned_token_store).await.unwrap();
+        assert_eq!(result.sub, "test@example.com");
+        assert_eq!(result.extra["roles"], serde_json::json!(["admin"]));
+        assert!(!result.extra.contains_key("sub"));
+    }
+
+    fn session_claims(expires_in: usize, session_expires_in: usize) -> Claims {
+        let now = current_timestamp().unwrap();
+        Claims {
+            exp: now + expires_in,
+            auth_time: Some(now - 60),
+            session_exp: Some(now + session_expires_in),
+            ..new_claims("test@example.com", 600).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_renew_auth_cookie_near_expiry() {
+        let mut claims = session_claims(60, 3600);
+        claims.extra.insert("tenant".to_owned(), "acme".into());
+        let auth_time = claims.auth_time;
+        let session_exp = claims.session_exp;
+
+        let cookie = renew_auth_cookie(claims).unwrap().unwrap();
+        let token = Secret::new(cookie.value().to_owned());
+        let banned_token_store =
+            Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+        let renewed = validate_token(&token, banned_token_store).await.unwrap();
+
+        let now = current_timestamp().unwrap();
+        assert!(renewed.exp > now + 60);
+        assert_eq!(renewed.auth_time, auth_time);
+        assert_eq!(renewed.session_exp, session_exp);
+        assert_eq!(renewed.extra["tenant"], "acme");
+    }
+
+    #[tokio::test]
+    async fn test_renew_auth_cookie_is_capped_at_session_exp() {
+        let claims = session_claims(60, 120);
+        let session_exp = claims.session_exp.unwrap();
+
+        let cookie = renew_auth_cookie(claims).unwrap().unwrap();
+        let token = Secret::new(cookie.value().to_owned());
+        let banned_token_store =
+            Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+        let renewed = validate_token(&token, banned_token_store).await.unwrap();
+        assert_eq!(renewed.exp, session_exp);
+    }
+
+    #[test]
+    fn test_renew_auth_cookie_skips_fresh_and_ended_sessions() {
+        assert!(renew_auth_cookie(session_claims(600, 3600))
+            .unwrap()
+            .is_none());
+        assert!(renew_auth_cookie(session_claims(60, 0)).unwrap().is_none());
+
+        let mut claims = session_claims(60, 3600);
+        claims.session_exp = None;
+        assert!(renew_auth_cookie(claims).unwrap().is_none());
     }
 
     #[tokio::test]
@@ -187,7 +677,7 @@ mod tests {
     async fn test_validate_token_with_banned_token() {
         let email =
             Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
-        let token = generate_auth_token(&email).unwrap();
+        let token = generate_auth_token(&email, Map::new()).unwrap();
         let banned_token_store =
             Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
         banned_token_store
@@ -0,0 +1,154 @@
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header::USER_AGENT, request::Parts, HeaderMap},
+};
+use color_eyre::eyre::{eyre, Result};
+use ipnet::IpNet;
+
+use super::constants::REQUEST_ID_HEADER;
+use crate::app_state::AppState;
+
+// Where a request came from. Behind nginx the peer is the proxy, so the
+// X-Real-IP header it sets is preferred, but only from a trusted proxy
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip());
+
+        Ok(Self {
+            ip: client_ip(&parts.headers, peer, &state.trusted_proxies),
+            user_agent: header(&parts.headers, USER_AGENT.as_str()),
+            request_id: header(&parts.headers, REQUEST_ID_HEADER),
+        })
+    }
+}
+
+// Proxies, by address or CIDR range, whose X-Real-IP header is believed.
+// From anyone else it could hide or forge where a request came from
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<IpNet>);
+
+impl TrustedProxies {
+    pub fn parse(proxies: &[String]) -> Result<Self> {
+        proxies
+            .iter()
+            .map(|proxy| {
+                proxy
+                    .parse::<IpNet>()
+                    .or_else(|_| proxy.parse::<IpAddr>().map(IpNet::from))
+                    .map_err(|_| eyre!("Invalid proxy address: {}", proxy))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    fn contains(&self, ip: &IpAddr) -> bool {
+        self.0.iter().any(|proxy| proxy.contains(ip))
+    }
+}
+
+fn client_ip(
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+    trusted_proxies: &TrustedProxies,
+) -> Option<String> {
+    match peer {
+        Some(peer) if trusted_proxies.contains(&peer) => {
+            header(headers, X_REAL_IP).or_else(|| Some(peer.to_string()))
+        }
+        peer => peer.map(|peer| peer.to_string()),
+    }
+}
+
+fn header(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+}
+
+const X_REAL_IP: &str = "x-real-ip";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trusted_proxies() -> TrustedProxies {
+        TrustedProxies::parse(&["10.0.0.0/8".to_owned(), "::1".to_owned()])
+            .unwrap()
+    }
+
+    fn headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(X_REAL_IP, "203.0.113.7".parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_header_is_used_from_trusted_proxy() {
+        let proxy = "10.1.2.3".parse().ok();
+
+        assert_eq!(
+            client_ip(&headers(), proxy, &trusted_proxies()).as_deref(),
+            Some("203.0.113.7")
+        );
+        assert_eq!(
+            client_ip(&HeaderMap::new(), proxy, &trusted_proxies()).as_deref(),
+            Some("10.1.2.3")
+        );
+        assert_eq!(
+            client_ip(&headers(), "::1".parse().ok(), &trusted_proxies())
+                .as_deref(),
+            Some("203.0.113.7")
+        );
+    }
+
+    #[test]
+    fn test_header_is_ignored_from_other_peers() {
+        assert_eq!(
+            client_ip(
+                &headers(),
+                "198.51.100.1".parse().ok(),
+                &trusted_proxies()
+            )
+            .as_deref(),
+            Some("198.51.100.1")
+        );
+        assert_eq!(
+            client_ip(
+                &headers(),
+                "10.1.2.3".parse().ok(),
+                &TrustedProxies::default()
+            )
+            .as_deref(),
+            Some("10.1.2.3")
+        );
+        assert_eq!(client_ip(&headers(), None, &trusted_proxies()), None);
+    }
+
+    #[test]
+    fn test_invalid_proxy_is_rejected() {
+        assert!(TrustedProxies::parse(&["nginx".to_owned()]).is_err());
+    }
+}
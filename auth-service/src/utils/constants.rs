@@ -1,7 +1,7 @@
 use dotenvy::dotenv;
 use lazy_static::lazy_static;
 use secrecy::Secret;
-use std::env as std_env;
+use std::{env as std_env, path::PathBuf, str::FromStr};
 
 lazy_static! {
     pub static ref JWT_SECRET: Secret<String> = set_token();
@@ -18,9 +18,150 @@ lazy_static! {
         set_postmark_auth_token();
     pub static ref POSTMARK_EMAIL_SENDER_ADDRESS: Secret<String> =
         set_postmark_email_sender_address();
+    // Which email clients send the outbox: `postmark`, `smtp` or `file`, or
+    // several separated by commas to fail over between them in that order
+    pub static ref EMAIL_PROVIDER: String =
+        load_or_default(env::EMAIL_PROVIDER_ENV_VAR, DEFAULT_EMAIL_PROVIDER);
+    pub static ref SMTP_URL: Option<Secret<String>> =
+        load_optional_secret(env::SMTP_URL_ENV_VAR);
+    pub static ref SMTP_SENDER_ADDRESS: Option<Secret<String>> =
+        load_optional_secret(env::SMTP_SENDER_ADDRESS_ENV_VAR);
+    // The password policy new passwords must follow
+    pub static ref PASSWORD_MIN_LENGTH: usize =
+        load_number(env::PASSWORD_MIN_LENGTH_ENV_VAR, DEFAULT_PASSWORD_MIN_LENGTH);
+    pub static ref PASSWORD_MAX_LENGTH: usize =
+        load_number(env::PASSWORD_MAX_LENGTH_ENV_VAR, DEFAULT_PASSWORD_MAX_LENGTH);
+    pub static ref PASSWORD_MIN_STRENGTH: u8 = load_number(
+        env::PASSWORD_MIN_STRENGTH_ENV_VAR,
+        DEFAULT_PASSWORD_MIN_STRENGTH
+    );
+    pub static ref PASSWORD_BANNED_WORDS: Vec<String> =
+        load_list(env::PASSWORD_BANNED_WORDS_ENV_VAR);
+    // The Argon2id cost of user password hashes
+    pub static ref PASSWORD_HASH_MEMORY_KIB: u32 = load_number(
+        env::PASSWORD_HASH_MEMORY_KIB_ENV_VAR,
+        DEFAULT_PASSWORD_HASH_MEMORY_KIB
+    );
+    pub static ref PASSWORD_HASH_ITERATIONS: u32 = load_number(
+        env::PASSWORD_HASH_ITERATIONS_ENV_VAR,
+        DEFAULT_PASSWORD_HASH_ITERATIONS
+    );
+    pub static ref PASSWORD_HASH_PARALLELISM: u32 = load_number(
+        env::PASSWORD_HASH_PARALLELISM_ENV_VAR,
+        DEFAULT_PASSWORD_HASH_PARALLELISM
+    );
+    // How many recent passwords, including the current one, can't be reused
+    pub static ref PASSWORD_HISTORY_SIZE: usize = load_number(
+        env::PASSWORD_HISTORY_SIZE_ENV_VAR,
+        DEFAULT_PASSWORD_HISTORY_SIZE
+    );
+    // `VERSION:SECRET` peppers for user password hashes, current first
+    pub static ref PASSWORD_PEPPERS: Vec<Secret<String>> =
+        load_list(env::PASSWORD_PEPPERS_ENV_VAR)
+            .into_iter()
+            .map(Secret::new)
+            .collect();
+    // How signup checks passwords against breached ones: `off`, `file` to
+    // look them up in local range files, or `hibp` to use the range API
+    pub static ref BREACHED_PASSWORD_CHECK: String = load_or_default(
+        env::BREACHED_PASSWORD_CHECK_ENV_VAR,
+        DEFAULT_BREACHED_PASSWORD_CHECK
+    );
+    // Lets passwords through when the breach check fails, instead of refusing
+    // them
+    pub static ref BREACHED_PASSWORD_CHECK_FAIL_OPEN: bool =
+        load_flag(env::BREACHED_PASSWORD_CHECK_FAIL_OPEN_ENV_VAR);
+    pub static ref BREACHED_PASSWORD_RANGES_DIR: PathBuf = load_or_default(
+        env::BREACHED_PASSWORD_RANGES_DIR_ENV_VAR,
+        DEFAULT_BREACHED_PASSWORD_RANGES_DIR
+    )
+    .into();
+    pub static ref HIBP_API_URL: String =
+        load_or_default(env::HIBP_API_URL_ENV_VAR, DEFAULT_HIBP_API_URL);
+    // When set, only these email domains can sign up
+    pub static ref SIGNUP_ALLOWED_EMAIL_DOMAINS: Vec<String> =
+        load_list(env::SIGNUP_ALLOWED_EMAIL_DOMAINS_ENV_VAR);
+    pub static ref SIGNUP_DENIED_EMAIL_DOMAINS: Vec<String> =
+        load_list(env::SIGNUP_DENIED_EMAIL_DOMAINS_ENV_VAR);
+    // Disposable email domains, one a line, refused at signup
+    pub static ref DISPOSABLE_EMAIL_DOMAINS_FILE: Option<PathBuf> =
+        load_optional(env::DISPOSABLE_EMAIL_DOMAINS_FILE_ENV_VAR)
+            .map(PathBuf::from);
+    // Addresses or CIDR ranges of the proxies whose X-Real-IP header is
+    // trusted, e.g. nginx's Docker network
+    pub static ref TRUSTED_PROXIES: Vec<String> =
+        load_list(env::TRUSTED_PROXIES_ENV_VAR);
+    // Where the `file` email client writes its `.eml` files
+    pub static ref MAILBOX_DIR: PathBuf =
+        load_or_default(env::MAILBOX_DIR_ENV_VAR, DEFAULT_MAILBOX_DIR).into();
+    // Serves the `file` client's emails, 2FA codes and all, at `/dev/mailbox`
+    // without authentication. Only for local development
+    pub static ref DEV_MAILBOX_ENABLED: bool =
+        load_flag(env::DEV_MAILBOX_ENABLED_ENV_VAR);
     pub static ref REDIS_HOST_NAME: String = set_redis_host();
+    // Branding of the emails sent to users
+    pub static ref PRODUCT_NAME: String =
+        load_or_default(env::PRODUCT_NAME_ENV_VAR, DEFAULT_PRODUCT_NAME);
+    pub static ref BRAND_COLOR: String =
+        load_or_default(env::BRAND_COLOR_ENV_VAR, DEFAULT_BRAND_COLOR);
+    pub static ref BRAND_LOGO_URL: Option<String> =
+        load_optional(env::BRAND_LOGO_URL_ENV_VAR);
+    // How long session and access tokens, and 2FA codes, are valid for
+    pub static ref TOKEN_TTL_SECONDS: i64 = load_seconds(
+        env::TOKEN_TTL_SECONDS_ENV_VAR,
+        DEFAULT_TOKEN_TTL_SECONDS
+    );
+    pub static ref TWO_FA_CODE_TTL_SECONDS: i64 = load_seconds(
+        env::TWO_FA_CODE_TTL_SECONDS_ENV_VAR,
+        DEFAULT_TWO_FA_CODE_TTL_SECONDS
+    );
+    pub static ref TRUSTED_DEVICE_TTL_SECONDS: i64 = load_seconds(
+        env::TRUSTED_DEVICE_TTL_SECONDS_ENV_VAR,
+        DEFAULT_TRUSTED_DEVICE_TTL_SECONDS
+    );
+    // Setting this turns on sliding sessions, which are renewed while in use
+    // for up to this long after sign in
+    pub static ref SESSION_MAX_AGE_SECONDS: Option<i64> =
+        load_optional_seconds(env::SESSION_MAX_AGE_SECONDS_ENV_VAR);
+    // Public base URL of this service, used as the `iss` of ID tokens
+    pub static ref ISSUER: String =
+        load_or_default(env::ISSUER_ENV_VAR, "http://localhost:3000");
+    // Expected `iss` and `aud` of the tokens this service issues and accepts
+    pub static ref JWT_ISSUER: String =
+        load_or_default(env::JWT_ISSUER_ENV_VAR, &ISSUER);
+    pub static ref JWT_AUDIENCE: String =
+        load_or_default(env::JWT_AUDIENCE_ENV_VAR, DEFAULT_JWT_AUDIENCE);
+    pub static ref OIDC_SIGNING_KEY: Option<Secret<String>> =
+        load_optional_secret(env::OIDC_SIGNING_KEY_ENV_VAR);
+    // Endpoints sent user lifecycle events, and the secret they're signed with
+    pub static ref WEBHOOK_URLS: Vec<String> =
+        load_list(env::WEBHOOK_URLS_ENV_VAR);
+    pub static ref WEBHOOK_SECRET: Option<Secret<String>> =
+        load_optional_secret(env::WEBHOOK_SECRET_ENV_VAR);
+    // Writes the audit log to this file as JSON lines, instead of Postgres
+    pub static ref AUDIT_LOG_PATH: Option<String> =
+        load_optional(env::AUDIT_LOG_PATH_ENV_VAR);
+    // Service accounts created at startup, so there is a way in to the admin API
+    pub static ref APP_SERVICE_CREDENTIALS: Option<ServiceCredentials> =
+        set_service_credentials(
+            env::APP_SERVICE_CLIENT_ID_ENV_VAR,
+            env::APP_SERVICE_CLIENT_SECRET_ENV_VAR
+        );
+    pub static ref ADMIN_CREDENTIALS: Option<ServiceCredentials> =
+        set_service_credentials(
+            env::ADMIN_CLIENT_ID_ENV_VAR,
+            env::ADMIN_CLIENT_SECRET_ENV_VAR
+        );
+    // Used by Postmark to authenticate its bounce webhooks
+    pub static ref POSTMARK_WEBHOOK_CREDENTIALS: Option<ServiceCredentials> =
+        set_service_credentials(
+            env::POSTMARK_WEBHOOK_CLIENT_ID_ENV_VAR,
+            env::POSTMARK_WEBHOOK_CLIENT_SECRET_ENV_VAR
+        );
 }
 
+pub type ServiceCredentials = (String, Secret<String>);
+
 fn load_env() {
     dotenv().ok();
 }
@@ -76,23 +217,176 @@ fn load_or_default(variable_name: &str, default_value: &str) -> String {
     }
 }
 
+fn load_seconds(variable_name: &str, default_value: i64) -> i64 {
+    load_optional_seconds(variable_name).unwrap_or(default_value)
+}
+
+fn load_optional_seconds(variable_name: &str) -> Option<i64> {
+    load_env();
+    let value = std_env::var(variable_name)
+        .ok()
+        .filter(|value| !value.is_empty())?;
+
+    match value.parse::<i64>() {
+        Ok(seconds) if seconds > 0 => Some(seconds),
+        _ => panic!("{} must be a positive number of seconds.", variable_name),
+    }
+}
+
+fn load_number<T: FromStr>(variable_name: &str, default_value: T) -> T {
+    match load_optional(variable_name) {
+        Some(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("{} must be a number.", variable_name)),
+        None => default_value,
+    }
+}
+
+// Off unless set to `true`
+fn load_flag(variable_name: &str) -> bool {
+    match load_optional(variable_name).as_deref() {
+        None | Some("false") => false,
+        Some("true") => true,
+        Some(_) => panic!("{} must be true or false.", variable_name),
+    }
+}
+
+fn load_list(variable_name: &str) -> Vec<String> {
+    load_optional(variable_name)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+fn load_optional(variable_name: &str) -> Option<String> {
+    load_env();
+    std_env::var(variable_name)
+        .ok()
+        .filter(|value| !value.is_empty())
+}
+
+fn load_optional_secret(variable_name: &str) -> Option<Secret<String>> {
+    load_optional(variable_name).map(Secret::new)
+}
+
 fn set_redis_host() -> String {
     load_env();
     std_env::var(env::REDIS_HOST_NAME_ENV_VAR)
         .unwrap_or(DEFAULT_REDIS_HOSTNAME.to_owned())
 }
 
+fn set_service_credentials(
+    client_id_var: &str,
+    client_secret_var: &str,
+) -> Option<ServiceCredentials> {
+    load_env();
+    let client_id = std_env::var(client_id_var).ok()?;
+    let client_secret = std_env::var(client_secret_var).ok()?;
+    if client_id.is_empty() || client_secret.is_empty() {
+        return None;
+    }
+    Some((client_id, Secret::new(client_secret)))
+}
+
 pub mod env {
     pub const DATABASE_URL_ENV_VAR: &str = "DATABASE_URL";
     pub const JWT_SECRET_ENV_VAR: &str = "JWT_SECRET";
+    pub const JWT_ISSUER_ENV_VAR: &str = "JWT_ISSUER";
+    pub const JWT_AUDIENCE_ENV_VAR: &str = "JWT_AUDIENCE";
     pub const POSTMARK_AUTH_TOKEN_ENV_VAR: &str = "POSTMARK_AUTH_TOKEN";
     pub const POSTMARK_EMAIL_SENDER_ADDRESS_ENV_VAR: &str =
         "POSTMARK_EMAIL_SENDER_ADDRESS";
     pub const REDIS_HOST_NAME_ENV_VAR: &str = "REDIS_HOST_NAME";
+    pub const TOKEN_TTL_SECONDS_ENV_VAR: &str = "TOKEN_TTL_SECONDS";
+    pub const TWO_FA_CODE_TTL_SECONDS_ENV_VAR: &str = "TWO_FA_CODE_TTL_SECONDS";
+    pub const TRUSTED_DEVICE_TTL_SECONDS_ENV_VAR: &str =
+        "TRUSTED_DEVICE_TTL_SECONDS";
+    pub const SESSION_MAX_AGE_SECONDS_ENV_VAR: &str = "SESSION_MAX_AGE_SECONDS";
+    pub const ISSUER_ENV_VAR: &str = "OIDC_ISSUER";
+    pub const OIDC_SIGNING_KEY_ENV_VAR: &str = "OIDC_SIGNING_KEY";
+    pub const APP_SERVICE_CLIENT_ID_ENV_VAR: &str = "APP_SERVICE_CLIENT_ID";
+    pub const APP_SERVICE_CLIENT_SECRET_ENV_VAR: &str =
+        "APP_SERVICE_CLIENT_SECRET";
+    pub const ADMIN_CLIENT_ID_ENV_VAR: &str = "ADMIN_CLIENT_ID";
+    pub const ADMIN_CLIENT_SECRET_ENV_VAR: &str = "ADMIN_CLIENT_SECRET";
+    pub const POSTMARK_WEBHOOK_CLIENT_ID_ENV_VAR: &str =
+        "POSTMARK_WEBHOOK_CLIENT_ID";
+    pub const POSTMARK_WEBHOOK_CLIENT_SECRET_ENV_VAR: &str =
+        "POSTMARK_WEBHOOK_CLIENT_SECRET";
+    pub const AUDIT_LOG_PATH_ENV_VAR: &str = "AUDIT_LOG_PATH";
+    pub const WEBHOOK_URLS_ENV_VAR: &str = "WEBHOOK_URLS";
+    pub const WEBHOOK_SECRET_ENV_VAR: &str = "WEBHOOK_SECRET";
+    pub const EMAIL_PROVIDER_ENV_VAR: &str = "EMAIL_PROVIDER";
+    pub const SMTP_URL_ENV_VAR: &str = "SMTP_URL";
+    pub const SMTP_SENDER_ADDRESS_ENV_VAR: &str = "SMTP_SENDER_ADDRESS";
+    pub const MAILBOX_DIR_ENV_VAR: &str = "MAILBOX_DIR";
+    pub const TRUSTED_PROXIES_ENV_VAR: &str = "TRUSTED_PROXIES";
+    pub const DEV_MAILBOX_ENABLED_ENV_VAR: &str = "DEV_MAILBOX_ENABLED";
+    pub const PASSWORD_MIN_LENGTH_ENV_VAR: &str = "PASSWORD_MIN_LENGTH";
+    pub const PASSWORD_MAX_LENGTH_ENV_VAR: &str = "PASSWORD_MAX_LENGTH";
+    pub const PASSWORD_MIN_STRENGTH_ENV_VAR: &str = "PASSWORD_MIN_STRENGTH";
+    pub const PASSWORD_BANNED_WORDS_ENV_VAR: &str = "PASSWORD_BANNED_WORDS";
+    pub const PASSWORD_HASH_MEMORY_KIB_ENV_VAR: &str =
+        "PASSWORD_HASH_MEMORY_KIB";
+    pub const PASSWORD_HASH_ITERATIONS_ENV_VAR: &str =
+        "PASSWORD_HASH_ITERATIONS";
+    pub const PASSWORD_HASH_PARALLELISM_ENV_VAR: &str =
+        "PASSWORD_HASH_PARALLELISM";
+    pub const PASSWORD_PEPPERS_ENV_VAR: &str = "PASSWORD_PEPPERS";
+    pub const PASSWORD_HISTORY_SIZE_ENV_VAR: &str = "PASSWORD_HISTORY_SIZE";
+    pub const BREACHED_PASSWORD_CHECK_ENV_VAR: &str = "BREACHED_PASSWORD_CHECK";
+    pub const BREACHED_PASSWORD_CHECK_FAIL_OPEN_ENV_VAR: &str =
+        "BREACHED_PASSWORD_CHECK_FAIL_OPEN";
+    pub const BREACHED_PASSWORD_RANGES_DIR_ENV_VAR: &str =
+        "BREACHED_PASSWORD_RANGES_DIR";
+    pub const HIBP_API_URL_ENV_VAR: &str = "HIBP_API_URL";
+    pub const SIGNUP_ALLOWED_EMAIL_DOMAINS_ENV_VAR: &str =
+        "SIGNUP_ALLOWED_EMAIL_DOMAINS";
+    pub const SIGNUP_DENIED_EMAIL_DOMAINS_ENV_VAR: &str =
+        "SIGNUP_DENIED_EMAIL_DOMAINS";
+    pub const DISPOSABLE_EMAIL_DOMAINS_FILE_ENV_VAR: &str =
+        "DISPOSABLE_EMAIL_DOMAINS_FILE";
+    pub const PRODUCT_NAME_ENV_VAR: &str = "PRODUCT_NAME";
+    pub const BRAND_COLOR_ENV_VAR: &str = "BRAND_COLOR";
+    pub const BRAND_LOGO_URL_ENV_VAR: &str = "BRAND_LOGO_URL";
 }
 
 pub const JWT_COOKIE_NAME: &str = "jwt";
+pub const DEFAULT_EMAIL_PROVIDER: &str = "postmark";
+pub const DEFAULT_MAILBOX_DIR: &str = "mailbox";
+pub const MAILBOX_SENDER_ADDRESS: &str = "no-reply@localhost";
+pub const DEFAULT_PASSWORD_MIN_LENGTH: usize = 8;
+pub const DEFAULT_PASSWORD_MAX_LENGTH: usize = 128;
+pub const DEFAULT_PASSWORD_MIN_STRENGTH: u8 = 0;
+pub const DEFAULT_PASSWORD_HASH_MEMORY_KIB: u32 = 15000;
+pub const DEFAULT_PASSWORD_HASH_ITERATIONS: u32 = 2;
+pub const DEFAULT_PASSWORD_HASH_PARALLELISM: u32 = 1;
+pub const DEFAULT_PASSWORD_HISTORY_SIZE: usize = 5;
+pub const DEFAULT_BREACHED_PASSWORD_CHECK: &str = "off";
+pub const DEFAULT_BREACHED_PASSWORD_RANGES_DIR: &str = "breached-passwords";
+pub const DEFAULT_HIBP_API_URL: &str = "https://api.pwnedpasswords.com";
+pub const DEFAULT_PRODUCT_NAME: &str = "LGR Bootcamp";
+pub const DEFAULT_BRAND_COLOR: &str = "#212529";
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+pub const DEFAULT_JWT_AUDIENCE: &str = "auth-service";
+pub const DEFAULT_TOKEN_TTL_SECONDS: i64 = 600; // 10 minutes
+pub const DEFAULT_TWO_FA_CODE_TTL_SECONDS: i64 = 600; // 10 minutes
+pub const DEFAULT_TRUSTED_DEVICE_TTL_SECONDS: i64 = 60 * 60 * 24 * 30; // 30 days
+pub const TRUSTED_DEVICE_COOKIE_NAME: &str = "trusted_device";
+// Scopes service accounts need for the service-only routes
+pub const VERIFY_TOKEN_SCOPE: &str = "tokens:verify";
+pub const REVOKE_TOKEN_SCOPE: &str = "tokens:revoke";
+pub const ADMIN_SCOPE: &str = "admin";
+pub const POSTMARK_WEBHOOK_SCOPE: &str = "webhooks:postmark";
 pub const DEFAULT_REDIS_HOSTNAME: &str = "127.0.0.1";
+// Headers sent with each webhook. The signature is an HMAC-SHA256 of
+// "{timestamp}.{body}", so receivers can reject old or altered requests
+pub const WEBHOOK_ID_HEADER: &str = "x-webhook-id";
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "x-webhook-signature";
 
 pub mod prod {
     pub const APP_ADDRESS: &str = "0.0.0.0:3000";
@@ -102,6 +396,46 @@ pub mod prod {
         pub const BASE_URL: &str = "https://api.postmarkapp.com/email";
         pub const TIMEOUT: Duration = std::time::Duration::from_secs(10);
     }
+    pub mod smtp {
+        use std::time::Duration;
+
+        pub const TIMEOUT: Duration = std::time::Duration::from_secs(10);
+        pub const POOL_SIZE: u32 = 10;
+    }
+    pub mod email_failover {
+        use std::time::Duration;
+
+        // Longer than the providers' own timeouts, to catch sends that hang
+        pub const TIMEOUT: Duration = Duration::from_secs(15);
+        pub const FAILURE_THRESHOLD: u32 = 3;
+        pub const OPEN_DURATION: Duration = Duration::from_secs(60);
+    }
+    pub mod breached_passwords {
+        use std::time::Duration;
+
+        pub const TIMEOUT: Duration = Duration::from_secs(5);
+    }
+    pub mod email_outbox {
+        use std::time::Duration;
+
+        // The last retry is 75 seconds after the first attempt, so a 2FA
+        // code still arrives well within DEFAULT_TWO_FA_CODE_TTL_SECONDS
+        pub const MAX_ATTEMPTS: u32 = 5;
+        pub const BASE_RETRY_DELAY: Duration =
+            std::time::Duration::from_secs(5);
+        pub const POLL_INTERVAL: Duration = std::time::Duration::from_secs(5);
+    }
+    pub mod webhooks {
+        use std::time::Duration;
+
+        pub const TIMEOUT: Duration = std::time::Duration::from_secs(10);
+        pub const MAX_ATTEMPTS: u32 = 8;
+        // Doubled after each failed attempt, so the last retry is about an
+        // hour after the event
+        pub const BASE_RETRY_DELAY: Duration =
+            std::time::Duration::from_secs(30);
+        pub const POLL_INTERVAL: Duration = std::time::Duration::from_secs(5);
+    }
 }
 
 pub mod test {
@@ -112,4 +446,41 @@ pub mod test {
         // pub const SENDER: &str = "test@email.com";
         pub const TIMEOUT: Duration = std::time::Duration::from_millis(200);
     }
+    pub mod smtp {
+        use std::time::Duration;
+
+        pub const TIMEOUT: Duration = std::time::Duration::from_secs(1);
+        pub const POOL_SIZE: u32 = 2;
+    }
+    pub mod email_failover {
+        use std::time::Duration;
+
+        pub const TIMEOUT: Duration = Duration::from_millis(100);
+        pub const FAILURE_THRESHOLD: u32 = 2;
+        pub const OPEN_DURATION: Duration = Duration::from_millis(200);
+    }
+    pub mod breached_passwords {
+        use std::time::Duration;
+
+        pub const TIMEOUT: Duration = Duration::from_millis(200);
+    }
+    pub mod email_outbox {
+        use std::time::Duration;
+
+        pub const MAX_ATTEMPTS: u32 = 3;
+        pub const BASE_RETRY_DELAY: Duration =
+            std::time::Duration::from_millis(50);
+        pub const POLL_INTERVAL: Duration =
+            std::time::Duration::from_millis(50);
+    }
+    pub mod webhooks {
+        use std::time::Duration;
+
+        pub const TIMEOUT: Duration = std::time::Duration::from_millis(200);
+        pub const MAX_ATTEMPTS: u32 = 3;
+        pub const BASE_RETRY_DELAY: Duration =
+            std::time::Duration::from_millis(50);
+        pub const POLL_INTERVAL: Duration =
+            std::time::Duration::from_millis(50);
+    }
 }
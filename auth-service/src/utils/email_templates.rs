@@ -0,0 +1,255 @@
+use askama::Template;
+use color_eyre::eyre::Result;
+
+use super::constants::{BRAND_COLOR, BRAND_LOGO_URL, PRODUCT_NAME};
+
+// How emails name and style the product
+#[derive(Debug, Clone)]
+pub struct Branding {
+    pub product_name: String,
+    pub brand_color: String,
+    pub logo_url: Option<String>,
+}
+
+impl Branding {
+    pub fn from_config() -> Self {
+        Self {
+            product_name: PRODUCT_NAME.to_owned(),
+            brand_color: BRAND_COLOR.to_owned(),
+            logo_url: BRAND_LOGO_URL.clone(),
+        }
+    }
+}
+
+// An email ready for EmailClient::send_email
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: String,
+}
+
+pub struct TwoFACodeEmail {
+    pub code: String,
+    pub expires_in_minutes: i64,
+}
+
+#[derive(Template)]
+#[template(path = "emails/two_fa_code.html")]
+struct TwoFACodeHtml<'a> {
+    branding: &'a Branding,
+    email: &'a TwoFACodeEmail,
+}
+
+#[derive(Template)]
+#[template(path = "emails/two_fa_code.txt")]
+struct TwoFACodeText<'a> {
+    branding: &'a Branding,
+    email: &'a TwoFACodeEmail,
+}
+
+impl TwoFACodeEmail {
+    pub fn render(&self, branding: &Branding) -> Result<RenderedEmail> {
+        Ok(RenderedEmail {
+            subject: format!("Your {} sign-in code", branding.product_name),
+            html_body: TwoFACodeHtml {
+                branding,
+                email: self,
+            }
+            .render()?,
+            text_body: TwoFACodeText {
+                branding,
+                email: self,
+            }
+            .render()?,
+        })
+    }
+}
+
+pub struct VerificationEmail {
+    pub verification_url: String,
+    pub expires_in_minutes: i64,
+}
+
+#[derive(Template)]
+#[template(path = "emails/verification.html")]
+struct VerificationHtml<'a> {
+    branding: &'a Branding,
+    email: &'a VerificationEmail,
+}
+
+#[derive(Template)]
+#[template(path = "emails/verification.txt")]
+struct VerificationText<'a> {
+    branding: &'a Branding,
+    email: &'a VerificationEmail,
+}
+
+impl VerificationEmail {
+    pub fn render(&self, branding: &Branding) -> Result<RenderedEmail> {
+        Ok(RenderedEmail {
+            subject: format!(
+                "Verify your {} email address",
+                branding.product_name
+            ),
+            html_body: VerificationHtml {
+                branding,
+                email: self,
+            }
+            .render()?,
+            text_body: VerificationText {
+                branding,
+                email: self,
+            }
+            .render()?,
+        })
+    }
+}
+
+pub struct PasswordResetEmail {
+    pub reset_url: String,
+    pub expires_in_minutes: i64,
+}
+
+#[derive(Template)]
+#[template(path = "emails/password_reset.html")]
+struct PasswordResetHtml<'a> {
+    branding: &'a Branding,
+    email: &'a PasswordResetEmail,
+}
+
+#[derive(Template)]
+#[template(path = "emails/password_reset.txt")]
+struct PasswordResetText<'a> {
+    branding: &'a Branding,
+    email: &'a PasswordResetEmail,
+}
+
+impl PasswordResetEmail {
+    pub fn render(&self, branding: &Branding) -> Result<RenderedEmail> {
+        Ok(RenderedEmail {
+            subject: format!("Reset your {} password", branding.product_name),
+            html_body: PasswordResetHtml {
+                branding,
+                email: self,
+            }
+            .render()?,
+            text_body: PasswordResetText {
+                branding,
+                email: self,
+            }
+            .render()?,
+        })
+    }
+}
+
+// Tells the user about something that happened to their account, such as
+// a sign-in from a new device, listing details like the device and IP
+pub struct SecurityNoticeEmail {
+    pub title: String,
+    pub message: String,
+    pub details: Vec<(String, String)>,
+}
+
+#[derive(Template)]
+#[template(path = "emails/security_notice.html")]
+struct SecurityNoticeHtml<'a> {
+    branding: &'a Branding,
+    email: &'a SecurityNoticeEmail,
+}
+
+#[derive(Template)]
+#[template(path = "emails/security_notice.txt")]
+struct SecurityNoticeText<'a> {
+    branding: &'a Branding,
+    email: &'a SecurityNoticeEmail,
+}
+
+impl SecurityNoticeEmail {
+    pub fn render(&self, branding: &Branding) -> Result<RenderedEmail> {
+        Ok(RenderedEmail {
+            subject: format!("{}: {}", branding.product_name, self.title),
+            html_body: SecurityNoticeHtml {
+                branding,
+                email: self,
+            }
+            .render()?,
+            text_body: SecurityNoticeText {
+                branding,
+                email: self,
+            }
+            .render()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branding() -> Branding {
+        Branding {
+            product_name: "Acme".to_owned(),
+            brand_color: "#ff0000".to_owned(),
+            logo_url: Some("https://example.com/logo.png".to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_two_fa_code_email() {
+        let email = TwoFACodeEmail {
+            code: "123456".to_owned(),
+            expires_in_minutes: 10,
+        }
+        .render(&branding())
+        .unwrap();
+
+        assert_eq!(email.subject, "Your Acme sign-in code");
+        assert!(email.html_body.contains("123456"));
+        assert!(email.html_body.contains("#ff0000"));
+        assert!(email.html_body.contains("https://example.com/logo.png"));
+        assert!(email.text_body.starts_with("Acme\n"));
+        assert!(email.text_body.contains("    123456\n"));
+        assert!(email.text_body.contains("expires in 10 minutes"));
+        assert!(!email.text_body.contains('<'));
+    }
+
+    #[test]
+    fn test_html_escapes_values() {
+        let email = SecurityNoticeEmail {
+            title: "New sign-in".to_owned(),
+            message: "Your account was signed in to.".to_owned(),
+            details: vec![("Device".to_owned(), "<script>".to_owned())],
+        }
+        .render(&branding())
+        .unwrap();
+
+        assert_eq!(email.subject, "Acme: New sign-in");
+        assert!(email.html_body.contains("&lt;script&gt;"));
+        assert!(email.text_body.contains("Device: <script>"));
+    }
+
+    #[test]
+    fn test_links_are_included() {
+        let verification = VerificationEmail {
+            verification_url: "https://example.com/verify?token=abc".to_owned(),
+            expires_in_minutes: 60,
+        }
+        .render(&branding())
+        .unwrap();
+        let reset = PasswordResetEmail {
+            reset_url: "https://example.com/reset?token=abc".to_owned(),
+            expires_in_minutes: 30,
+        }
+        .render(&branding())
+        .unwrap();
+
+        assert!(verification
+            .text_body
+            .contains("https://example.com/verify?token=abc"));
+        assert!(reset
+            .text_body
+            .contains("https://example.com/reset?token=abc"));
+        assert!(reset.html_body.contains("Reset password"));
+    }
+}
@@ -0,0 +1,91 @@
+use color_eyre::eyre::{eyre, Result};
+
+use crate::{
+    app_state::AppState,
+    domain::{Email, LoginEvent, LoginMethod, OutboxEmail},
+};
+
+use super::{
+    auth::{current_timestamp, current_timestamp_millis},
+    client_info::ClientInfo,
+    email_templates::{Branding, SecurityNoticeEmail},
+};
+
+// Record a login attempt, emailing the user if a successful one comes from a
+// device or IP they haven't signed in from before. The email is queued in the
+// same transaction as the event
+#[tracing::instrument(name = "Recording login", skip_all)]
+pub async fn record_login(
+    state: &AppState,
+    email: &Email,
+    client: &ClientInfo,
+    method: LoginMethod,
+    success: bool,
+) -> Result<()> {
+    let mut notice = None;
+    if success {
+        let is_new_device = state
+            .login_history_store
+            .read()
+            .await
+            .is_new_device(
+                email,
+                client.ip.as_deref(),
+                client.user_agent.as_deref(),
+            )
+            .await
+            .map_err(|e| eyre!(e))?;
+
+        // The login itself shouldn't fail because the notice couldn't be written
+        if is_new_device {
+            match new_device_email(email, client) {
+                Ok(email) => notice = Some(email),
+                Err(e) => {
+                    tracing::error!("Failed to write new device email: {:?}", e)
+                }
+            }
+        }
+    }
+
+    let event = LoginEvent::new(
+        email.clone(),
+        current_timestamp()?,
+        client.ip.clone(),
+        client.user_agent.clone(),
+        method,
+        success,
+    );
+    state
+        .login_history_store
+        .write()
+        .await
+        .add_event(event, notice)
+        .await
+        .map_err(|e| eyre!(e))
+}
+
+fn new_device_email(email: &Email, client: &ClientInfo) -> Result<OutboxEmail> {
+    let message = SecurityNoticeEmail {
+        title: "New sign-in".to_owned(),
+        message: "Your account was signed in to from a new device.".to_owned(),
+        details: vec![
+            (
+                "Device".to_owned(),
+                client.user_agent.as_deref().unwrap_or("Unknown").to_owned(),
+            ),
+            (
+                "IP address".to_owned(),
+                client.ip.as_deref().unwrap_or("Unknown").to_owned(),
+            ),
+        ],
+    }
+    .render(&Branding::from_config())?;
+
+    Ok(OutboxEmail::new(
+        email.clone(),
+        message.subject,
+        message.html_body,
+        message.text_body,
+        current_timestamp_millis()?,
+    ))
+}
@@ -1,3 +1,11 @@
+pub mod audit;
 pub mod auth;
+pub mod client_info;
 pub mod constants;
+pub mod email_templates;
+pub mod login_history;
+pub mod oauth;
+pub mod oidc;
+pub mod sliding_session;
 pub mod tracing;
+pub mod webhooks;
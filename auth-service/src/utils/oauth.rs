@@ -0,0 +1,208 @@
+use axum::http::{header::AUTHORIZATION, HeaderMap};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use secrecy::{ExposeSecret, Secret};
+
+use crate::{
+    app_state::{OAuthClientStoreType, ServiceAccountStoreType},
+    domain::{
+        ClientSecret, OAuthClient, OAuthClientStoreError, OAuthError,
+        ServiceAccount, ServiceAccountStoreError,
+    },
+};
+
+// Authenticate the client calling the token endpoint. Credentials may come in an
+// HTTP Basic header or the request body (RFC 6749 section 2.3.1); public clients
+// send only their client_id.
+#[tracing::instrument(name = "Authenticating OAuth client", skip_all)]
+pub async fn authenticate_client(
+    oauth_client_store: &OAuthClientStoreType,
+    headers: &HeaderMap,
+    client_id: Option<String>,
+    client_secret: Option<Secret<String>>,
+) -> Result<OAuthClient, OAuthError> {
+    let (client_id, client_secret) =
+        client_credentials(headers, client_id, client_secret)?;
+
+    oauth_client_store
+        .read()
+        .await
+        .validate_client(&client_id, client_secret.as_ref())
+        .await
+        .map_err(|e| match e {
+            OAuthClientStoreError::UnexpectedError(e) => {
+                OAuthError::UnexpectedError(e)
+            }
+            _ => OAuthError::InvalidClient,
+        })
+}
+
+// Authenticate a service account using the client_credentials grant. Unlike
+// OAuth clients, service accounts always have a secret.
+#[tracing::instrument(name = "Authenticating service account", skip_all)]
+pub async fn authenticate_service_account(
+    service_account_store: &ServiceAccountStoreType,
+    headers: &HeaderMap,
+    client_id: Option<String>,
+    client_secret: Option<Secret<String>>,
+) -> Result<ServiceAccount, OAuthError> {
+    let (client_id, client_secret) =
+        client_credentials(headers, client_id, client_secret)?;
+    let client_secret = client_secret.ok_or(OAuthError::InvalidClient)?;
+
+    service_account_store
+        .read()
+        .await
+        .validate_account(&client_id, &client_secret)
+        .await
+        .map_err(|e| match e {
+            ServiceAccountStoreError::UnexpectedError(e) => {
+                OAuthError::UnexpectedError(e)
+            }
+            _ => OAuthError::InvalidClient,
+        })
+}
+
+// The introspection and revocation endpoints accept both kinds of client
+pub enum Caller {
+    Client(OAuthClient),
+    ServiceAccount(ServiceAccount),
+}
+
+impl Caller {
+    // The client a token must have been issued to for the caller to act on it.
+    // Service accounts granted the scope may act on any token.
+    pub fn audience(&self, scope: &str) -> Result<Option<&str>, OAuthError> {
+        match self {
+            Caller::Client(client) => Ok(Some(&client.client_id)),
+            Caller::ServiceAccount(account)
+                if account.scopes.contains(scope) =>
+            {
+                Ok(None)
+            }
+            Caller::ServiceAccount(_) => Err(OAuthError::UnauthorizedClient),
+        }
+    }
+}
+
+// Authenticate as a service account when one has the client_id, falling back
+// to the OAuth clients otherwise
+#[tracing::instrument(name = "Authenticating caller", skip_all)]
+pub async fn authenticate_caller(
+    oauth_client_store: &OAuthClientStoreType,
+    service_account_store: &ServiceAccountStoreType,
+    headers: &HeaderMap,
+    client_id: Option<String>,
+    client_secret: Option<Secret<String>>,
+) -> Result<Caller, OAuthError> {
+    let (client_id, client_secret) =
+        client_credentials(headers, client_id, client_secret)?;
+
+    if let Some(client_secret) = &client_secret {
+        match service_account_store
+            .read()
+            .await
+            .validate_account(&client_id, client_secret)
+            .await
+        {
+            Ok(account) => return Ok(Caller::ServiceAccount(account)),
+            Err(ServiceAccountStoreError::AccountNotFound) => {}
+            Err(ServiceAccountStoreError::UnexpectedError(e)) => {
+                return Err(OAuthError::UnexpectedError(e))
+            }
+            Err(_) => return Err(OAuthError::InvalidClient),
+        }
+    }
+
+    oauth_client_store
+        .read()
+        .await
+        .validate_client(&client_id, client_secret.as_ref())
+        .await
+        .map(Caller::Client)
+        .map_err(|e| match e {
+            OAuthClientStoreError::UnexpectedError(e) => {
+                OAuthError::UnexpectedError(e)
+            }
+            _ => OAuthError::InvalidClient,
+        })
+}
+
+fn client_credentials(
+    headers: &HeaderMap,
+    client_id: Option<String>,
+    client_secret: Option<Secret<String>>,
+) -> Result<(String, Option<ClientSecret>), OAuthError> {
+    let (client_id, client_secret) = match basic_credentials(headers)? {
+        Some(credentials) => credentials,
+        None => (client_id.ok_or(OAuthError::InvalidClient)?, client_secret),
+    };
+
+    let client_secret = client_secret
+        .filter(|secret| !secret.expose_secret().is_empty())
+        .map(ClientSecret::parse)
+        .transpose()
+        .map_err(|_| OAuthError::InvalidClient)?;
+
+    Ok((client_id, client_secret))
+}
+
+type ClientCredentials = (String, Option<Secret<String>>);
+
+fn basic_credentials(
+    headers: &HeaderMap,
+) -> Result<Option<ClientCredentials>, OAuthError> {
+    let Some(header) = headers.get(AUTHORIZATION) else {
+        return Ok(None);
+    };
+
+    let encoded = header
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix("Basic "))
+        .ok_or(OAuthError::InvalidClient)?;
+    let decoded = STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or(OAuthError::InvalidClient)?;
+    let (client_id, client_secret) =
+        decoded.split_once(':').ok_or(OAuthError::InvalidClient)?;
+
+    Ok(Some((
+        client_id.to_owned(),
+        Some(Secret::new(client_secret.to_owned())),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_credentials() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            format!("Basic {}", STANDARD.encode("client:secret"))
+                .parse()
+                .unwrap(),
+        );
+
+        let (client_id, client_secret) =
+            basic_credentials(&headers).unwrap().unwrap();
+        assert_eq!(client_id, "client");
+        assert_eq!(client_secret.unwrap().expose_secret(), "secret");
+    }
+
+    #[test]
+    fn test_basic_credentials_missing() {
+        assert!(basic_credentials(&HeaderMap::new()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_basic_credentials_malformed() {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, "Bearer token".parse().unwrap());
+        assert!(basic_credentials(&headers).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use color_eyre::eyre::{eyre, Context, Result};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use lazy_static::lazy_static;
+use ring::{
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::domain::Email;
+
+use super::{
+    auth::current_timestamp,
+    constants::{ISSUER, OIDC_SIGNING_KEY, TOKEN_TTL_SECONDS},
+};
+
+lazy_static! {
+    pub static ref ID_TOKEN_SIGNING_KEY: SigningKey = load_signing_key();
+}
+
+fn load_signing_key() -> SigningKey {
+    match OIDC_SIGNING_KEY.as_ref() {
+        Some(key) => {
+            let der = STANDARD
+                .decode(key.expose_secret())
+                .expect("OIDC_SIGNING_KEY must be base64 encoded");
+            SigningKey::from_pkcs8(&der)
+                .expect("OIDC_SIGNING_KEY must be a P-256 PKCS#8 key")
+        }
+        None => {
+            tracing::warn!(
+                "OIDC_SIGNING_KEY is not set, signing ID tokens with a temporary key"
+            );
+            SigningKey::generate().expect("Failed to generate signing key")
+        }
+    }
+}
+
+// ES256 key that ID tokens are signed with. Relying parties fetch the public
+// half from the JWKS endpoint, so unlike JWT_SECRET it never has to be shared.
+pub struct SigningKey {
+    encoding_key: EncodingKey,
+    jwk: Jwk,
+}
+
+impl SigningKey {
+    pub fn from_pkcs8(der: &[u8]) -> Result<Self> {
+        let key_pair = EcdsaKeyPair::from_pkcs8(
+            &ECDSA_P256_SHA256_FIXED_SIGNING,
+            der,
+            &SystemRandom::new(),
+        )
+        .map_err(|e| eyre!("invalid signing key: {}", e))?;
+
+        // Uncompressed point: 0x04 || x || y
+        let public_key = key_pair.public_key().as_ref();
+        let (x, y) = public_key[1..].split_at(32);
+
+        Ok(Self {
+            encoding_key: EncodingKey::from_ec_der(der),
+            jwk: Jwk {
+                kty: "EC".to_owned(),
+                crv: "P-256".to_owned(),
+                x: URL_SAFE_NO_PAD.encode(x),
+                y: URL_SAFE_NO_PAD.encode(y),
+                key_use: "sig".to_owned(),
+                alg: "ES256".to_owned(),
+                kid: URL_SAFE_NO_PAD.encode(&Sha256::digest(public_key)[..12]),
+            },
+        })
+    }
+
+    pub fn generate() -> Result<Self> {
+        let document = EcdsaKeyPair::generate_pkcs8(
+            &ECDSA_P256_SHA256_FIXED_SIGNING,
+            &SystemRandom::new(),
+        )
+        .map_err(|e| eyre!("failed to generate signing key: {}", e))?;
+
+        Self::from_pkcs8(document.as_ref())
+    }
+
+    pub fn jwk(&self) -> &Jwk {
+        &self.jwk
+    }
+
+    fn sign<T: Serialize>(&self, claims: &T) -> Result<Secret<String>> {
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(self.jwk.kid.clone());
+
+        let token = encode(&header, claims, &self.encoding_key)
+            .wrap_err("failed to sign token")?;
+        Ok(Secret::new(token))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    pub crv: String,
+    pub x: String,
+    pub y: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub alg: String,
+    pub kid: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: String,
+    pub exp: usize,
+    pub iat: usize,
+    pub auth_time: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    // Authentication methods, see RFC 8176
+    pub amr: Vec<String>,
+}
+
+// Create an OpenID Connect ID token for the client the user signed in to
+#[tracing::instrument(name = "Generating ID token", skip_all)]
+pub fn generate_id_token(
+    email: &Email,
+    client_id: &str,
+    nonce: Option<String>,
+    auth_time: usize,
+    requires_2fa: bool,
+) -> Result<Secret<String>> {
+    let iat = current_timestamp()?;
+    let ttl: usize = (*TOKEN_TTL_SECONDS)
+        .try_into()
+        .wrap_err("failed to cast TOKEN_TTL_SECONDS to usize")?;
+
+    let amr = match requires_2fa {
+        true => vec!["pwd".to_owned(), "otp".to_owned()],
+        false => vec!["pwd".to_owned()],
+    };
+
+    let claims = IdTokenClaims {
+        iss: ISSUER.to_owned(),
+        sub: email.as_ref().expose_secret().to_owned(),
+        aud: client_id.to_owned(),
+        exp: iat + ttl,
+        iat,
+        auth_time,
+        nonce,
+        amr,
+    };
+
+    ID_TOKEN_SIGNING_KEY.sign(&claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{decode, decode_header, DecodingKey, Validation};
+
+    fn decode_id_token(token: &Secret<String>) -> IdTokenClaims {
+        let jwk = ID_TOKEN_SIGNING_KEY.jwk();
+        let mut validation = Validation::new(Algorithm::ES256);
+        validation.set_audience(&["client"]);
+        validation.set_issuer(&[ISSUER.as_str()]);
+
+        decode::<IdTokenClaims>(
+            token.expose_secret(),
+            &DecodingKey::from_ec_components(&jwk.x, &jwk.y).unwrap(),
+            &validation,
+        )
+        .unwrap()
+        .claims
+    }
+
+    #[test]
+    fn test_id_token_verifies_with_published_key() {
+        let email =
+            Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let token = generate_id_token(
+            &email,
+            "client",
+            Some("n-0S6_WzA2Mj".to_owned()),
+            1_700_000_000,
+            false,
+        )
+        .unwrap();
+
+        let header = decode_header(token.expose_secret()).unwrap();
+        assert_eq!(header.kid.as_ref(), Some(&ID_TOKEN_SIGNING_KEY.jwk().kid));
+
+        let claims = decode_id_token(&token);
+        assert_eq!(claims.sub, "test@example.com");
+        assert_eq!(claims.nonce.as_deref(), Some("n-0S6_WzA2Mj"));
+        assert_eq!(claims.auth_time, 1_700_000_000);
+        assert_eq!(claims.amr, vec!["pwd"]);
+    }
+
+    #[test]
+    fn test_id_token_amr_includes_otp_for_2fa_users() {
+        let email =
+            Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let token = generate_id_token(&email, "client", None, 0, true).unwrap();
+
+        let claims = decode_id_token(&token);
+        assert_eq!(claims.amr, vec!["pwd", "otp"]);
+        assert!(claims.nonce.is_none());
+    }
+
+    #[test]
+    fn test_signing_key_round_trips_through_pkcs8() {
+        let document = EcdsaKeyPair::generate_pkcs8(
+            &ECDSA_P256_SHA256_FIXED_SIGNING,
+            &SystemRandom::new(),
+        )
+        .unwrap();
+
+        let first = SigningKey::from_pkcs8(document.as_ref()).unwrap();
+        let second = SigningKey::from_pkcs8(document.as_ref()).unwrap();
+        assert_eq!(first.jwk().kid, second.jwk().kid);
+        assert!(SigningKey::from_pkcs8(b"not a key").is_err());
+    }
+}
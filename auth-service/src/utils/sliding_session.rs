@@ -0,0 +1,55 @@
+use axum::{
+    extract::{Request, State},
+    http::{header::SET_COOKIE, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use axum_extra::extract::CookieJar;
+use secrecy::Secret;
+
+use crate::app_state::AppState;
+
+use super::{
+    auth::{renew_auth_cookie, validate_token},
+    constants::{JWT_COOKIE_NAME, SESSION_MAX_AGE_SECONDS},
+};
+
+// Renew the session cookie of requests made near its expiry, when sliding
+// sessions are turned on
+#[tracing::instrument(name = "Sliding session", skip_all)]
+pub async fn sliding_session(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+
+    // Responses that set the cookie themselves, like login and logout, win
+    if SESSION_MAX_AGE_SECONDS.is_none()
+        || response.headers().contains_key(SET_COOKIE)
+    {
+        return response;
+    }
+
+    let Some(cookie) = jar.get(JWT_COOKIE_NAME) else {
+        return response;
+    };
+    let token = Secret::new(cookie.value().to_owned());
+    let Ok(claims) = validate_token(&token, state.banned_token_store).await
+    else {
+        return response;
+    };
+
+    match renew_auth_cookie(claims) {
+        Ok(Some(cookie)) => {
+            if let Ok(value) = HeaderValue::from_str(&cookie.to_string()) {
+                response.headers_mut().append(SET_COOKIE, value);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::error!("Failed to renew session: {:?}", e),
+    }
+
+    response
+}
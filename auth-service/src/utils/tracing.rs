@@ -2,6 +2,8 @@ use std::time::Duration;
 
 use axum::{body::Body, extract::Request, response::Response};
 use color_eyre::eyre::Result;
+
+use super::constants::REQUEST_ID_HEADER;
 use tracing::{Level, Span};
 use tracing_error::ErrorLayer;
 use tracing_subscriber::prelude::*;
@@ -28,8 +30,15 @@ pub fn init_tracing() -> Result<()> {
     Ok(())
 }
 
+// Uses the id from the x-request-id header set by SetRequestIdLayer, so
+// handlers can refer to the same request
 pub fn make_span_with_request_id(request: &Request<Body>) -> Span {
-    let request_id = uuid::Uuid::new_v4();
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
     tracing::span!(
         Level::INFO,
         "[REQUEST]",
@@ -0,0 +1,38 @@
+use secrecy::ExposeSecret;
+
+use crate::{
+    app_state::AppState,
+    domain::{Email, WebhookEvent, WebhookEventData, WebhookEventType},
+};
+
+use super::auth::current_timestamp;
+
+// Queue a user lifecycle event for the webhook endpoints. Failing to queue
+// it is logged, but doesn't fail the request it came from
+#[tracing::instrument(name = "Publishing user event", skip_all)]
+pub async fn publish_user_event(
+    state: &AppState,
+    event_type: WebhookEventType,
+    email: &Email,
+    requires_2fa: Option<bool>,
+) {
+    let created_at = match current_timestamp() {
+        Ok(created_at) => created_at,
+        Err(e) => {
+            tracing::error!("Failed to publish webhook event: {:?}", e);
+            return;
+        }
+    };
+
+    let event = WebhookEvent::new(
+        event_type,
+        created_at,
+        WebhookEventData {
+            email: email.as_ref().expose_secret().to_owned(),
+            requires_2fa,
+        },
+    );
+    if let Err(e) = state.webhook_publisher.publish(event).await {
+        tracing::error!("Failed to publish webhook event: {:?}", e);
+    }
+}
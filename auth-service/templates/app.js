@@ -32,6 +32,19 @@ signupLoginLink.addEventListener("click", (e) => {
 
 // -----------------------------------------------------
 
+// OAuth authorization requests send the user here with ?next=oauth/authorize...
+// so they can sign in first. Only relative OAuth paths are followed.
+function redirectAfterLogin() {
+  const next = new URLSearchParams(window.location.search).get("next");
+  if (next !== null && next.startsWith("oauth/")) {
+    window.location.href = next;
+  } else {
+    window.location.href = "{{app_service_external_address}}";
+  }
+}
+
+// -----------------------------------------------------
+
 const loginForm = document.getElementById("login-form");
 const loginButton = document.getElementById("login-form-submit");
 const loginErrAlter = document.getElementById("login-err-alert");
@@ -67,7 +80,7 @@ loginButton.addEventListener("click", (e) => {
       loginForm.password.value = "";
       loginErrAlter.style.display = "none";
       alert("You have successfully logged in.");
-      window.location.href = "{{app_service_external_address}}";
+      redirectAfterLogin();
     } else {
       response.json().then((data) => {
         let error_msg = data.error;
@@ -114,6 +127,13 @@ signupButton.addEventListener("click", (e) => {
         let error_msg = data.error;
         if (error_msg !== undefined && error_msg !== null && error_msg !== "") {
           signupErrAlter.innerHTML = `<span><strong>Error: </strong>${error_msg}</span>`;
+          // One line for each password policy rule broken. Messages can
+          // quote the email, so they're added as text
+          for (const violation of data.violations ?? []) {
+            const line = document.createElement("div");
+            line.textContent = violation.message;
+            signupErrAlter.appendChild(line);
+          }
           signupErrAlter.style.display = "block";
         } else {
           signupErrAlter.style.display = "none";
@@ -133,24 +153,31 @@ TwoFAButton.addEventListener("click", (e) => {
   const email = TwoFAForm.email.value;
   const loginAttemptId = TwoFAForm.login_attempt_id.value;
   const TwoFACode = TwoFAForm.email_code.value;
+  const rememberDevice = TwoFAForm.rememberDevice.checked;
 
   fetch("verify-2fa", {
     method: "POST",
     headers: {
       "Content-Type": "application/json",
     },
-    body: JSON.stringify({ email, loginAttemptId, "2FACode": TwoFACode }),
+    body: JSON.stringify({
+      email,
+      loginAttemptId,
+      "2FACode": TwoFACode,
+      rememberDevice,
+    }),
   }).then((response) => {
     if (response.ok) {
       TwoFAForm.email.value = "";
       TwoFAForm.email_code.value = "";
       TwoFAForm.login_attempt_id.value = "";
+      TwoFAForm.rememberDevice.checked = false;
       TwoFAErrAlter.style.display = "none";
       alert("You have successfully logged in.");
       loginSection.style.display = "block";
       twoFASection.style.display = "none";
       signupSection.style.display = "none";
-      window.location.href = "{{app_service_external_address}}";
+      redirectAfterLogin();
     } else {
       response.json().then((data) => {
         let error_msg = data.error;
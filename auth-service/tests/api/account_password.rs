@@ -0,0 +1,108 @@
+use auth_service::ErrorResponse;
+use test_context::test_context;
+
+use crate::helpers::{sign_in, TestApp, PASSWORD_HISTORY_SIZE};
+
+async fn change_password(
+    app: &TestApp,
+    current_password: &str,
+    new_password: &str,
+) -> reqwest::Response {
+    app.put_account_password(&serde_json::json!({
+        "currentPassword": current_password,
+        "newPassword": new_password
+    }))
+    .await
+}
+
+async fn login(app: &TestApp, email: &str, password: &str) -> u16 {
+    app.post_login(&serde_json::json!({
+        "email": email,
+        "password": password,
+    }))
+    .await
+    .status()
+    .as_u16()
+}
+
+async fn error(response: reqwest::Response) -> String {
+    response
+        .json::<ErrorResponse>()
+        .await
+        .expect("Could not deserialize response body to ErrorResponse")
+        .error
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_change_password(app: &mut TestApp) {
+    let email = sign_in(app).await;
+
+    let response = change_password(app, "password", "new-password").await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(login(app, &email, "password").await, 401);
+    assert_eq!(login(app, &email, "new-password").await, 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_password_was_used_recently(app: &mut TestApp) {
+    sign_in(app).await;
+
+    let response = change_password(app, "password", "password").await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        error(response).await,
+        "Password was used recently. Choose another one."
+    );
+
+    let mut current = "password".to_owned();
+    for i in 1..PASSWORD_HISTORY_SIZE {
+        let next = format!("new-password-{}", i);
+        let response = change_password(app, &current, &next).await;
+        assert_eq!(response.status().as_u16(), 200);
+        current = next;
+    }
+    // Still one of the last PASSWORD_HISTORY_SIZE
+    let response = change_password(app, &current, "password").await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = change_password(app, &current, "newest-password").await;
+    assert_eq!(response.status().as_u16(), 200);
+    // Now old enough to use again
+    let response = change_password(app, "newest-password", "password").await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_current_password_is_incorrect(app: &mut TestApp) {
+    let email = sign_in(app).await;
+
+    let response = change_password(app, "wrong-password", "new-password").await;
+
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(login(app, &email, "password").await, 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_new_password_breaks_the_policy(
+    app: &mut TestApp,
+) {
+    sign_in(app).await;
+
+    let response = change_password(app, "password", "short").await;
+
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(error(response).await, "Password does not meet the policy");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_no_session(app: &mut TestApp) {
+    let response = change_password(app, "password", "new-password").await;
+
+    assert_eq!(response.status().as_u16(), 400);
+}
@@ -0,0 +1,162 @@
+use crate::helpers::{add_service_account, get_random_email, sign_in, TestApp};
+use auth_service::{
+    domain::{AuditAction, AuditEvent, AuditOutcome},
+    routes::{CreateServiceAccountResponse, TokenResponse},
+    utils::constants::{ADMIN_SCOPE, REQUEST_ID_HEADER},
+};
+use test_context::test_context;
+
+fn summarize(
+    events: &[AuditEvent],
+) -> Vec<(AuditAction, AuditOutcome, Option<&str>)> {
+    events
+        .iter()
+        .map(|event| (event.action, event.outcome, event.actor.as_deref()))
+        .collect()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_audit_user_lifecycle(app: &mut TestApp) {
+    let email = sign_in(app).await;
+    assert_eq!(app.post_logout().await.status().as_u16(), 200);
+    let response = app
+        .delete_user(&serde_json::json!({ "email": email }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let events = app.audit_sink.events().await;
+
+    assert_eq!(
+        summarize(&events),
+        vec![
+            (AuditAction::Signup, AuditOutcome::Success, None),
+            (
+                AuditAction::Login,
+                AuditOutcome::Success,
+                Some(email.as_str())
+            ),
+            (
+                AuditAction::Logout,
+                AuditOutcome::Success,
+                Some(email.as_str())
+            ),
+            (AuditAction::DeleteUser, AuditOutcome::Success, None),
+        ]
+    );
+    assert!(events
+        .iter()
+        .all(|event| event.target.as_deref() == Some(email.as_str())
+            && event.ip.as_deref() == Some("127.0.0.1")
+            && event.request_id.is_some()));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_audit_failures(app: &mut TestApp) {
+    let email = sign_in(app).await;
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 409);
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": get_random_email(),
+            "password": "password"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let events = app.audit_sink.events().await;
+
+    assert_eq!(
+        summarize(&events[2..]),
+        vec![
+            (AuditAction::Signup, AuditOutcome::Failure, None),
+            (AuditAction::Login, AuditOutcome::Failure, None),
+        ]
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_record_request_id_of_response(app: &mut TestApp) {
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": get_random_email(),
+            "password": "password"
+        }))
+        .await;
+    let request_id = response
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .expect("No request id header")
+        .to_str()
+        .unwrap()
+        .to_owned();
+
+    let events = app.audit_sink.events().await;
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].request_id, Some(request_id));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_audit_admin_actions(app: &mut TestApp) {
+    let (admin_id, admin_secret) = add_service_account(app, ADMIN_SCOPE).await;
+    let admin_token = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "client_credentials",
+            "client_id": admin_id,
+            "client_secret": admin_secret
+        }))
+        .await
+        .json::<TokenResponse>()
+        .await
+        .unwrap()
+        .access_token;
+
+    let response = app
+        .post_service_account(
+            &serde_json::json!({ "name": "Reports", "scope": "tokens:verify" }),
+            &admin_token,
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let account = response
+        .json::<CreateServiceAccountResponse>()
+        .await
+        .unwrap();
+    let response = app
+        .delete_service_account(&account.client_id, &admin_token)
+        .await;
+    assert_eq!(response.status().as_u16(), 204);
+    let response = app
+        .delete_service_account(&account.client_id, &admin_token)
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let events = app.audit_sink.events().await;
+
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| (event.action, event.outcome))
+            .collect::<Vec<_>>(),
+        vec![
+            (AuditAction::CreateServiceAccount, AuditOutcome::Success),
+            (AuditAction::DeleteServiceAccount, AuditOutcome::Success),
+            (AuditAction::DeleteServiceAccount, AuditOutcome::Failure),
+        ]
+    );
+    assert!(events
+        .iter()
+        .all(|event| event.actor.as_deref() == Some(admin_id.as_str())
+            && event.target.as_deref() == Some(account.client_id.as_str())));
+}
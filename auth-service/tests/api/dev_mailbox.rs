@@ -0,0 +1,96 @@
+use auth_service::{
+    domain::{Email, EmailClient},
+    routes::MailboxEntryResponse,
+    services::file_email_client::MailboxMessage,
+    utils::constants::MAILBOX_SENDER_ADDRESS,
+};
+use secrecy::Secret;
+use test_context::test_context;
+use uuid::Uuid;
+
+use crate::helpers::{get_random_email, TestApp};
+
+async fn send_email(app: &TestApp, recipient: &str, subject: &str) {
+    app.mailbox
+        .send_email(
+            &Uuid::new_v4().to_string(),
+            &Email::parse(Secret::new(recipient.to_owned())).unwrap(),
+            subject,
+            "<p>Your code is <strong>123456</strong></p>",
+            "Your code is 123456",
+        )
+        .await
+        .unwrap();
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_list_messages_newest_first(app: &mut TestApp) {
+    let first_recipient = get_random_email();
+    let second_recipient = get_random_email();
+    send_email(app, &first_recipient, "First").await;
+    send_email(app, &second_recipient, "Second").await;
+
+    let response = app.get_mailbox(&()).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let messages = response
+        .json::<Vec<MailboxEntryResponse>>()
+        .await
+        .expect("Could not deserialize response body to mailbox entries");
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].subject, "Second");
+    assert_eq!(messages[0].to, second_recipient);
+    assert_eq!(messages[0].from, MAILBOX_SENDER_ADDRESS);
+    assert_eq!(messages[1].subject, "First");
+    assert_eq!(messages[1].to, first_recipient);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_filter_messages_by_recipient(app: &mut TestApp) {
+    let recipient = get_random_email();
+    send_email(app, &recipient, "Mine").await;
+    send_email(app, &get_random_email(), "Someone else's").await;
+
+    let response = app.get_mailbox(&[("to", &recipient)]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let messages = response
+        .json::<Vec<MailboxEntryResponse>>()
+        .await
+        .expect("Could not deserialize response body to mailbox entries");
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].subject, "Mine");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_the_message_bodies(app: &mut TestApp) {
+    let recipient = get_random_email();
+    send_email(app, &recipient, "Your code").await;
+    let entries = app
+        .get_mailbox(&())
+        .await
+        .json::<Vec<MailboxEntryResponse>>()
+        .await
+        .unwrap();
+
+    let response = app.get_mailbox_message(&entries[0].id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let message = response
+        .json::<MailboxMessage>()
+        .await
+        .expect("Could not deserialize response body to a mailbox message");
+    assert_eq!(message.id, entries[0].id);
+    assert_eq!(message.to, recipient);
+    assert_eq!(message.subject, "Your code");
+    assert!(message.text_body.contains("Your code is 123456"));
+    assert!(message.html_body.contains("<strong>123456</strong>"));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_an_unknown_message(app: &mut TestApp) {
+    let response = app.get_mailbox_message("unknown").await;
+
+    assert_eq!(response.status().as_u16(), 404);
+}
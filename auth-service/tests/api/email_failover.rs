@@ -0,0 +1,228 @@
+use std::{sync::Arc, time::Duration};
+
+use auth_service::{
+    app_state::EmailClientType,
+    domain::{Email, EmailClient, EmailProviderMetrics},
+    services::failover_email_client::FailoverEmailClient,
+    utils::constants::{test, ADMIN_SCOPE, VERIFY_TOKEN_SCOPE},
+};
+use color_eyre::eyre::Result;
+use secrecy::Secret;
+use test_context::test_context;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use crate::helpers::{
+    configure_postmark_email_client, get_service_token, TestApp,
+};
+
+// Never answers within the failover timeout
+struct HangingEmailClient;
+
+#[async_trait::async_trait]
+impl EmailClient for HangingEmailClient {
+    async fn send_email(
+        &self,
+        _idempotency_key: &str,
+        _recipient: &Email,
+        _subject: &str,
+        _html_body: &str,
+        _text_body: &str,
+    ) -> Result<()> {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        Ok(())
+    }
+}
+
+async fn mock_postmark(status: u16, expected_calls: u64) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(status))
+        .expect(expected_calls)
+        .mount(&server)
+        .await;
+    server
+}
+
+fn postmark(server: &MockServer) -> EmailClientType {
+    Arc::new(configure_postmark_email_client(server.uri()))
+}
+
+fn failover_email_client(
+    providers: Vec<(&str, EmailClientType)>,
+) -> FailoverEmailClient {
+    FailoverEmailClient::new(
+        providers
+            .into_iter()
+            .map(|(name, email_client)| (name.to_owned(), email_client))
+            .collect(),
+        test::email_failover::TIMEOUT,
+        test::email_failover::FAILURE_THRESHOLD,
+        test::email_failover::OPEN_DURATION,
+    )
+}
+
+async fn send(email_client: &FailoverEmailClient) -> Result<()> {
+    email_client
+        .send_email(
+            "message-1",
+            &Email::parse(Secret::new("user@example.com".to_owned())).unwrap(),
+            "Subject",
+            "<p>Content</p>",
+            "Content",
+        )
+        .await
+}
+
+fn metrics(
+    provider: &str,
+    sent: u64,
+    failed: u64,
+    skipped: u64,
+    circuit: &str,
+) -> EmailProviderMetrics {
+    EmailProviderMetrics {
+        provider: provider.to_owned(),
+        sent,
+        failed,
+        skipped,
+        circuit: circuit.to_owned(),
+    }
+}
+
+#[tokio::test]
+async fn should_send_with_the_first_provider_that_works() {
+    let primary = mock_postmark(200, 1).await;
+    let backup = mock_postmark(200, 0).await;
+    let email_client = failover_email_client(vec![
+        ("postmark", postmark(&primary)),
+        ("smtp", postmark(&backup)),
+    ]);
+
+    send(&email_client).await.unwrap();
+
+    assert_eq!(
+        email_client.delivery_metrics(),
+        [
+            metrics("postmark", 1, 0, 0, "closed"),
+            metrics("smtp", 0, 0, 0, "closed")
+        ]
+    );
+}
+
+#[tokio::test]
+async fn should_fail_over_when_a_provider_errors() {
+    let primary = mock_postmark(500, 1).await;
+    let backup = mock_postmark(200, 1).await;
+    let email_client = failover_email_client(vec![
+        ("postmark", postmark(&primary)),
+        ("smtp", postmark(&backup)),
+    ]);
+
+    send(&email_client).await.unwrap();
+
+    assert_eq!(
+        email_client.delivery_metrics(),
+        [
+            metrics("postmark", 0, 1, 0, "closed"),
+            metrics("smtp", 1, 0, 0, "closed")
+        ]
+    );
+}
+
+#[tokio::test]
+async fn should_fail_over_when_a_provider_times_out() {
+    let backup = mock_postmark(200, 1).await;
+    let email_client = failover_email_client(vec![
+        ("postmark", Arc::new(HangingEmailClient)),
+        ("smtp", postmark(&backup)),
+    ]);
+
+    send(&email_client).await.unwrap();
+
+    assert_eq!(email_client.delivery_metrics()[0].failed, 1);
+    assert_eq!(email_client.delivery_metrics()[1].sent, 1);
+}
+
+#[tokio::test]
+async fn should_skip_a_provider_while_its_circuit_is_open() {
+    let primary = mock_postmark(500, 2).await;
+    let backup = mock_postmark(200, 3).await;
+    let email_client = failover_email_client(vec![
+        ("postmark", postmark(&primary)),
+        ("smtp", postmark(&backup)),
+    ]);
+
+    for _ in 0..3 {
+        send(&email_client).await.unwrap();
+    }
+    assert_eq!(
+        email_client.delivery_metrics()[0],
+        metrics("postmark", 0, 2, 1, "open")
+    );
+    primary.verify().await;
+
+    // Once it has recovered, a trial send closes the circuit again
+    primary.reset().await;
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&primary)
+        .await;
+    tokio::time::sleep(test::email_failover::OPEN_DURATION).await;
+
+    send(&email_client).await.unwrap();
+    assert_eq!(
+        email_client.delivery_metrics()[0],
+        metrics("postmark", 1, 2, 1, "closed")
+    );
+}
+
+#[tokio::test]
+async fn should_fail_when_every_provider_fails() {
+    let primary = mock_postmark(500, 1).await;
+    let backup = mock_postmark(500, 1).await;
+    let email_client = failover_email_client(vec![
+        ("postmark", postmark(&primary)),
+        ("smtp", postmark(&backup)),
+    ]);
+
+    let error = send(&email_client).await.unwrap_err();
+
+    assert!(error.to_string().contains("Every email provider failed"));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_email_provider_metrics_with_admin_token(
+    app: &mut TestApp,
+) {
+    let admin_token = get_service_token(app, ADMIN_SCOPE).await;
+
+    let response = app.get_email_provider_metrics(&admin_token).await;
+    assert_eq!(response.status().as_u16(), 200);
+    // The test app has a single provider, so there's nothing to fail over to
+    let metrics = response
+        .json::<Vec<EmailProviderMetrics>>()
+        .await
+        .expect("Could not deserialize response body to metrics");
+    assert!(metrics.is_empty());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_require_admin_scope_for_email_provider_metrics(
+    app: &mut TestApp,
+) {
+    let service_token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
+
+    let response = app.get_email_provider_metrics(&service_token).await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let response = app.get_email_provider_metrics("invalid").await;
+    assert_eq!(response.status().as_u16(), 401);
+}
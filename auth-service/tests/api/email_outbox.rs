@@ -0,0 +1,131 @@
+use crate::helpers::{get_random_email, TestApp};
+use auth_service::domain::{Email, OutboxEmail};
+use secrecy::Secret;
+use test_context::test_context;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+async fn start_2fa_login(app: &TestApp) -> reqwest::Response {
+    let email = get_random_email();
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    app.post_login(&serde_json::json!({
+        "email": email,
+        "password": "password123"
+    }))
+    .await
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_wait_for_the_email_provider(app: &mut TestApp) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(3)
+        .mount(&app.email_server)
+        .await;
+
+    let response = start_2fa_login(app).await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    app.wait_for_outbox().await;
+    let failed = app
+        .email_outbox_store
+        .read()
+        .await
+        .get_failed_emails()
+        .await
+        .unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].attempts, 3);
+    assert_eq!(failed[0].subject, "Your LGR Bootcamp sign-in code");
+    assert!(failed[0].last_error.is_some());
+
+    // Every attempt carries the same idempotency key
+    let requests = app.email_server.received_requests().await.unwrap();
+    for request in requests {
+        let body = request.body_json::<serde_json::Value>().unwrap();
+        assert_eq!(body["Metadata"]["idempotency_key"], failed[0].id);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_retry_failed_emails(app: &mut TestApp) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let response = start_2fa_login(app).await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    app.wait_for_outbox().await;
+    let requests = app.email_server.received_requests().await.unwrap();
+    assert_eq!(requests[0].body, requests[1].body);
+    assert!(app
+        .email_outbox_store
+        .read()
+        .await
+        .get_failed_emails()
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_let_two_workers_claim_the_same_email(app: &mut TestApp) {
+    // Due far in the future, so this app's own worker leaves it alone
+    let due_at = 1 << 50;
+    let email = OutboxEmail::new(
+        Email::parse(Secret::new(get_random_email())).unwrap(),
+        "Subject".to_owned(),
+        "<p>Content</p>".to_owned(),
+        "Content".to_owned(),
+        due_at,
+    );
+    let mut outbox_store = app.email_outbox_store.write().await;
+    outbox_store.add_email(email.clone()).await.unwrap();
+
+    let claimed = outbox_store
+        .claim_due_emails(due_at, due_at + 100, 10)
+        .await
+        .unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].id, email.id);
+    assert!(outbox_store
+        .claim_due_emails(due_at, due_at + 100, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Once the claim runs out, the email can be claimed again
+    let claimed = outbox_store
+        .claim_due_emails(due_at + 100, due_at + 200, 10)
+        .await
+        .unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].next_attempt_at, due_at + 200);
+
+    outbox_store.remove_email(&email.id).await.unwrap();
+}
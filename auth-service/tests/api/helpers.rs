@@ -1,15 +1,44 @@
+use argon2::Params;
 use auth_service::{
-    app_state::{AppState, BannedTokenStoreType, TwoFACodeStoreType},
-    domain::Email,
+    app_state::{
+        AppState, BannedTokenStoreType, BreachedPasswordCheckerType,
+        EmailOutboxStoreType, MailboxType, ServiceAccountStoreType,
+        TwoFACodeStoreType, WebhookStoreType,
+    },
+    domain::{
+        ClientSecret, Email, EmailDomainPolicy, PasswordPolicy, Scopes,
+        ServiceAccount,
+    },
     get_postgres_pool, get_redis_client,
+    routes::{
+        DeviceAuthorizationResponse, RegisterClientResponse, TokenResponse,
+    },
     services::{
+        argon2_password_hasher::{Argon2PasswordHasher, Pepper},
         data_stores::{
-            PostgresUserStore, RedisBannedTokenStore, RedisTwoFACodeStore,
+            PostgresEmailOutboxStore, PostgresLoginHistoryStore,
+            PostgresOAuthClientStore, PostgresServiceAccountStore,
+            PostgresTrustedDeviceStore, PostgresUserStore,
+            PostgresWebhookStore, RedisAuthorizationCodeStore,
+            RedisBannedTokenStore, RedisDeviceCodeStore,
+            RedisRefreshTokenStore, RedisTwoFACodeStore,
         },
+        email_outbox::EmailOutbox,
+        file_email_client::FileEmailClient,
+        hibp_breached_password_checker::HibpBreachedPasswordChecker,
+        in_memory_audit_sink::InMemoryAuditSink,
+        no_op_breached_password_checker::NoOpBreachedPasswordChecker,
+        no_op_claims_enricher::NoOpClaimsEnricher,
+        password_hashers::PasswordHashers,
         postmark_email_client::PostmarkEmailClient,
+        retry_policy::RetryPolicy,
+        webhook_dispatcher::WebhookDispatcher,
     },
+    utils::client_info::TrustedProxies,
     utils::constants::{
-        test, DATABASE_URL, POSTMARK_EMAIL_SENDER_ADDRESS, REDIS_HOST_NAME,
+        test, ADMIN_SCOPE, DATABASE_URL, DEFAULT_PASSWORD_HASH_ITERATIONS,
+        DEFAULT_PASSWORD_HASH_MEMORY_KIB, DEFAULT_PASSWORD_HASH_PARALLELISM,
+        MAILBOX_SENDER_ADDRESS, POSTMARK_EMAIL_SENDER_ADDRESS, REDIS_HOST_NAME,
     },
     Application,
 };
@@ -19,46 +48,147 @@ use sqlx::{
     postgres::{PgConnectOptions, PgConnection, PgPoolOptions},
     Connection, Executor, PgPool,
 };
-use std::{str::FromStr, sync::Arc};
+use std::{path::PathBuf, str::FromStr, sync::Arc};
 use test_context::AsyncTestContext;
-use tokio::sync::RwLock;
+use tokio::{sync::RwLock, task::JoinHandle};
 use uuid::Uuid;
 use wiremock::MockServer;
 
 pub struct TestApp {
     pub address: String,
+    pub audit_sink: Arc<InMemoryAuditSink>,
     pub banned_token_store: BannedTokenStoreType,
     pub cookie_jar: Arc<Jar>,
+    pub email_outbox_store: EmailOutboxStoreType,
     pub email_server: MockServer,
     pub http_client: reqwest::Client,
+    pub mailbox: MailboxType,
+    mailbox_dir: PathBuf,
+    pg_pool: PgPool,
+    pub service_account_store: ServiceAccountStoreType,
     pub tmp_db_name: String,
     pub two_fa_code_store: TwoFACodeStoreType,
+    pub webhook_server: MockServer,
+    pub webhook_store: WebhookStoreType,
+    workers: Vec<JoinHandle<()>>,
 }
 
 impl TestApp {
+    // Passwords aren't checked for breaches, see `BreachCheckTestApp`
     pub async fn new() -> Self {
+        Self::build(Arc::new(NoOpBreachedPasswordChecker)).await
+    }
+
+    async fn build(
+        breached_password_checker: BreachedPasswordCheckerType,
+    ) -> Self {
         let tmp_db_name = Uuid::new_v4().to_string();
         let pg_pool = configure_postgresql(&tmp_db_name).await;
-        let user_store = Arc::new(RwLock::new(PostgresUserStore::new(pg_pool)));
+        let user_store = Arc::new(RwLock::new(PostgresUserStore::new(
+            pg_pool.clone(),
+            PasswordHashers::with_legacy_formats(Argon2PasswordHasher::new(
+                password_hash_params(),
+                vec![Pepper::new(
+                    PASSWORD_PEPPER_VERSION,
+                    Secret::new(PASSWORD_PEPPER_SECRET.to_owned()),
+                )],
+            )),
+            PASSWORD_HISTORY_SIZE,
+        )));
+        let oauth_client_store = Arc::new(RwLock::new(
+            PostgresOAuthClientStore::new(pg_pool.clone()),
+        ));
+        let service_account_store = Arc::new(RwLock::new(
+            PostgresServiceAccountStore::new(pg_pool.clone()),
+        ));
+        let trusted_device_store = Arc::new(RwLock::new(
+            PostgresTrustedDeviceStore::new(pg_pool.clone()),
+        ));
+        let login_history_store = Arc::new(RwLock::new(
+            PostgresLoginHistoryStore::new(pg_pool.clone()),
+        ));
+        let email_outbox_store: EmailOutboxStoreType = Arc::new(RwLock::new(
+            PostgresEmailOutboxStore::new(pg_pool.clone()),
+        ));
+        let webhook_store: WebhookStoreType =
+            Arc::new(RwLock::new(PostgresWebhookStore::new(pg_pool.clone())));
 
         let redis_connection = Arc::new(RwLock::new(configure_redis()));
         let banned_token_store = Arc::new(RwLock::new(
             RedisBannedTokenStore::new(redis_connection.clone()),
         ));
 
-        let two_fa_code_store =
-            Arc::new(RwLock::new(RedisTwoFACodeStore::new(redis_connection)));
+        let two_fa_code_store = Arc::new(RwLock::new(
+            RedisTwoFACodeStore::new(redis_connection.clone()),
+        ));
+        let authorization_code_store = Arc::new(RwLock::new(
+            RedisAuthorizationCodeStore::new(redis_connection.clone()),
+        ));
+        let device_code_store = Arc::new(RwLock::new(
+            RedisDeviceCodeStore::new(redis_connection.clone()),
+        ));
+        let refresh_token_store = Arc::new(RwLock::new(
+            RedisRefreshTokenStore::new(redis_connection),
+        ));
 
         let email_server = MockServer::start().await;
         let base_url = email_server.uri();
-        let email_client = Arc::new(configure_postmark_email_client(base_url));
+        let email_outbox = Arc::new(configure_email_outbox(
+            email_outbox_store.clone(),
+            base_url,
+        ));
+
+        let audit_sink = Arc::new(InMemoryAuditSink::default());
+
+        // Emails go to Postmark, but `/dev/mailbox` is served so it can be
+        // tested, with messages written to the mailbox directly
+        let mailbox_dir = std::env::temp_dir().join(&tmp_db_name);
+        let mailbox = Arc::new(FileEmailClient::new(
+            mailbox_dir.clone(),
+            Email::parse(Secret::new(MAILBOX_SENDER_ADDRESS.to_owned()))
+                .unwrap(),
+        ));
+
+        let webhook_server = MockServer::start().await;
+        let webhook_dispatcher = Arc::new(configure_webhook_dispatcher(
+            webhook_store.clone(),
+            format!("{}/webhooks", webhook_server.uri()),
+        ));
 
         let app_state = AppState::new(
             user_store,
             banned_token_store.clone(),
             two_fa_code_store.clone(),
-            email_client,
+            email_outbox.clone(),
+            oauth_client_store,
+            authorization_code_store,
+            refresh_token_store,
+            service_account_store.clone(),
+            device_code_store,
+            Arc::new(NoOpClaimsEnricher),
+            trusted_device_store,
+            login_history_store,
+            audit_sink.clone(),
+            webhook_dispatcher.clone(),
+            breached_password_checker,
+            Arc::new(PasswordPolicy {
+                banned_words: vec![BANNED_PASSWORD_WORD.to_owned()],
+                ..PasswordPolicy::default()
+            }),
+            Arc::new(EmailDomainPolicy {
+                denied_domains: vec![DENIED_EMAIL_DOMAIN.to_owned()],
+                disposable_domains: [DISPOSABLE_EMAIL_DOMAIN.to_owned()].into(),
+                ..EmailDomainPolicy::default()
+            }),
+            // Requests all come from localhost, so X-Real-IP can be tested
+            Arc::new(TrustedProxies::parse(&["127.0.0.1".to_owned()]).unwrap()),
+            Some(mailbox.clone()),
+            None,
         );
+        let workers = vec![
+            tokio::spawn(webhook_dispatcher.run()),
+            tokio::spawn(email_outbox.run()),
+        ];
 
         let app = Application::build(app_state, test::APP_ADDRESS)
             .await
@@ -71,23 +201,87 @@ impl TestApp {
         let cookie_jar = Arc::new(Jar::default());
         let http_client = reqwest::Client::builder()
             .cookie_provider(cookie_jar.clone())
+            // OAuth tests need to inspect the redirects themselves
+            .redirect(reqwest::redirect::Policy::none())
             .build()
             .unwrap();
 
         Self {
             address,
+            audit_sink,
             banned_token_store,
             cookie_jar,
+            email_outbox_store,
             email_server,
             http_client,
+            mailbox,
+            mailbox_dir,
+            pg_pool,
+            service_account_store,
             tmp_db_name,
             two_fa_code_store,
+            webhook_server,
+            webhook_store,
+            workers,
         }
     }
 
+    // Password hashes are read and written directly, as no route exposes them
+    pub async fn get_password_hash(&self, email: &str) -> String {
+        sqlx::query_scalar("SELECT password_hash FROM users WHERE email = $1")
+            .bind(email)
+            .fetch_one(&self.pg_pool)
+            .await
+            .expect("Failed to get password hash")
+    }
+
+    pub async fn set_password_hash(&self, email: &str, password_hash: &str) {
+        sqlx::query("UPDATE users SET password_hash = $1 WHERE email = $2")
+            .bind(password_hash)
+            .bind(email)
+            .execute(&self.pg_pool)
+            .await
+            .expect("Failed to set password hash");
+    }
+
+    // A user store like the app's, but with its own peppers, as if they'd
+    // been rotated
+    pub fn new_user_store(&self, peppers: Vec<Pepper>) -> PostgresUserStore {
+        PostgresUserStore::new(
+            self.pg_pool.clone(),
+            PasswordHashers::with_legacy_formats(Argon2PasswordHasher::new(
+                password_hash_params(),
+                peppers,
+            )),
+            PASSWORD_HISTORY_SIZE,
+        )
+    }
+
+    // A webhook store like another replica's, sharing only the database
+    pub fn new_webhook_store(&self) -> PostgresWebhookStore {
+        PostgresWebhookStore::new(self.pg_pool.clone())
+    }
+
+    // Wait until every queued email has been sent or has failed
+    pub async fn wait_for_outbox(&self) {
+        for _ in 0..100 {
+            let pending: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM email_outbox WHERE failed_at IS NULL",
+            )
+            .fetch_one(&self.pg_pool)
+            .await
+            .expect("Failed to count pending emails");
+            if pending == 0 {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        panic!("Timed out waiting for the email outbox");
+    }
+
     pub async fn get_root(&self) -> reqwest::Response {
         self.http_client
-            .get(&format!("{}/", &self.address))
+            .get(format!("{}/", &self.address))
             .send()
             .await
             .expect("Failed to execute request")
@@ -98,7 +292,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.http_client
-            .post(&format!("{}/signup", &self.address))
+            .post(format!("{}/signup", &self.address))
             .json(body)
             .send()
             .await
@@ -110,7 +304,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.http_client
-            .post(&format!("{}/login", &self.address))
+            .post(format!("{}/login", &self.address))
             .json(body)
             .send()
             .await
@@ -119,7 +313,7 @@ impl TestApp {
 
     pub async fn post_logout(&self) -> reqwest::Response {
         self.http_client
-            .post(&format!("{}/logout", &self.address))
+            .post(format!("{}/logout", &self.address))
             .send()
             .await
             .expect("Failed to execute request")
@@ -130,7 +324,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.http_client
-            .post(&format!("{}/verify-2fa", &self.address))
+            .post(format!("{}/verify-2fa", &self.address))
             .json(body)
             .send()
             .await
@@ -140,12 +334,14 @@ impl TestApp {
     pub async fn post_verify_token<Body>(
         &self,
         body: &Body,
+        service_token: &str,
     ) -> reqwest::Response
     where
         Body: serde::Serialize,
     {
         self.http_client
-            .post(&format!("{}/verify-token", &self.address))
+            .post(format!("{}/verify-token", &self.address))
+            .bearer_auth(service_token)
             .json(body)
             .send()
             .await
@@ -157,12 +353,339 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.http_client
-            .delete(&format!("{}/delete-user", &self.address))
+            .delete(format!("{}/delete-user", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_oauth_client<Body>(
+        &self,
+        body: &Body,
+        service_token: &str,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/oauth/clients", &self.address))
+            .bearer_auth(service_token)
             .json(body)
             .send()
             .await
             .expect("Failed to execute request")
     }
+
+    pub async fn get_oauth_authorize<Query>(
+        &self,
+        query: &Query,
+    ) -> reqwest::Response
+    where
+        Query: serde::Serialize,
+    {
+        self.http_client
+            .get(format!("{}/oauth/authorize", &self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_oauth_authorize<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/oauth/authorize", &self.address))
+            .form(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_oauth_token<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/oauth/token", &self.address))
+            .form(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_oauth_introspect<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/oauth/introspect", &self.address))
+            .form(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_oauth_revoke<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/oauth/revoke", &self.address))
+            .form(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_oauth_device_authorization<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/oauth/device_authorization", &self.address))
+            .form(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_oauth_device<Query>(
+        &self,
+        query: &Query,
+    ) -> reqwest::Response
+    where
+        Query: serde::Serialize,
+    {
+        self.http_client
+            .get(format!("{}/oauth/device", &self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_oauth_device<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/oauth/device", &self.address))
+            .form(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_service_account<Body>(
+        &self,
+        body: &Body,
+        service_token: &str,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/admin/service-accounts", &self.address))
+            .bearer_auth(service_token)
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_service_accounts(
+        &self,
+        service_token: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/service-accounts", &self.address))
+            .bearer_auth(service_token)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_postmark_webhook<Body>(
+        &self,
+        body: &Body,
+        client_id: &str,
+        client_secret: &str,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/webhooks/postmark", &self.address))
+            .basic_auth(client_id, Some(client_secret))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_undeliverable_emails(
+        &self,
+        service_token: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/undeliverable-emails", &self.address))
+            .bearer_auth(service_token)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_undeliverable_email(
+        &self,
+        email: &str,
+        service_token: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .delete(format!(
+                "{}/admin/undeliverable-emails/{}",
+                &self.address, email
+            ))
+            .bearer_auth(service_token)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_user_import(
+        &self,
+        body: String,
+        content_type: &str,
+        service_token: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/admin/users/import", &self.address))
+            .bearer_auth(service_token)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_email_provider_metrics(
+        &self,
+        service_token: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/email-providers", &self.address))
+            .bearer_auth(service_token)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_service_account(
+        &self,
+        client_id: &str,
+        service_token: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .delete(format!(
+                "{}/admin/service-accounts/{}",
+                &self.address, client_id
+            ))
+            .bearer_auth(service_token)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_login_history(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/account/login-history", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn put_account_2fa<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .put(format!("{}/account/2fa", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn put_account_password<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .put(format!("{}/account/password", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_mailbox<Query>(&self, query: &Query) -> reqwest::Response
+    where
+        Query: serde::Serialize,
+    {
+        self.http_client
+            .get(format!("{}/dev/mailbox", &self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_mailbox_message(&self, id: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/dev/mailbox/{}", &self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_trusted_devices(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/trusted-devices", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_trusted_device(
+        &self,
+        device_id: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/trusted-devices/{}", &self.address, device_id))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
 }
 
 impl AsyncTestContext for TestApp {
@@ -171,18 +694,259 @@ impl AsyncTestContext for TestApp {
     }
 
     async fn teardown(self) {
+        // Emails are sent in the background, and the email server checks its
+        // expectations when dropped
+        self.wait_for_outbox().await;
+        // Stop the workers polling the database before it's dropped
+        for worker in &self.workers {
+            worker.abort();
+        }
         delete_database(&self.tmp_db_name).await;
+        let _ = std::fs::remove_dir_all(&self.mailbox_dir);
     }
 }
 
+// A test app that checks passwords against a stand-in for the Pwned
+// Passwords range API, with each test mounting the ranges it needs
+pub struct BreachCheckTestApp {
+    pub app: TestApp,
+    pub breached_password_server: MockServer,
+}
+
+impl AsyncTestContext for BreachCheckTestApp {
+    async fn setup() -> BreachCheckTestApp {
+        let breached_password_server = MockServer::start().await;
+        let app = TestApp::build(Arc::new(HibpBreachedPasswordChecker::new(
+            breached_password_server.uri(),
+            Client::builder()
+                .timeout(test::breached_passwords::TIMEOUT)
+                .build()
+                .unwrap(),
+        )))
+        .await;
+
+        BreachCheckTestApp {
+            app,
+            breached_password_server,
+        }
+    }
+
+    async fn teardown(self) {
+        self.app.teardown().await;
+    }
+}
+
+pub const WEBHOOK_SECRET: &str = "webhook_secret";
+
+// The Argon2id cost the test app hashes passwords with
+pub fn password_hash_params() -> Params {
+    Params::new(
+        DEFAULT_PASSWORD_HASH_MEMORY_KIB,
+        DEFAULT_PASSWORD_HASH_ITERATIONS,
+        DEFAULT_PASSWORD_HASH_PARALLELISM,
+        None,
+    )
+    .unwrap()
+}
+
+// The pepper the test app mixes into new password hashes
+pub const PASSWORD_PEPPER_VERSION: u32 = 1;
+pub const PASSWORD_PEPPER_SECRET: &str = "test-pepper";
+
+// How many recent passwords the test app refuses to reuse
+pub const PASSWORD_HISTORY_SIZE: usize = 3;
+
+// Refused at signup by the test app's email domain policy
+pub const DENIED_EMAIL_DOMAIN: &str = "denied.example.com";
+pub const DISPOSABLE_EMAIL_DOMAIN: &str = "mailinator.com";
+
+// Banned by the test app's password policy
+pub const BANNED_PASSWORD_WORD: &str = "bootcamp";
+
 pub fn get_random_email() -> String {
     format!("{}@example.com", Uuid::new_v4())
 }
 
+// PKCE example values from RFC 7636 appendix B
+pub const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+pub const CODE_CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+pub const REDIRECT_URI: &str = "http://localhost:8000/callback";
+
+// Sign up and log in a new user without 2FA, leaving the session cookie in the jar
+pub async fn sign_in(app: &TestApp) -> String {
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    email
+}
+
+pub async fn register_client(
+    app: &TestApp,
+    token_endpoint_auth_method: &str,
+) -> RegisterClientResponse {
+    let service_token = get_service_token(app, ADMIN_SCOPE).await;
+    let response = app
+        .post_oauth_client(
+            &serde_json::json!({
+                "client_name": "Test client",
+                "redirect_uris": [REDIRECT_URI],
+                "scope": "read write",
+                "token_endpoint_auth_method": token_endpoint_auth_method
+            }),
+            &service_token,
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    response
+        .json::<RegisterClientResponse>()
+        .await
+        .expect("Could not deserialize response body to RegisterClientResponse")
+}
+
+// Run the authorization step as the signed-in user, returning the issued code
+pub async fn authorize(app: &TestApp, client_id: &str, scope: &str) -> String {
+    let response = app
+        .post_oauth_authorize(&serde_json::json!({
+            "response_type": "code",
+            "client_id": client_id,
+            "redirect_uri": REDIRECT_URI,
+            "scope": scope,
+            "state": "xyz",
+            "code_challenge": CODE_CHALLENGE,
+            "code_challenge_method": "S256",
+            "decision": "allow"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 303);
+
+    let location = get_location(&response);
+    location
+        .query_pairs()
+        .find(|(key, _)| key == "code")
+        .map(|(_, code)| code.into_owned())
+        .expect("No code in redirect")
+}
+
+// Run the authorization code flow as the signed-in user, returning the tokens
+pub async fn get_tokens(
+    app: &TestApp,
+    client: &RegisterClientResponse,
+    scope: &str,
+) -> TokenResponse {
+    let code = authorize(app, &client.client_id, scope).await;
+
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "authorization_code",
+            "code": code,
+            "redirect_uri": REDIRECT_URI,
+            "code_verifier": CODE_VERIFIER,
+            "client_id": client.client_id,
+            "client_secret": client.client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    response
+        .json::<TokenResponse>()
+        .await
+        .expect("Could not deserialize response body to TokenResponse")
+}
+
+// Start a device authorization for the client, as a device would
+pub async fn authorize_device(
+    app: &TestApp,
+    client: &RegisterClientResponse,
+    scope: &str,
+) -> DeviceAuthorizationResponse {
+    let response = app
+        .post_oauth_device_authorization(&serde_json::json!({
+            "client_id": client.client_id,
+            "client_secret": client.client_secret,
+            "scope": scope
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    response.json::<DeviceAuthorizationResponse>().await.expect(
+        "Could not deserialize response body to DeviceAuthorizationResponse",
+    )
+}
+
+// Create a service account directly in the store, returning its credentials
+pub async fn add_service_account(
+    app: &TestApp,
+    scope: &str,
+) -> (String, String) {
+    let client_secret = ClientSecret::default();
+    let account = ServiceAccount::new(
+        "Test service".to_owned(),
+        Scopes::parse(scope).unwrap(),
+        client_secret.clone(),
+    );
+    let client_id = account.client_id.clone();
+
+    app.service_account_store
+        .write()
+        .await
+        .add_account(account)
+        .await
+        .expect("Failed to add service account");
+
+    (client_id, client_secret.as_ref().expose_secret().to_owned())
+}
+
+// Create a service account and fetch a token for it with the client_credentials grant
+pub async fn get_service_token(app: &TestApp, scope: &str) -> String {
+    let (client_id, client_secret) = add_service_account(app, scope).await;
+
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "client_credentials",
+            "client_id": client_id,
+            "client_secret": client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    response
+        .json::<TokenResponse>()
+        .await
+        .expect("Could not deserialize response body to TokenResponse")
+        .access_token
+}
+
+pub fn get_location(response: &reqwest::Response) -> url::Url {
+    let location = response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .expect("No Location header")
+        .to_str()
+        .unwrap();
+    response.url().join(location).unwrap()
+}
+
 async fn configure_postgresql(db_name: &str) -> PgPool {
     let postgresql_conn_url = DATABASE_URL.to_owned();
 
-    configure_database(&postgresql_conn_url, &db_name).await;
+    configure_database(&postgresql_conn_url, db_name).await;
 
     let postgresql_conn_url_with_db = Secret::new(format!(
         "{}/{}",
@@ -266,7 +1030,24 @@ fn configure_redis() -> redis::Connection {
         .expect("Failed to get Redis connection")
 }
 
-fn configure_postmark_email_client(base_url: String) -> PostmarkEmailClient {
+fn configure_email_outbox(
+    email_outbox_store: EmailOutboxStoreType,
+    base_url: String,
+) -> EmailOutbox {
+    EmailOutbox::new(
+        email_outbox_store,
+        Arc::new(configure_postmark_email_client(base_url)),
+        RetryPolicy {
+            max_attempts: test::email_outbox::MAX_ATTEMPTS,
+            base_delay: test::email_outbox::BASE_RETRY_DELAY,
+            poll_interval: test::email_outbox::POLL_INTERVAL,
+        },
+    )
+}
+
+pub fn configure_postmark_email_client(
+    base_url: String,
+) -> PostmarkEmailClient {
     let postmark_auth_token = Secret::new("auth_token".to_owned());
 
     let sender =
@@ -279,3 +1060,25 @@ fn configure_postmark_email_client(base_url: String) -> PostmarkEmailClient {
 
     PostmarkEmailClient::new(base_url, sender, postmark_auth_token, http_client)
 }
+
+fn configure_webhook_dispatcher(
+    webhook_store: WebhookStoreType,
+    endpoint: String,
+) -> WebhookDispatcher {
+    let http_client = Client::builder()
+        .timeout(test::webhooks::TIMEOUT)
+        .build()
+        .expect("Failed to build HTTP client");
+
+    WebhookDispatcher::new(
+        webhook_store,
+        vec![endpoint],
+        Secret::new(WEBHOOK_SECRET.to_owned()),
+        RetryPolicy {
+            max_attempts: test::webhooks::MAX_ATTEMPTS,
+            base_delay: test::webhooks::BASE_RETRY_DELAY,
+            poll_interval: test::webhooks::POLL_INTERVAL,
+        },
+        http_client,
+    )
+}
@@ -0,0 +1,103 @@
+use crate::helpers::{sign_in, TestApp};
+use auth_service::routes::LoginEventResponse;
+use reqwest::header::USER_AGENT;
+use test_context::test_context;
+use wiremock::{matchers::method, matchers::path, Mock, ResponseTemplate};
+
+async fn login_from(
+    app: &TestApp,
+    email: &str,
+    password: &str,
+    user_agent: &str,
+) -> reqwest::Response {
+    app.http_client
+        .post(format!("{}/login", &app.address))
+        .header(USER_AGENT, user_agent)
+        .header("X-Real-IP", "203.0.113.7")
+        .json(&serde_json::json!({
+            "email": email,
+            "password": password
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request")
+}
+
+async fn mock_email_server(app: &TestApp, expected_emails: u64) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(expected_emails)
+        .mount(&app.email_server)
+        .await;
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_login_history(app: &mut TestApp) {
+    mock_email_server(app, 1).await;
+    let email = sign_in(app).await;
+
+    let response = login_from(app, &email, "wrong-password", "Firefox").await;
+    assert_eq!(response.status().as_u16(), 401);
+    let response = login_from(app, &email, "password", "Firefox").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.get_login_history().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    let events = response
+        .json::<Vec<LoginEventResponse>>()
+        .await
+        .expect("Could not deserialize response body to login events");
+    assert_eq!(events.len(), 3);
+    assert!(events[0].success);
+    assert_eq!(events[0].ip.as_deref(), Some("203.0.113.7"));
+    assert_eq!(events[0].user_agent.as_deref(), Some("Firefox"));
+    assert_eq!(events[0].method, "password");
+    assert!(!events[1].success);
+    assert!(events[2].success);
+    assert_eq!(events[2].ip.as_deref(), Some("127.0.0.1"));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_email_on_login_from_new_device(app: &mut TestApp) {
+    mock_email_server(app, 1).await;
+    let email = sign_in(app).await;
+
+    let response = login_from(app, &email, "password", "Firefox").await;
+
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_email_on_login_from_known_device(app: &mut TestApp) {
+    mock_email_server(app, 1).await;
+    let email = sign_in(app).await;
+    login_from(app, &email, "password", "Firefox").await;
+
+    let response = login_from(app, &email, "password", "Firefox").await;
+
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_email_after_failed_login(app: &mut TestApp) {
+    mock_email_server(app, 0).await;
+    let email = sign_in(app).await;
+
+    let response = login_from(app, &email, "wrong-password", "Firefox").await;
+
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_no_session(app: &mut TestApp) {
+    let response = app.get_login_history().await;
+
+    assert_eq!(response.status().as_u16(), 400);
+}
@@ -1,8 +1,29 @@
+mod account_password;
+mod audit;
 mod delete_user;
+mod dev_mailbox;
+mod email_failover;
+mod email_outbox;
 mod helpers;
 mod login;
+mod login_history;
 mod logout;
+mod oauth_authorize;
+mod oauth_clients;
+mod oauth_device;
+mod oauth_introspect;
+mod oauth_revoke;
+mod oauth_token;
+mod openid;
+mod password_rehash;
+mod postmark_webhook;
 mod root;
+mod service_accounts;
 mod signup;
+mod smtp_email_client;
+mod smtp_sink;
+mod trusted_devices;
+mod user_import;
 mod verify_2fa;
 mod verify_token;
+mod webhooks;
@@ -0,0 +1,142 @@
+use crate::helpers::{
+    get_location, register_client, sign_in, TestApp, CODE_CHALLENGE,
+    REDIRECT_URI,
+};
+use test_context::test_context;
+
+fn authorize_query(client_id: &str) -> serde_json::Value {
+    serde_json::json!({
+        "response_type": "code",
+        "client_id": client_id,
+        "redirect_uri": REDIRECT_URI,
+        "scope": "read",
+        "state": "xyz",
+        "code_challenge": CODE_CHALLENGE,
+        "code_challenge_method": "S256"
+    })
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_render_consent_page_for_signed_in_user(app: &mut TestApp) {
+    let email = sign_in(app).await;
+    let client = register_client(app, "none").await;
+
+    let response = app
+        .get_oauth_authorize(&authorize_query(&client.client_id))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let page = response.text().await.unwrap();
+    assert!(page.contains("Test client"));
+    assert!(page.contains(&email));
+    assert!(page.contains(CODE_CHALLENGE));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_redirect_to_login_if_not_signed_in(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "none").await;
+    app.post_logout().await;
+
+    let response = app
+        .get_oauth_authorize(&authorize_query(&client.client_id))
+        .await;
+    assert_eq!(response.status().as_u16(), 303);
+
+    let location = get_location(&response);
+    assert_eq!(location.path(), "/");
+    let next = location
+        .query_pairs()
+        .find(|(key, _)| key == "next")
+        .map(|(_, next)| next.into_owned())
+        .expect("No next parameter");
+    assert!(next.starts_with("oauth/authorize?"));
+    assert!(next.contains(&client.client_id));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_redirect_with_code_when_user_allows(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "none").await;
+
+    let mut body = authorize_query(&client.client_id);
+    body["decision"] = "allow".into();
+    let response = app.post_oauth_authorize(&body).await;
+    assert_eq!(response.status().as_u16(), 303);
+
+    let location = get_location(&response);
+    assert!(location.as_str().starts_with(REDIRECT_URI));
+    let params: Vec<(String, String)> =
+        location.query_pairs().into_owned().collect();
+    assert!(params.iter().any(|(key, _)| key == "code"));
+    assert!(params.contains(&("state".to_owned(), "xyz".to_owned())));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_redirect_with_access_denied_when_user_denies(
+    app: &mut TestApp,
+) {
+    sign_in(app).await;
+    let client = register_client(app, "none").await;
+
+    let mut body = authorize_query(&client.client_id);
+    body["decision"] = "deny".into();
+    let response = app.post_oauth_authorize(&body).await;
+    assert_eq!(response.status().as_u16(), 303);
+
+    let params: Vec<(String, String)> =
+        get_location(&response).query_pairs().into_owned().collect();
+    assert!(params.contains(&("error".to_owned(), "access_denied".to_owned())));
+    assert!(params.contains(&("state".to_owned(), "xyz".to_owned())));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_redirect_with_error_for_invalid_request(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "none").await;
+
+    let test_cases = [
+        ("response_type", "token", "unsupported_response_type"),
+        ("code_challenge_method", "plain", "invalid_request"),
+        ("code_challenge", "too-short", "invalid_request"),
+        ("scope", "read admin", "invalid_scope"),
+    ];
+
+    for (field, value, error) in test_cases.iter() {
+        let mut query = authorize_query(&client.client_id);
+        query[field] = (*value).into();
+
+        let response = app.get_oauth_authorize(&query).await;
+        assert_eq!(response.status().as_u16(), 303, "Failed for {}", field);
+
+        let params: Vec<(String, String)> =
+            get_location(&response).query_pairs().into_owned().collect();
+        assert!(
+            params.contains(&("error".to_owned(), (*error).to_owned())),
+            "Failed for {}",
+            field
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_unknown_client_or_redirect_uri(
+    app: &mut TestApp,
+) {
+    sign_in(app).await;
+    let client = register_client(app, "none").await;
+
+    let response = app.get_oauth_authorize(&authorize_query("unknown")).await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let mut query = authorize_query(&client.client_id);
+    query["redirect_uri"] = "http://evil.example.com/callback".into();
+    let response = app.get_oauth_authorize(&query).await;
+    assert_eq!(response.status().as_u16(), 400);
+}
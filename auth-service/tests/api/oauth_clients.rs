@@ -0,0 +1,120 @@
+use crate::helpers::{
+    get_service_token, register_client, sign_in, TestApp, REDIRECT_URI,
+};
+use auth_service::utils::constants::{ADMIN_SCOPE, VERIFY_TOKEN_SCOPE};
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_201_with_secret_for_confidential_client(
+    app: &mut TestApp,
+) {
+    let client = register_client(app, "client_secret_basic").await;
+
+    assert!(client.client_secret.is_some());
+    assert_eq!(client.redirect_uris, vec![REDIRECT_URI.to_owned()]);
+    assert_eq!(client.scope, "read write");
+    assert_eq!(client.token_endpoint_auth_method, "client_secret_basic");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_201_without_secret_for_public_client(app: &mut TestApp) {
+    let client = register_client(app, "none").await;
+
+    assert!(client.client_secret.is_none());
+    assert_eq!(client.token_endpoint_auth_method, "none");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_missing_token(app: &mut TestApp) {
+    // A signed-in user isn't enough to register a client
+    sign_in(app).await;
+
+    let response = app
+        .http_client
+        .post(format!("{}/oauth/clients", &app.address))
+        .json(&serde_json::json!({
+            "client_name": "Test client",
+            "redirect_uris": [REDIRECT_URI]
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_403_without_admin_scope(app: &mut TestApp) {
+    let service_token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
+
+    let response = app
+        .post_oauth_client(
+            &serde_json::json!({
+                "client_name": "Test client",
+                "redirect_uris": [REDIRECT_URI]
+            }),
+            &service_token,
+        )
+        .await;
+
+    assert_eq!(response.status().as_u16(), 403);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_invalid_input(app: &mut TestApp) {
+    let service_token = get_service_token(app, ADMIN_SCOPE).await;
+
+    let test_cases = [
+        serde_json::json!({
+            "client_name": "Test client",
+            "redirect_uris": []
+        }),
+        serde_json::json!({
+            "client_name": "Test client",
+            "redirect_uris": ["/callback"]
+        }),
+        serde_json::json!({
+            "client_name": "Test client",
+            "redirect_uris": ["http://localhost:8000/callback#fragment"]
+        }),
+        serde_json::json!({
+            "client_name": "",
+            "redirect_uris": [REDIRECT_URI]
+        }),
+        serde_json::json!({
+            "client_name": "Test client",
+            "redirect_uris": [REDIRECT_URI],
+            "token_endpoint_auth_method": "private_key_jwt"
+        }),
+    ];
+
+    for test_case in test_cases.iter() {
+        let response = app.post_oauth_client(test_case, &service_token).await;
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "Failed for input: {:?}",
+            test_case
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_422_if_malformed_input(app: &mut TestApp) {
+    let service_token = get_service_token(app, ADMIN_SCOPE).await;
+
+    let response = app
+        .post_oauth_client(
+            &serde_json::json!({ "client_name": "Test client" }),
+            &service_token,
+        )
+        .await;
+
+    assert_eq!(response.status().as_u16(), 422);
+}
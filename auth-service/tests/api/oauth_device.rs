@@ -0,0 +1,211 @@
+use crate::helpers::{
+    authorize_device, get_location, register_client, sign_in, TestApp,
+};
+use auth_service::{
+    routes::{
+        DeviceAuthorizationResponse, TokenResponse, DEVICE_CODE_GRANT_TYPE,
+    },
+    OAuthErrorResponse,
+};
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_device_and_user_codes(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+
+    let response = app
+        .post_oauth_device_authorization(&serde_json::json!({
+            "client_id": client.client_id,
+            "client_secret": client.client_secret,
+            "scope": "read"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers().get("cache-control").unwrap(), "no-store");
+
+    let device = response
+        .json::<DeviceAuthorizationResponse>()
+        .await
+        .unwrap();
+    assert_eq!(device.user_code.len(), 9);
+    assert!(device.verification_uri.ends_with("/oauth/device"));
+    assert!(device.verification_uri_complete.contains(&device.user_code));
+    assert_eq!(device.expires_in, 600);
+    assert_eq!(device.interval, 5);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_unknown_scope(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+
+    let response = app
+        .post_oauth_device_authorization(&serde_json::json!({
+            "client_id": client.client_id,
+            "client_secret": client.client_secret,
+            "scope": "admin"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "invalid_scope");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_pending_then_slow_down(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+    let device = authorize_device(app, &client, "read").await;
+    let request = serde_json::json!({
+        "grant_type": DEVICE_CODE_GRANT_TYPE,
+        "device_code": device.device_code,
+        "client_id": client.client_id,
+        "client_secret": client.client_secret
+    });
+
+    let response = app.post_oauth_token(&request).await;
+    assert_eq!(response.status().as_u16(), 400);
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "authorization_pending");
+
+    // Polling again straight away is faster than the interval allows
+    let response = app.post_oauth_token(&request).await;
+    assert_eq!(response.status().as_u16(), 400);
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "slow_down");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_issue_tokens_once_approved(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+    let device = authorize_device(app, &client, "read").await;
+
+    let response = app
+        .get_oauth_device(&serde_json::json!({
+            "user_code": device.user_code.to_lowercase()
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let page = response.text().await.unwrap();
+    assert!(page.contains(&device.user_code));
+    assert!(page.contains("Test client"));
+
+    let response = app
+        .post_oauth_device(&serde_json::json!({
+            "user_code": device.user_code,
+            "decision": "allow"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let request = serde_json::json!({
+        "grant_type": DEVICE_CODE_GRANT_TYPE,
+        "device_code": device.device_code,
+        "client_id": client.client_id,
+        "client_secret": client.client_secret
+    });
+    let response = app.post_oauth_token(&request).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let tokens = response
+        .json::<TokenResponse>()
+        .await
+        .expect("Could not deserialize response body to TokenResponse");
+    assert_eq!(tokens.scope, "read");
+    assert!(tokens.refresh_token.is_some());
+
+    // Device codes can only be exchanged once
+    let response = app.post_oauth_token(&request).await;
+    assert_eq!(response.status().as_u16(), 400);
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "invalid_grant");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_access_denied_once_denied(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+    let device = authorize_device(app, &client, "read").await;
+
+    let response = app
+        .post_oauth_device(&serde_json::json!({
+            "user_code": device.user_code,
+            "decision": "deny"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": DEVICE_CODE_GRANT_TYPE,
+            "device_code": device.device_code,
+            "client_id": client.client_id,
+            "client_secret": client.client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "access_denied");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_device_code_from_another_client(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+    let other = register_client(app, "client_secret_post").await;
+    let device = authorize_device(app, &client, "read").await;
+
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": DEVICE_CODE_GRANT_TYPE,
+            "device_code": device.device_code,
+            "client_id": other.client_id,
+            "client_secret": other.client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "invalid_grant");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_redirect_to_login_without_session(app: &mut TestApp) {
+    let response = app
+        .get_oauth_device(&serde_json::json!({ "user_code": "BCDF-GHJK" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 303);
+
+    let location = get_location(&response);
+    let next = location
+        .query_pairs()
+        .find(|(key, _)| key == "next")
+        .map(|(_, next)| next.into_owned())
+        .expect("No next in redirect");
+    assert_eq!(next, "oauth/device?user_code=BCDF-GHJK");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_show_error_for_invalid_user_code(app: &mut TestApp) {
+    sign_in(app).await;
+
+    let response = app
+        .get_oauth_device(&serde_json::json!({ "user_code": "BCDF-GHJK" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let page = response.text().await.unwrap();
+    assert!(page.contains("That code is invalid or has expired."));
+}
@@ -0,0 +1,128 @@
+use crate::helpers::{
+    add_service_account, get_service_token, get_tokens, register_client,
+    sign_in, TestApp,
+};
+use auth_service::{
+    routes::IntrospectionResponse, utils::constants::VERIFY_TOKEN_SCOPE,
+    OAuthErrorResponse,
+};
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_claims_for_active_token(app: &mut TestApp) {
+    let email = sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+    let tokens = get_tokens(app, &client, "read").await;
+    let (client_id, client_secret) =
+        add_service_account(app, VERIFY_TOKEN_SCOPE).await;
+
+    let response = app
+        .post_oauth_introspect(&serde_json::json!({
+            "token": tokens.access_token,
+            "client_id": client_id,
+            "client_secret": client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers().get("cache-control").unwrap(), "no-store");
+
+    let introspection = response
+        .json::<IntrospectionResponse>()
+        .await
+        .expect("Could not deserialize response body to IntrospectionResponse");
+    assert!(introspection.active);
+    assert_eq!(introspection.sub, Some(email));
+    assert_eq!(introspection.scope.as_deref(), Some("read"));
+    assert_eq!(introspection.client_id, Some(client.client_id));
+    assert!(introspection.jti.is_some());
+    assert!(introspection.iat.unwrap() < introspection.exp.unwrap());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_inactive_for_invalid_token(app: &mut TestApp) {
+    let (client_id, client_secret) =
+        add_service_account(app, VERIFY_TOKEN_SCOPE).await;
+
+    let response = app
+        .post_oauth_introspect(&serde_json::json!({
+            "token": "invalid",
+            "client_id": client_id,
+            "client_secret": client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let introspection = response.json::<IntrospectionResponse>().await.unwrap();
+    assert!(!introspection.active);
+    assert!(introspection.sub.is_none());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_only_show_clients_their_own_tokens(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_basic").await;
+    let other = register_client(app, "client_secret_basic").await;
+    let tokens = get_tokens(app, &client, "read").await;
+
+    let response = app
+        .post_oauth_introspect(&serde_json::json!({
+            "token": tokens.access_token,
+            "client_id": client.client_id,
+            "client_secret": client.client_secret
+        }))
+        .await;
+    let introspection = response.json::<IntrospectionResponse>().await.unwrap();
+    assert!(introspection.active);
+
+    let response = app
+        .post_oauth_introspect(&serde_json::json!({
+            "token": tokens.access_token,
+            "client_id": other.client_id,
+            "client_secret": other.client_secret
+        }))
+        .await;
+    let introspection = response.json::<IntrospectionResponse>().await.unwrap();
+    assert!(!introspection.active);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_service_account_without_scope(
+    app: &mut TestApp,
+) {
+    let token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
+    let (client_id, client_secret) = add_service_account(app, "admin").await;
+
+    let response = app
+        .post_oauth_introspect(&serde_json::json!({
+            "token": token,
+            "client_id": client_id,
+            "client_secret": client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "unauthorized_client");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_for_invalid_credentials(app: &mut TestApp) {
+    let (client_id, _) = add_service_account(app, VERIFY_TOKEN_SCOPE).await;
+
+    let response = app
+        .post_oauth_introspect(&serde_json::json!({
+            "token": "token",
+            "client_id": client_id,
+            "client_secret": "wrong"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "invalid_client");
+}
@@ -0,0 +1,145 @@
+use crate::helpers::{
+    add_service_account, get_service_token, get_tokens, register_client,
+    sign_in, TestApp,
+};
+use auth_service::{
+    routes::IntrospectionResponse,
+    utils::constants::{REVOKE_TOKEN_SCOPE, VERIFY_TOKEN_SCOPE},
+    OAuthErrorResponse,
+};
+use test_context::test_context;
+
+async fn is_active(app: &TestApp, token: &str) -> bool {
+    let (client_id, client_secret) =
+        add_service_account(app, VERIFY_TOKEN_SCOPE).await;
+
+    app.post_oauth_introspect(&serde_json::json!({
+        "token": token,
+        "client_id": client_id,
+        "client_secret": client_secret
+    }))
+    .await
+    .json::<IntrospectionResponse>()
+    .await
+    .expect("Could not deserialize response body to IntrospectionResponse")
+    .active
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_revoke_access_token(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+    let tokens = get_tokens(app, &client, "read").await;
+    assert!(is_active(app, &tokens.access_token).await);
+
+    let response = app
+        .post_oauth_revoke(&serde_json::json!({
+            "token": tokens.access_token,
+            "token_type_hint": "access_token",
+            "client_id": client.client_id,
+            "client_secret": client.client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    assert!(!is_active(app, &tokens.access_token).await);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_revoke_refresh_token(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+    let tokens = get_tokens(app, &client, "read").await;
+    let refresh_token = tokens.refresh_token.unwrap();
+
+    let response = app
+        .post_oauth_revoke(&serde_json::json!({
+            "token": refresh_token,
+            "client_id": client.client_id,
+            "client_secret": client.client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "refresh_token",
+            "refresh_token": refresh_token,
+            "client_id": client.client_id,
+            "client_secret": client.client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "invalid_grant");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_ignore_tokens_of_other_clients(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+    let other = register_client(app, "client_secret_post").await;
+    let tokens = get_tokens(app, &client, "read").await;
+    let refresh_token = tokens.refresh_token.unwrap();
+
+    for token in [&tokens.access_token, &refresh_token] {
+        let response = app
+            .post_oauth_revoke(&serde_json::json!({
+                "token": token,
+                "client_id": other.client_id,
+                "client_secret": other.client_secret
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 200);
+    }
+
+    assert!(is_active(app, &tokens.access_token).await);
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "refresh_token",
+            "refresh_token": refresh_token,
+            "client_id": client.client_id,
+            "client_secret": client.client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_200_for_unknown_token(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+
+    let response = app
+        .post_oauth_revoke(&serde_json::json!({
+            "token": "unknown",
+            "client_id": client.client_id,
+            "client_secret": client.client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_let_service_account_revoke_any_token(app: &mut TestApp) {
+    let token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
+    let (client_id, client_secret) =
+        add_service_account(app, REVOKE_TOKEN_SCOPE).await;
+
+    let response = app
+        .post_oauth_revoke(&serde_json::json!({
+            "token": token,
+            "client_id": client_id,
+            "client_secret": client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    assert!(!is_active(app, &token).await);
+}
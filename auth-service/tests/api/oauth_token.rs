@@ -0,0 +1,225 @@
+use crate::helpers::{
+    authorize, get_service_token, register_client, sign_in, TestApp,
+    CODE_VERIFIER, REDIRECT_URI,
+};
+use auth_service::{
+    routes::TokenResponse, utils::constants::VERIFY_TOKEN_SCOPE,
+    OAuthErrorResponse,
+};
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_exchange_code_for_tokens(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+    let code = authorize(app, &client.client_id, "read").await;
+
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "authorization_code",
+            "code": code,
+            "redirect_uri": REDIRECT_URI,
+            "code_verifier": CODE_VERIFIER,
+            "client_id": client.client_id,
+            "client_secret": client.client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers().get("cache-control").unwrap(), "no-store");
+
+    let tokens = response
+        .json::<TokenResponse>()
+        .await
+        .expect("Could not deserialize response body to TokenResponse");
+    assert_eq!(tokens.token_type, "Bearer");
+    assert_eq!(tokens.scope, "read");
+    assert!(tokens.refresh_token.is_some());
+
+    // Access tokens aren't user sessions, resource servers introspect them
+    let service_token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
+    let response = app
+        .post_verify_token(
+            &serde_json::json!({ "token": tokens.access_token }),
+            &service_token,
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_accept_basic_client_authentication(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_basic").await;
+    let code = authorize(app, &client.client_id, "read").await;
+
+    let response = app
+        .http_client
+        .post(format!("{}/oauth/token", &app.address))
+        .basic_auth(&client.client_id, client.client_secret.as_ref())
+        .form(&serde_json::json!({
+            "grant_type": "authorization_code",
+            "code": code,
+            "code_verifier": CODE_VERIFIER
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_for_invalid_client_secret(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+    let code = authorize(app, &client.client_id, "read").await;
+
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "authorization_code",
+            "code": code,
+            "code_verifier": CODE_VERIFIER,
+            "client_id": client.client_id,
+            "client_secret": "wrong"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "invalid_client");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_wrong_code_verifier(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "none").await;
+    let code = authorize(app, &client.client_id, "read").await;
+
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "authorization_code",
+            "code": code,
+            "code_verifier": "a".repeat(43),
+            "client_id": client.client_id
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "invalid_grant");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_code_is_reused(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "none").await;
+    let code = authorize(app, &client.client_id, "read").await;
+
+    let body = serde_json::json!({
+        "grant_type": "authorization_code",
+        "code": code,
+        "code_verifier": CODE_VERIFIER,
+        "client_id": client.client_id
+    });
+
+    assert_eq!(app.post_oauth_token(&body).await.status().as_u16(), 200);
+
+    let response = app.post_oauth_token(&body).await;
+    assert_eq!(response.status().as_u16(), 400);
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "invalid_grant");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_rotate_refresh_token(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "none").await;
+    let code = authorize(app, &client.client_id, "read write").await;
+
+    let tokens = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "authorization_code",
+            "code": code,
+            "code_verifier": CODE_VERIFIER,
+            "client_id": client.client_id
+        }))
+        .await
+        .json::<TokenResponse>()
+        .await
+        .unwrap();
+    let refresh_token = tokens.refresh_token.unwrap();
+
+    let body = serde_json::json!({
+        "grant_type": "refresh_token",
+        "refresh_token": refresh_token,
+        "scope": "read",
+        "client_id": client.client_id
+    });
+
+    let response = app.post_oauth_token(&body).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let refreshed = response.json::<TokenResponse>().await.unwrap();
+    assert_eq!(refreshed.scope, "read");
+    assert_ne!(refreshed.refresh_token.unwrap(), refresh_token);
+
+    // The old refresh token has been used up
+    let response = app.post_oauth_token(&body).await;
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_refresh_widens_scope(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "none").await;
+    let code = authorize(app, &client.client_id, "read").await;
+
+    let tokens = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "authorization_code",
+            "code": code,
+            "code_verifier": CODE_VERIFIER,
+            "client_id": client.client_id
+        }))
+        .await
+        .json::<TokenResponse>()
+        .await
+        .unwrap();
+
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "refresh_token",
+            "refresh_token": tokens.refresh_token.unwrap(),
+            "scope": "read write",
+            "client_id": client.client_id
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "invalid_scope");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_unsupported_grant_type(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "none").await;
+
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "password",
+            "client_id": client.client_id
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "unsupported_grant_type");
+}
@@ -0,0 +1,201 @@
+use crate::helpers::{
+    get_location, get_service_token, register_client, sign_in, TestApp,
+    CODE_CHALLENGE, CODE_VERIFIER, REDIRECT_URI,
+};
+use auth_service::{
+    routes::{JwkSet, OpenIdConfiguration, TokenResponse, UserInfoResponse},
+    utils::{
+        constants::{ADMIN_SCOPE, ISSUER},
+        oidc::IdTokenClaims,
+    },
+};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use test_context::test_context;
+
+async fn get_tokens(
+    app: &TestApp,
+    client_id: &str,
+    scope: &str,
+) -> TokenResponse {
+    let response = app
+        .post_oauth_authorize(&serde_json::json!({
+            "response_type": "code",
+            "client_id": client_id,
+            "redirect_uri": REDIRECT_URI,
+            "scope": scope,
+            "nonce": "n-0S6_WzA2Mj",
+            "code_challenge": CODE_CHALLENGE,
+            "code_challenge_method": "S256",
+            "decision": "allow"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 303);
+
+    let code = get_location(&response)
+        .query_pairs()
+        .find(|(key, _)| key == "code")
+        .map(|(_, code)| code.into_owned())
+        .expect("No code in redirect");
+
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "authorization_code",
+            "code": code,
+            "code_verifier": CODE_VERIFIER,
+            "client_id": client_id
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    response
+        .json::<TokenResponse>()
+        .await
+        .expect("Could not deserialize response body to TokenResponse")
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_discovery_document(app: &mut TestApp) {
+    let response = app
+        .http_client
+        .get(format!("{}/.well-known/openid-configuration", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let configuration = response.json::<OpenIdConfiguration>().await.unwrap();
+    assert_eq!(configuration.issuer, ISSUER.trim_end_matches('/'));
+    assert!(configuration.token_endpoint.ends_with("/oauth/token"));
+    assert!(configuration.jwks_uri.ends_with("/.well-known/jwks.json"));
+    assert_eq!(
+        configuration.id_token_signing_alg_values_supported,
+        ["ES256"]
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_issue_id_token_verifiable_with_jwks(app: &mut TestApp) {
+    let email = sign_in(app).await;
+    let client = register_client_with_openid(app).await;
+
+    let tokens = get_tokens(app, &client, "openid email").await;
+    let id_token = tokens.id_token.expect("No ID token issued");
+
+    let jwks = app
+        .http_client
+        .get(format!("{}/.well-known/jwks.json", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request")
+        .json::<JwkSet>()
+        .await
+        .unwrap();
+
+    let header = decode_header(&id_token).unwrap();
+    let jwk = jwks
+        .keys
+        .iter()
+        .find(|key| Some(&key.kid) == header.kid.as_ref())
+        .expect("Signing key not published");
+
+    let mut validation = Validation::new(Algorithm::ES256);
+    validation.set_audience(&[&client]);
+    validation.set_issuer(&[ISSUER.as_str()]);
+    let claims = decode::<IdTokenClaims>(
+        &id_token,
+        &DecodingKey::from_ec_components(&jwk.x, &jwk.y).unwrap(),
+        &validation,
+    )
+    .expect("ID token should verify")
+    .claims;
+
+    assert_eq!(claims.sub, email);
+    assert_eq!(claims.nonce.as_deref(), Some("n-0S6_WzA2Mj"));
+    assert_eq!(claims.amr, vec!["pwd"]);
+    assert!(claims.auth_time <= claims.iat);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_issue_id_token_without_openid_scope(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "none").await;
+
+    let tokens = get_tokens(app, &client.client_id, "read").await;
+    assert!(tokens.id_token.is_none());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_userinfo_for_openid_access_token(app: &mut TestApp) {
+    let email = sign_in(app).await;
+    let client = register_client_with_openid(app).await;
+    let tokens = get_tokens(app, &client, "openid email").await;
+
+    let response = app
+        .http_client
+        .get(format!("{}/userinfo", &app.address))
+        .bearer_auth(&tokens.access_token)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let userinfo = response.json::<UserInfoResponse>().await.unwrap();
+    assert_eq!(userinfo.sub, email);
+    assert_eq!(userinfo.email, Some(email));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_from_userinfo_without_openid_scope(
+    app: &mut TestApp,
+) {
+    sign_in(app).await;
+    let client = register_client(app, "none").await;
+    let tokens = get_tokens(app, &client.client_id, "read").await;
+
+    let response = app
+        .http_client
+        .get(format!("{}/userinfo", &app.address))
+        .bearer_auth(&tokens.access_token)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_from_userinfo_without_token(app: &mut TestApp) {
+    let response = app
+        .http_client
+        .get(format!("{}/userinfo", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+async fn register_client_with_openid(app: &TestApp) -> String {
+    let service_token = get_service_token(app, ADMIN_SCOPE).await;
+    let response = app
+        .post_oauth_client(
+            &serde_json::json!({
+                "client_name": "OIDC client",
+                "redirect_uris": [REDIRECT_URI],
+                "scope": "openid email",
+                "token_endpoint_auth_method": "none"
+            }),
+            &service_token,
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    response.json::<serde_json::Value>().await.unwrap()["client_id"]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
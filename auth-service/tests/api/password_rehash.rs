@@ -0,0 +1,272 @@
+use argon2::{
+    password_hash::SaltString, Algorithm, Argon2, KeyId, Params, ParamsBuilder,
+    PasswordHash, PasswordHasher, Version,
+};
+use auth_service::{
+    domain::{Email, Password, UserStore, UserStoreError},
+    services::argon2_password_hasher::Pepper,
+};
+use secrecy::Secret;
+use test_context::test_context;
+
+use crate::helpers::{
+    get_random_email, password_hash_params, TestApp, PASSWORD_PEPPER_SECRET,
+    PASSWORD_PEPPER_VERSION,
+};
+
+// Peppered like the test app's own hashes
+fn hash(password: &str, algorithm: Algorithm, params: Params) -> String {
+    hash_with_pepper(
+        password,
+        algorithm,
+        params,
+        Some((PASSWORD_PEPPER_VERSION, PASSWORD_PEPPER_SECRET)),
+    )
+}
+
+fn hash_with_pepper(
+    password: &str,
+    algorithm: Algorithm,
+    params: Params,
+    pepper: Option<(u32, &str)>,
+) -> String {
+    let salt = SaltString::generate(&mut rand::thread_rng());
+    let argon2 = match pepper {
+        Some((version, secret)) => {
+            let params = ParamsBuilder::new()
+                .m_cost(params.m_cost())
+                .t_cost(params.t_cost())
+                .p_cost(params.p_cost())
+                .keyid(KeyId::new(version.to_string().as_bytes()).unwrap())
+                .build()
+                .unwrap();
+            Argon2::new_with_secret(
+                secret.as_bytes(),
+                algorithm,
+                Version::V0x13,
+                params,
+            )
+            .unwrap()
+        }
+        None => Argon2::new(algorithm, Version::V0x13, params),
+    };
+    argon2
+        .hash_password(password.as_bytes(), &salt)
+        .unwrap()
+        .to_string()
+}
+
+fn hash_params(password_hash: &str) -> Params {
+    Params::try_from(&PasswordHash::new(password_hash).unwrap()).unwrap()
+}
+
+async fn signup(app: &TestApp, email: &str) {
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+}
+
+async fn login(app: &TestApp, email: &str, password: &str) -> u16 {
+    app.post_login(&serde_json::json!({
+        "email": email,
+        "password": password,
+    }))
+    .await
+    .status()
+    .as_u16()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_rehash_weaker_password_hash_on_login(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email).await;
+    let weak_hash = hash(
+        "password123",
+        Algorithm::Argon2id,
+        Params::new(1024, 1, 1, None).unwrap(),
+    );
+    app.set_password_hash(&email, &weak_hash).await;
+
+    assert_eq!(login(app, &email, "password123").await, 200);
+
+    let password_hash = app.get_password_hash(&email).await;
+    assert_ne!(password_hash, weak_hash);
+    let params = hash_params(&password_hash);
+    let expected = password_hash_params();
+    assert_eq!(params.m_cost(), expected.m_cost());
+    assert_eq!(params.t_cost(), expected.t_cost());
+    assert_eq!(params.p_cost(), expected.p_cost());
+
+    // The new hash still verifies
+    assert_eq!(login(app, &email, "password123").await, 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_rehash_other_argon2_variants_on_login(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email).await;
+    let argon2i_hash =
+        hash("password123", Algorithm::Argon2i, password_hash_params());
+    app.set_password_hash(&email, &argon2i_hash).await;
+
+    assert_eq!(login(app, &email, "password123").await, 200);
+
+    let password_hash = app.get_password_hash(&email).await;
+    assert!(password_hash.starts_with("$argon2id$"));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_rehash_password_hashed_before_normalisation(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email).await;
+    // A decomposed "é", hashed as typed with the current params
+    let typed_hash = hash(
+        "cafe\u{301}-au-lait",
+        Algorithm::Argon2id,
+        password_hash_params(),
+    );
+    app.set_password_hash(&email, &typed_hash).await;
+
+    assert_eq!(login(app, &email, "cafe\u{301}-au-lait").await, 200);
+    assert_ne!(app.get_password_hash(&email).await, typed_hash);
+
+    // The new hash is of the normalised password, so either form logs in
+    assert_eq!(login(app, &email, "caf\u{e9}-au-lait").await, 200);
+    assert_eq!(login(app, &email, "cafe\u{301}-au-lait").await, 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_rehash_on_failed_login(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email).await;
+    let weak_hash = hash(
+        "password123",
+        Algorithm::Argon2id,
+        Params::new(1024, 1, 1, None).unwrap(),
+    );
+    app.set_password_hash(&email, &weak_hash).await;
+
+    assert_eq!(login(app, &email, "wrong-password").await, 401);
+
+    assert_eq!(app.get_password_hash(&email).await, weak_hash);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_keep_stronger_password_hash(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email).await;
+    let current = password_hash_params();
+    let strong_hash = hash(
+        "password123",
+        Algorithm::Argon2id,
+        Params::new(current.m_cost() * 2, current.t_cost(), 1, None).unwrap(),
+    );
+    app.set_password_hash(&email, &strong_hash).await;
+
+    assert_eq!(login(app, &email, "password123").await, 200);
+
+    assert_eq!(app.get_password_hash(&email).await, strong_hash);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_pepper_new_password_hashes(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email).await;
+
+    let params = hash_params(&app.get_password_hash(&email).await);
+    assert_eq!(
+        params.keyid(),
+        PASSWORD_PEPPER_VERSION.to_string().as_bytes()
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_pepper_unpeppered_hash_on_login(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email).await;
+    let unpeppered_hash = hash_with_pepper(
+        "password123",
+        Algorithm::Argon2id,
+        password_hash_params(),
+        None,
+    );
+    app.set_password_hash(&email, &unpeppered_hash).await;
+
+    assert_eq!(login(app, &email, "password123").await, 200);
+
+    let params = hash_params(&app.get_password_hash(&email).await);
+    assert_eq!(
+        params.keyid(),
+        PASSWORD_PEPPER_VERSION.to_string().as_bytes()
+    );
+    assert_eq!(login(app, &email, "password123").await, 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_pepper_version_is_unknown(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email).await;
+    let retired_pepper_hash = hash_with_pepper(
+        "password123",
+        Algorithm::Argon2id,
+        password_hash_params(),
+        Some((PASSWORD_PEPPER_VERSION + 1, "retired-pepper")),
+    );
+    app.set_password_hash(&email, &retired_pepper_hash).await;
+
+    assert_eq!(login(app, &email, "password123").await, 401);
+
+    assert_eq!(app.get_password_hash(&email).await, retired_pepper_hash);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_refuse_reused_password_after_its_pepper_is_retired(
+    app: &mut TestApp,
+) {
+    let email = get_random_email();
+    signup(app, &email).await;
+    let parsed_email = Email::parse(Secret::new(email.clone())).unwrap();
+    let password =
+        || Password::parse(Secret::new("password123".to_owned())).unwrap();
+    let new_pepper = Pepper::new(
+        PASSWORD_PEPPER_VERSION + 1,
+        Secret::new("new-pepper".to_owned()),
+    );
+    let old_pepper = Pepper::new(
+        PASSWORD_PEPPER_VERSION,
+        Secret::new(PASSWORD_PEPPER_SECRET.to_owned()),
+    );
+
+    // Logging in after the pepper is rotated rehashes the password
+    app.new_user_store(vec![new_pepper.clone(), old_pepper])
+        .validate_user(&parsed_email, &password())
+        .await
+        .unwrap();
+    let params = hash_params(&app.get_password_hash(&email).await);
+    assert_eq!(
+        params.keyid(),
+        (PASSWORD_PEPPER_VERSION + 1).to_string().as_bytes()
+    );
+
+    // Once the old pepper is retired, the password is still recognised
+    assert_eq!(
+        app.new_user_store(vec![new_pepper])
+            .update_password(&parsed_email, password())
+            .await,
+        Err(UserStoreError::PasswordReused)
+    );
+}
@@ -0,0 +1,214 @@
+use auth_service::{
+    domain::AuditAction,
+    routes::UndeliverableEmailResponse,
+    utils::constants::{
+        ADMIN_SCOPE, POSTMARK_WEBHOOK_SCOPE, VERIFY_TOKEN_SCOPE,
+    },
+    ErrorResponse,
+};
+use test_context::test_context;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::{
+    add_service_account, get_random_email, get_service_token, TestApp,
+};
+
+async fn sign_up_with_2fa(app: &TestApp) -> String {
+    let email = get_random_email();
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    email
+}
+
+async fn login(app: &TestApp, email: &str) -> reqwest::Response {
+    app.post_login(&serde_json::json!({
+        "email": email,
+        "password": "password123"
+    }))
+    .await
+}
+
+async fn post_event(
+    app: &TestApp,
+    body: serde_json::Value,
+) -> reqwest::Response {
+    let (client_id, client_secret) =
+        add_service_account(app, POSTMARK_WEBHOOK_SCOPE).await;
+    app.post_postmark_webhook(&body, &client_id, &client_secret)
+        .await
+}
+
+fn bounce(bounce_type: &str, email: &str) -> serde_json::Value {
+    serde_json::json!({
+        "RecordType": "Bounce",
+        "ID": 4323372036854775807u64,
+        "Type": bounce_type,
+        "TypeCode": 1,
+        "Name": "Hard bounce",
+        "MessageStream": "outbound",
+        "Description": "The server was unable to deliver your message",
+        "Email": email,
+        "BouncedAt": "2026-10-19T16:09:19Z",
+        "Inactive": true
+    })
+}
+
+async fn undeliverable_emails(
+    app: &TestApp,
+) -> Vec<UndeliverableEmailResponse> {
+    let admin_token = get_service_token(app, ADMIN_SCOPE).await;
+    let response = app.get_undeliverable_emails(&admin_token).await;
+    assert_eq!(response.status().as_u16(), 200);
+    response
+        .json::<Vec<UndeliverableEmailResponse>>()
+        .await
+        .expect("Could not deserialize response body to undeliverable emails")
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_stop_2fa_emails_after_a_hard_bounce(app: &mut TestApp) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+    let email = sign_up_with_2fa(app).await;
+
+    let response = post_event(app, bounce("HardBounce", &email)).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = login(app, &email).await;
+    assert_eq!(response.status().as_u16(), 409);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Email address is undeliverable"
+    );
+
+    let undeliverable = undeliverable_emails(app).await;
+    assert_eq!(undeliverable.len(), 1);
+    assert_eq!(undeliverable[0].email, email);
+    assert_eq!(undeliverable[0].reason, "HardBounce");
+
+    let events = app.audit_sink.events().await;
+    assert!(events
+        .iter()
+        .any(|event| event.action == AuditAction::MarkEmailUndeliverable
+            && event.target.as_deref() == Some(email.as_str())));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_mark_spam_complaints_undeliverable(app: &mut TestApp) {
+    let email = sign_up_with_2fa(app).await;
+
+    let response = post_event(
+        app,
+        serde_json::json!({
+            "RecordType": "SpamComplaint",
+            "Type": "SpamComplaint",
+            "TypeCode": 512,
+            "Email": email,
+            "BouncedAt": "2026-10-19T16:09:19Z"
+        }),
+    )
+    .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let undeliverable = undeliverable_emails(app).await;
+    assert_eq!(undeliverable.len(), 1);
+    assert_eq!(undeliverable[0].reason, "SpamComplaint");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_ignore_other_events(app: &mut TestApp) {
+    let email = sign_up_with_2fa(app).await;
+
+    let events = [
+        bounce("SoftBounce", &email),
+        bounce("HardBounce", &get_random_email()),
+        serde_json::json!({
+            "RecordType": "Delivery",
+            "Recipient": email,
+            "DeliveredAt": "2026-10-19T16:09:19Z"
+        }),
+    ];
+    for event in events {
+        let response = post_event(app, event).await;
+        assert_eq!(response.status().as_u16(), 200);
+    }
+
+    assert!(undeliverable_emails(app).await.is_empty());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_send_2fa_emails_again_once_cleared(app: &mut TestApp) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+    let email = sign_up_with_2fa(app).await;
+    post_event(app, bounce("HardBounce", &email)).await;
+    let admin_token = get_service_token(app, ADMIN_SCOPE).await;
+
+    let response = app.delete_undeliverable_email(&email, &admin_token).await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    assert!(undeliverable_emails(app).await.is_empty());
+    let response = login(app, &email).await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let response = app
+        .delete_undeliverable_email(&get_random_email(), &admin_token)
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_require_webhook_credentials(app: &mut TestApp) {
+    let email = sign_up_with_2fa(app).await;
+    let body = bounce("HardBounce", &email);
+
+    let response = app.post_postmark_webhook(&body, "unknown", "secret").await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let (client_id, client_secret) =
+        add_service_account(app, VERIFY_TOKEN_SCOPE).await;
+    let response = app
+        .post_postmark_webhook(&body, &client_id, &client_secret)
+        .await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    assert!(undeliverable_emails(app).await.is_empty());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_require_admin_scope_for_undeliverable_emails(
+    app: &mut TestApp,
+) {
+    let service_token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
+
+    let response = app.get_undeliverable_emails(&service_token).await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let response = app
+        .delete_undeliverable_email(&get_random_email(), &service_token)
+        .await;
+    assert_eq!(response.status().as_u16(), 403);
+}
@@ -0,0 +1,185 @@
+use crate::helpers::{
+    add_service_account, get_service_token, register_client, sign_in, TestApp,
+};
+use auth_service::{
+    routes::{
+        CreateServiceAccountResponse, ServiceAccountResponse, TokenResponse,
+    },
+    utils::constants::{ADMIN_SCOPE, VERIFY_TOKEN_SCOPE},
+    OAuthErrorResponse,
+};
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_issue_service_token_for_client_credentials(app: &mut TestApp) {
+    let (client_id, client_secret) =
+        add_service_account(app, "tokens:verify admin").await;
+
+    let response = app
+        .http_client
+        .post(format!("{}/oauth/token", &app.address))
+        .basic_auth(&client_id, Some(&client_secret))
+        .form(&serde_json::json!({
+            "grant_type": "client_credentials",
+            "scope": "tokens:verify"
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let tokens = response.json::<TokenResponse>().await.unwrap();
+    assert_eq!(tokens.token_type, "Bearer");
+    assert_eq!(tokens.expires_in, 300);
+    assert_eq!(tokens.scope, "tokens:verify");
+    assert!(tokens.refresh_token.is_none());
+    assert!(tokens.id_token.is_none());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_scopes_beyond_the_account(app: &mut TestApp) {
+    let (client_id, client_secret) =
+        add_service_account(app, VERIFY_TOKEN_SCOPE).await;
+
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "client_credentials",
+            "client_id": client_id,
+            "client_secret": client_secret,
+            "scope": "tokens:verify admin"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let error = response.json::<OAuthErrorResponse>().await.unwrap();
+    assert_eq!(error.error, "invalid_scope");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_invalid_service_credentials(app: &mut TestApp) {
+    let (client_id, _) = add_service_account(app, VERIFY_TOKEN_SCOPE).await;
+
+    // OAuth clients can't use the client_credentials grant
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_post").await;
+
+    let requests = [
+        serde_json::json!({
+            "grant_type": "client_credentials",
+            "client_id": client_id,
+            "client_secret": "wrong"
+        }),
+        serde_json::json!({
+            "grant_type": "client_credentials",
+            "client_id": client_id
+        }),
+        serde_json::json!({
+            "grant_type": "client_credentials",
+            "client_id": client.client_id,
+            "client_secret": client.client_secret
+        }),
+    ];
+
+    for request in requests {
+        let response = app.post_oauth_token(&request).await;
+        assert_eq!(response.status().as_u16(), 401);
+
+        let error = response.json::<OAuthErrorResponse>().await.unwrap();
+        assert_eq!(error.error, "invalid_client");
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_manage_service_accounts_with_admin_token(app: &mut TestApp) {
+    let admin_token = get_service_token(app, ADMIN_SCOPE).await;
+
+    let response = app
+        .post_service_account(
+            &serde_json::json!({
+                "name": "Reporting",
+                "scope": "tokens:verify"
+            }),
+            &admin_token,
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let account = response
+        .json::<CreateServiceAccountResponse>()
+        .await
+        .unwrap();
+    assert_eq!(account.scope, "tokens:verify");
+
+    // The new account can use its credentials straight away
+    let response = app
+        .post_oauth_token(&serde_json::json!({
+            "grant_type": "client_credentials",
+            "client_id": account.client_id,
+            "client_secret": account.client_secret
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.get_service_accounts(&admin_token).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let accounts = response
+        .json::<Vec<ServiceAccountResponse>>()
+        .await
+        .unwrap();
+    assert!(accounts
+        .iter()
+        .any(|listed| listed.client_id == account.client_id
+            && listed.name == "Reporting"));
+
+    let response = app
+        .delete_service_account(&account.client_id, &admin_token)
+        .await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = app
+        .delete_service_account(&account.client_id, &admin_token)
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_422_if_malformed_service_account(app: &mut TestApp) {
+    let admin_token = get_service_token(app, ADMIN_SCOPE).await;
+
+    let response = app
+        .post_service_account(&serde_json::json!({ "name": 1 }), &admin_token)
+        .await;
+    assert_eq!(response.status().as_u16(), 422);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_invalid_service_account(app: &mut TestApp) {
+    let admin_token = get_service_token(app, ADMIN_SCOPE).await;
+
+    let invalid_requests = [
+        serde_json::json!({ "name": "", "scope": "tokens:verify" }),
+        serde_json::json!({ "name": "Reporting", "scope": "" }),
+    ];
+
+    for request in invalid_requests {
+        let response = app.post_service_account(&request, &admin_token).await;
+        assert_eq!(response.status().as_u16(), 400);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_require_admin_scope(app: &mut TestApp) {
+    let service_token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
+
+    let response = app.get_service_accounts(&service_token).await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let response = app.get_service_accounts("invalid").await;
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -1,6 +1,17 @@
-use crate::helpers::{get_random_email, TestApp};
-use auth_service::{routes::SignupResponse, ErrorResponse};
+use crate::helpers::{
+    get_random_email, BreachCheckTestApp, TestApp, BANNED_PASSWORD_WORD,
+    DENIED_EMAIL_DOMAIN, DISPOSABLE_EMAIL_DOMAIN,
+};
+use auth_service::{
+    domain::{PasswordRule, PasswordViolation},
+    routes::SignupResponse,
+    ErrorResponse,
+};
 use test_context::test_context;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
 
 #[test_context(TestApp)]
 #[tokio::test]
@@ -98,14 +109,9 @@ async fn should_return_400_if_invalid_input(app: &mut TestApp) {
         }),
         serde_json::json!({
             "email": "a@b.com",
-            "password": "abcd123",
+            "password": "",
             "requires2FA": true
         }),
-        serde_json::json!({
-            "email": "foo@bar.com",
-            "password": "abcdefghijklmnopqrstuvwxyz1234567890ABCDEFGHIJKLMNOPQRSTUVWXYZ12abcdefghijklmnopqrstuvwxyz1234567890ABCDEFGHIJKLMNOPQRSTUVWXYZ123",
-            "requires2FA": false
-        }),
     ];
 
     for test_case in test_cases.iter() {
@@ -127,6 +133,150 @@ async fn should_return_400_if_invalid_input(app: &mut TestApp) {
     }
 }
 
+async fn assert_password_violations(
+    response: reqwest::Response,
+    expected: &[PasswordRule],
+) {
+    assert_eq!(response.status().as_u16(), 400);
+    let body = response
+        .json::<ErrorResponse>()
+        .await
+        .expect("Could not deserialise response body to ErrorResponse");
+    assert_eq!(body.error, "Password does not meet the policy");
+    assert_eq!(
+        body.violations
+            .iter()
+            .map(|violation| violation.rule)
+            .collect::<Vec<_>>(),
+        expected
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_with_each_rule_the_password_breaks(
+    app: &mut TestApp,
+) {
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": get_random_email(),
+            "password": "abcd123",
+            "requires2FA": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialise response body to ErrorResponse")
+            .violations,
+        [PasswordViolation {
+            rule: PasswordRule::MinLength,
+            message: "Too short. Should be 8 to 128 characters.".to_owned(),
+        }]
+    );
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": get_random_email(),
+            "password": "abcdefghijklmnopqrstuvwxyz1234567890ABCDEFGHIJKLMNOPQRSTUVWXYZ12abcdefghijklmnopqrstuvwxyz1234567890ABCDEFGHIJKLMNOPQRSTUVWXYZ123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_password_violations(response, &[PasswordRule::MaxLength]).await;
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": get_random_email(),
+            "password": format!("b00t{}", BANNED_PASSWORD_WORD),
+            "requires2FA": false
+        }))
+        .await;
+    assert_password_violations(response, &[PasswordRule::BannedWord]).await;
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_password_contains_email_local_part(
+    app: &mut TestApp,
+) {
+    let local_part = uuid::Uuid::new_v4().simple().to_string();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": format!("{}@example.com", local_part),
+            "password": format!("{}!", local_part.to_uppercase()),
+            "requires2FA": false
+        }))
+        .await;
+
+    assert_password_violations(response, &[PasswordRule::BannedWord]).await;
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_log_in_with_a_differently_normalised_password(
+    app: &mut TestApp,
+) {
+    let email = get_random_email();
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "caf\u{e9}-au-lait",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "cafe\u{301}-au-lait",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_email_domain_is_rejected(app: &mut TestApp) {
+    let test_cases = [
+        (
+            format!("user@{}", DENIED_EMAIL_DOMAIN),
+            "Email domain is blocked",
+        ),
+        (
+            format!("user@sub.{}", DENIED_EMAIL_DOMAIN.to_uppercase()),
+            "Email domain is blocked",
+        ),
+        (
+            format!("user@{}", DISPOSABLE_EMAIL_DOMAIN),
+            "Disposable email addresses are not allowed",
+        ),
+    ];
+
+    for (email, expected_error) in test_cases {
+        let response = app
+            .post_signup(&serde_json::json!({
+                "email": email,
+                "password": "password123",
+                "requires2FA": false
+            }))
+            .await;
+
+        assert_eq!(response.status().as_u16(), 400, "{}", email);
+        assert_eq!(
+            response
+                .json::<ErrorResponse>()
+                .await
+                .expect("Could not deserialize response body to ErrorResponse")
+                .error,
+            expected_error
+        );
+    }
+}
+
 #[test_context(TestApp)]
 #[tokio::test]
 async fn should_return_409_if_email_exists(app: &mut TestApp) {
@@ -161,3 +311,96 @@ async fn should_return_409_if_email_exists(app: &mut TestApp) {
         "User already exists".to_owned()
     );
 }
+
+// SHA-1 of "password123" is CBFDAC6008F9CAB4083784CBD1874F76618D2A97
+async fn mount_breached_range(
+    app: &BreachCheckTestApp,
+    status: u16,
+    range: &str,
+) {
+    Mock::given(method("GET"))
+        .and(path("/range/CBFDA"))
+        .respond_with(ResponseTemplate::new(status).set_body_string(range))
+        .mount(&app.breached_password_server)
+        .await;
+}
+
+#[test_context(BreachCheckTestApp)]
+#[tokio::test]
+async fn should_return_400_if_password_is_breached(
+    app: &mut BreachCheckTestApp,
+) {
+    mount_breached_range(
+        app,
+        200,
+        "0018A45C4D1DEF81644B54AB7F969B88D65:0\r\n\
+         C6008F9CAB4083784CBD1874F76618D2A97:2254650\r\n",
+    )
+    .await;
+
+    let response = app
+        .app
+        .post_signup(&serde_json::json!({
+            "email": get_random_email(),
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialise response body to ErrorResponse")
+            .error,
+        "Password has appeared in a data breach. Choose another one."
+    );
+}
+
+#[test_context(BreachCheckTestApp)]
+#[tokio::test]
+async fn should_return_201_if_password_is_only_padding_in_range(
+    app: &mut BreachCheckTestApp,
+) {
+    mount_breached_range(app, 200, "C6008F9CAB4083784CBD1874F76618D2A97:0\r\n")
+        .await;
+
+    let response = app
+        .app
+        .post_signup(&serde_json::json!({
+            "email": get_random_email(),
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 201);
+}
+
+#[test_context(BreachCheckTestApp)]
+#[tokio::test]
+async fn should_return_503_if_breach_check_is_unavailable(
+    app: &mut BreachCheckTestApp,
+) {
+    mount_breached_range(app, 503, "").await;
+
+    let response = app
+        .app
+        .post_signup(&serde_json::json!({
+            "email": get_random_email(),
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 503);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Password could not be checked for data breaches. Try again later."
+    );
+}
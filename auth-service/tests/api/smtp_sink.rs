@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::Mutex,
+};
+
+// A local SMTP server that accepts everything and keeps what it was sent,
+// the way wiremock stands in for Postmark
+pub struct SmtpSink {
+    pub port: u16,
+    state: Arc<Mutex<SinkState>>,
+}
+
+#[derive(Default)]
+struct SinkState {
+    connections: usize,
+    auth: Vec<String>,
+    messages: Vec<String>,
+}
+
+impl SmtpSink {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let state = Arc::new(Mutex::new(SinkState::default()));
+
+        let accept_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                accept_state.lock().await.connections += 1;
+                tokio::spawn(handle_connection(stream, accept_state.clone()));
+            }
+        });
+
+        Self { port, state }
+    }
+
+    pub async fn connections(&self) -> usize {
+        self.state.lock().await.connections
+    }
+
+    // The AUTH commands received, without the mechanism
+    pub async fn auth(&self) -> Vec<String> {
+        self.state.lock().await.auth.clone()
+    }
+
+    pub async fn messages(&self) -> Vec<String> {
+        self.state.lock().await.messages.clone()
+    }
+}
+
+async fn handle_connection(stream: TcpStream, state: Arc<Mutex<SinkState>>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let command = line.to_uppercase();
+        let reply: &[u8] = if command.starts_with("EHLO") {
+            b"250-localhost\r\n250 AUTH PLAIN LOGIN\r\n"
+        } else if let Some(credentials) = line.strip_prefix("AUTH PLAIN ") {
+            state.lock().await.auth.push(credentials.to_owned());
+            b"235 2.7.0 Authentication successful\r\n"
+        } else if command == "DATA" {
+            writer
+                .write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n")
+                .await
+                .unwrap();
+            let mut message = String::new();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if line == "." {
+                    break;
+                }
+                message.push_str(&line);
+                message.push('\n');
+            }
+            state.lock().await.messages.push(message);
+            b"250 2.0.0 Ok: queued\r\n"
+        } else if command == "QUIT" {
+            writer.write_all(b"221 2.0.0 Bye\r\n").await.unwrap();
+            return;
+        } else {
+            b"250 2.0.0 Ok\r\n"
+        };
+        writer.write_all(reply).await.unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+use crate::helpers::{get_random_email, sign_in, TestApp};
+use auth_service::{
+    domain::Email,
+    routes::TrustedDeviceResponse,
+    utils::constants::{JWT_COOKIE_NAME, TRUSTED_DEVICE_COOKIE_NAME},
+};
+use reqwest::header::USER_AGENT;
+use secrecy::{ExposeSecret, Secret};
+use test_context::test_context;
+use wiremock::{matchers::method, matchers::path, Mock, ResponseTemplate};
+
+const TEST_USER_AGENT: &str = "Test browser";
+
+// Sign up a 2FA user and log in with the emailed code, asking to remember
+// the device if requested
+async fn sign_in_with_2fa(app: &TestApp, remember_device: bool) -> String {
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password",
+            "requires2FA": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = login(app, &email).await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let (login_attempt_id, two_fa_code) = app
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(&Email::parse(Secret::new(email.clone())).unwrap())
+        .await
+        .unwrap();
+
+    let response = app
+        .http_client
+        .post(format!("{}/verify-2fa", &app.address))
+        .header(USER_AGENT, TEST_USER_AGENT)
+        .json(&serde_json::json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id.as_ref().expose_secret(),
+            "2FACode": two_fa_code.as_ref().expose_secret(),
+            "rememberDevice": remember_device
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response
+            .cookies()
+            .any(|cookie| cookie.name() == TRUSTED_DEVICE_COOKIE_NAME),
+        remember_device
+    );
+
+    email
+}
+
+// Log in from the same browser, so no new device email is sent
+async fn login(app: &TestApp, email: &str) -> reqwest::Response {
+    app.http_client
+        .post(format!("{}/login", &app.address))
+        .header(USER_AGENT, TEST_USER_AGENT)
+        .json(&serde_json::json!({
+            "email": email,
+            "password": "password"
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request")
+}
+
+async fn mock_email_server(app: &TestApp, expected_emails: u64) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(expected_emails)
+        .mount(&app.email_server)
+        .await;
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_skip_2fa_on_trusted_device(app: &mut TestApp) {
+    mock_email_server(app, 1).await;
+    let email = sign_in_with_2fa(app, true).await;
+
+    let response = login(app, &email).await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response
+        .cookies()
+        .any(|cookie| cookie.name() == JWT_COOKIE_NAME));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_require_2fa_if_device_not_remembered(app: &mut TestApp) {
+    mock_email_server(app, 2).await;
+    let email = sign_in_with_2fa(app, false).await;
+
+    let response = login(app, &email).await;
+
+    assert_eq!(response.status().as_u16(), 206);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_list_trusted_devices(app: &mut TestApp) {
+    mock_email_server(app, 1).await;
+    sign_in_with_2fa(app, true).await;
+
+    let response = app.get_trusted_devices().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    let devices = response
+        .json::<Vec<TrustedDeviceResponse>>()
+        .await
+        .expect("Could not deserialize response body to devices");
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].name, TEST_USER_AGENT);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_require_2fa_after_device_revoked(app: &mut TestApp) {
+    mock_email_server(app, 2).await;
+    let email = sign_in_with_2fa(app, true).await;
+
+    let devices = app
+        .get_trusted_devices()
+        .await
+        .json::<Vec<TrustedDeviceResponse>>()
+        .await
+        .unwrap();
+    let response = app.delete_trusted_device(&devices[0].device_id).await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = login(app, &email).await;
+
+    assert_eq!(response.status().as_u16(), 206);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_another_users_device(app: &mut TestApp) {
+    mock_email_server(app, 1).await;
+    sign_in_with_2fa(app, true).await;
+
+    let devices = app
+        .get_trusted_devices()
+        .await
+        .json::<Vec<TrustedDeviceResponse>>()
+        .await
+        .unwrap();
+
+    sign_in(app).await;
+    let response = app.delete_trusted_device(&devices[0].device_id).await;
+
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_no_session(app: &mut TestApp) {
+    let response = app.get_trusted_devices().await;
+
+    assert_eq!(response.status().as_u16(), 400);
+}
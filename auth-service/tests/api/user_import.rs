@@ -0,0 +1,188 @@
+use auth_service::{
+    routes::{ImportUserFailure, ImportUsersResponse},
+    utils::constants::{ADMIN_SCOPE, VERIFY_TOKEN_SCOPE},
+};
+use test_context::test_context;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::{get_random_email, get_service_token, TestApp};
+
+// Both of "password123"
+const BCRYPT_HASH: &str =
+    "$2b$04$saltsaltsaltsaltsalt1uShJHmKw.Ovu4GAyULDoQiKPbIPiYlz2";
+const DJANGO_HASH: &str = "pbkdf2_sha256$1000$seasalt123$YzR5A63TtcprNwkafqZfuJ2ymncmHl07IsPj6CMT4gg=";
+
+async fn import_json(
+    app: &TestApp,
+    users: serde_json::Value,
+) -> ImportUsersResponse {
+    let token = get_service_token(app, ADMIN_SCOPE).await;
+    let response = app
+        .post_user_import(users.to_string(), "application/json", &token)
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    response
+        .json()
+        .await
+        .expect("Could not deserialize response body to ImportUsersResponse")
+}
+
+async fn login(app: &TestApp, email: &str, password: &str) -> u16 {
+    app.post_login(&serde_json::json!({
+        "email": email,
+        "password": password,
+    }))
+    .await
+    .status()
+    .as_u16()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_import_legacy_hashes_and_upgrade_them_on_login(
+    app: &mut TestApp,
+) {
+    let bcrypt_email = get_random_email();
+    let django_email = get_random_email();
+
+    let response = import_json(
+        app,
+        serde_json::json!([
+            {
+                "email": bcrypt_email,
+                "password_hash": BCRYPT_HASH,
+                "requires2FA": false
+            },
+            { "email": django_email, "password_hash": DJANGO_HASH }
+        ]),
+    )
+    .await;
+    assert_eq!(
+        response,
+        ImportUsersResponse {
+            imported: 2,
+            failed: Vec::new()
+        }
+    );
+
+    for (email, legacy_hash) in
+        [(&bcrypt_email, BCRYPT_HASH), (&django_email, DJANGO_HASH)]
+    {
+        assert_eq!(app.get_password_hash(email).await, legacy_hash);
+        assert_eq!(login(app, email, "password124").await, 401);
+        assert_eq!(app.get_password_hash(email).await, legacy_hash);
+
+        assert_eq!(login(app, email, "password123").await, 200);
+        let password_hash = app.get_password_hash(email).await;
+        assert!(password_hash.starts_with("$argon2id$"), "{}", password_hash);
+        assert_eq!(login(app, email, "password123").await, 200);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_import_users_from_csv(app: &mut TestApp) {
+    let email = get_random_email();
+    let token = get_service_token(app, ADMIN_SCOPE).await;
+    let csv = format!(
+        "email,password_hash,requires_2fa\n{},{},true\nnot-an-email,{},false\n",
+        email, BCRYPT_HASH, BCRYPT_HASH
+    );
+
+    let response = app.post_user_import(csv, "text/csv", &token).await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    let response = response.json::<ImportUsersResponse>().await.unwrap();
+    assert_eq!(response.imported, 1);
+    assert_eq!(
+        response.failed,
+        [ImportUserFailure {
+            row: 2,
+            email: Some("not-an-email".to_owned()),
+            error: "Invalid email".to_owned()
+        }]
+    );
+    // 2FA was kept, so signing in sends a code
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+    assert_eq!(login(app, &email, "password123").await, 206);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_report_users_that_cannot_be_imported(app: &mut TestApp) {
+    let existing_email = get_random_email();
+    let unknown_format_email = get_random_email();
+    app.post_signup(&serde_json::json!({
+        "email": existing_email,
+        "password": "password123",
+        "requires2FA": false
+    }))
+    .await;
+
+    let response = import_json(
+        app,
+        serde_json::json!([
+            { "email": existing_email, "password_hash": BCRYPT_HASH },
+            {
+                "email": unknown_format_email,
+                "password_hash": "$1$saltsalt$md5cryptisnotsupported"
+            }
+        ]),
+    )
+    .await;
+
+    assert_eq!(response.imported, 0);
+    assert_eq!(
+        response.failed,
+        [
+            ImportUserFailure {
+                row: 1,
+                email: Some(existing_email.clone()),
+                error: "User already exists".to_owned()
+            },
+            ImportUserFailure {
+                row: 2,
+                email: Some(unknown_format_email.clone()),
+                error: "Unrecognised password hash format".to_owned()
+            }
+        ]
+    );
+    assert_eq!(login(app, &existing_email, "password123").await, 200);
+    assert_eq!(login(app, &unknown_format_email, "password123").await, 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_json_is_malformed(app: &mut TestApp) {
+    let token = get_service_token(app, ADMIN_SCOPE).await;
+
+    let response = app
+        .post_user_import(
+            r#"{"email": "user@example.com"}"#.to_owned(),
+            "application/json",
+            &token,
+        )
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_403_without_admin_scope(app: &mut TestApp) {
+    let token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
+
+    let response = app
+        .post_user_import("[]".to_owned(), "application/json", &token)
+        .await;
+
+    assert_eq!(response.status().as_u16(), 403);
+}
@@ -1,5 +1,10 @@
-use crate::helpers::{get_random_email, TestApp};
-use auth_service::utils::constants::JWT_COOKIE_NAME;
+use crate::helpers::{
+    get_random_email, get_service_token, get_tokens, register_client, sign_in,
+    TestApp,
+};
+use auth_service::utils::constants::{
+    ADMIN_SCOPE, JWT_COOKIE_NAME, VERIFY_TOKEN_SCOPE,
+};
 use serde_json::json;
 use test_context::test_context;
 
@@ -35,13 +40,17 @@ async fn should_return_200_for_valid_token(app: &mut TestApp) {
         .expect("No auth cookie found");
 
     let token = auth_cookie.value();
+    let service_token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
 
     assert_eq!(
-        app.post_verify_token(&serde_json::json!(
-            {
-                "token": token
-            }
-        ))
+        app.post_verify_token(
+            &serde_json::json!(
+                {
+                    "token": token
+                }
+            ),
+            &service_token
+        )
         .await
         .status()
         .as_u16(),
@@ -57,8 +66,9 @@ async fn should_return_401_if_invalid_token(app: &mut TestApp) {
         JWT_COOKIE_NAME
     );
     let body = serde_json::json!({ "token": token });
+    let service_token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
 
-    let response = app.post_verify_token(&body).await;
+    let response = app.post_verify_token(&body, &service_token).await;
 
     assert_eq!(response.status().as_u16(), 401);
 }
@@ -71,9 +81,10 @@ async fn should_return_422_if_malformed_input(app: &mut TestApp) {
         json!({"token": 1}),
         json!({"broken": "token"}),
     ];
+    let service_token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
 
     for request in invalid_requests {
-        let response = app.post_verify_token(&request).await;
+        let response = app.post_verify_token(&request, &service_token).await;
         assert_eq!(response.status().as_u16(), 422);
     }
 }
@@ -112,9 +123,10 @@ async fn should_return_401_if_banned_token(app: &mut TestApp) {
         .to_owned();
 
     let verify_token_body = serde_json::json!({"token": &token});
+    let service_token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
 
     assert_eq!(
-        app.post_verify_token(&verify_token_body)
+        app.post_verify_token(&verify_token_body, &service_token)
             .await
             .status()
             .as_u16(),
@@ -123,7 +135,84 @@ async fn should_return_401_if_banned_token(app: &mut TestApp) {
 
     assert_eq!(app.post_logout().await.status().as_u16(), 200);
 
-    let response = app.post_verify_token(&verify_token_body).await;
+    let response = app
+        .post_verify_token(&verify_token_body, &service_token)
+        .await;
 
     assert_eq!(response.status().as_u16(), 401);
 }
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_without_service_token(app: &mut TestApp) {
+    let response = app
+        .http_client
+        .post(format!("{}/verify-token", &app.address))
+        .json(&json!({ "token": "token" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_caller_is_not_a_service(app: &mut TestApp) {
+    let email = get_random_email();
+    app.post_signup(&json!({
+        "email": email,
+        "password": "password",
+        "requires2FA": false
+    }))
+    .await;
+    let login_response = app
+        .post_login(&json!({
+            "email": email,
+            "password": "password"
+        }))
+        .await;
+    let user_token = login_response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie found")
+        .value()
+        .to_owned();
+
+    // A user's own session token doesn't make them a service
+    let response = app
+        .post_verify_token(&json!({ "token": &user_token }), &user_token)
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_403_without_verify_scope(app: &mut TestApp) {
+    let service_token = get_service_token(app, ADMIN_SCOPE).await;
+
+    let response = app
+        .post_verify_token(&json!({ "token": "token" }), &service_token)
+        .await;
+
+    assert_eq!(response.status().as_u16(), 403);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_for_oauth_and_service_tokens(app: &mut TestApp) {
+    sign_in(app).await;
+    let client = register_client(app, "client_secret_basic").await;
+    let access_token = get_tokens(app, &client, "read").await.access_token;
+    let other_service_token = get_service_token(app, ADMIN_SCOPE).await;
+    let service_token = get_service_token(app, VERIFY_TOKEN_SCOPE).await;
+
+    // Neither is a user's browser session, however narrowly it's scoped
+    for token in [access_token, other_service_token] {
+        let response = app
+            .post_verify_token(&json!({ "token": token }), &service_token)
+            .await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+}
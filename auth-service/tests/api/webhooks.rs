@@ -0,0 +1,259 @@
+use std::{collections::HashSet, time::Duration};
+
+use crate::helpers::{get_random_email, sign_in, TestApp, WEBHOOK_SECRET};
+use auth_service::{
+    domain::{Email, WebhookDelivery, WebhookStore},
+    services::webhook_dispatcher::sign_webhook,
+    utils::constants::{
+        WEBHOOK_ID_HEADER, WEBHOOK_SIGNATURE_HEADER, WEBHOOK_TIMESTAMP_HEADER,
+    },
+};
+use secrecy::{ExposeSecret, Secret};
+use test_context::test_context;
+use wiremock::{
+    matchers::{method, path},
+    Mock, Request, ResponseTemplate,
+};
+
+async fn mock_webhook_server(app: &TestApp, status: u16) {
+    Mock::given(path("/webhooks"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(status))
+        .mount(&app.webhook_server)
+        .await;
+}
+
+// Deliveries happen in the background, so poll until enough have arrived
+async fn wait_for_webhooks(app: &TestApp, count: usize) -> Vec<Request> {
+    for _ in 0..100 {
+        let requests = app.webhook_server.received_requests().await.unwrap();
+        if requests.len() >= count {
+            return requests;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("Timed out waiting for {} webhooks", count);
+}
+
+fn event_of_type(requests: &[Request], event_type: &str) -> serde_json::Value {
+    requests
+        .iter()
+        .map(|request| request.body_json::<serde_json::Value>().unwrap())
+        .find(|event| event["type"] == event_type)
+        .unwrap_or_else(|| panic!("No {} webhook was sent", event_type))
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_send_signed_webhook_on_signup(app: &mut TestApp) {
+    mock_webhook_server(app, 200).await;
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let requests = wait_for_webhooks(app, 1).await;
+    let request = &requests[0];
+    let event: serde_json::Value = request.body_json().unwrap();
+    assert_eq!(event["type"], "user.signed_up");
+    assert_eq!(event["data"]["email"], email);
+    assert_eq!(event["data"]["requires2FA"], true);
+    assert!(!request.headers[WEBHOOK_ID_HEADER].is_empty());
+
+    let timestamp: u64 = request.headers[WEBHOOK_TIMESTAMP_HEADER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let payload = std::str::from_utf8(&request.body).unwrap();
+    let expected_signature = sign_webhook(
+        &Secret::new(WEBHOOK_SECRET.to_owned()),
+        timestamp,
+        payload,
+    )
+    .unwrap();
+    assert_eq!(
+        request.headers[WEBHOOK_SIGNATURE_HEADER].to_str().unwrap(),
+        expected_signature
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_retry_failed_webhooks(app: &mut TestApp) {
+    Mock::given(path("/webhooks"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .mount(&app.webhook_server)
+        .await;
+    mock_webhook_server(app, 200).await;
+
+    sign_in(app).await;
+
+    let requests = wait_for_webhooks(app, 2).await;
+    assert_eq!(requests[0].body, requests[1].body);
+    assert_eq!(
+        requests[0].headers[WEBHOOK_ID_HEADER],
+        requests[1].headers[WEBHOOK_ID_HEADER]
+    );
+    assert!(app
+        .webhook_store
+        .read()
+        .await
+        .get_dead_letters()
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_dead_letter_webhooks_after_max_attempts(app: &mut TestApp) {
+    mock_webhook_server(app, 500).await;
+
+    let email = sign_in(app).await;
+
+    for _ in 0..100 {
+        let dead_letters = app
+            .webhook_store
+            .read()
+            .await
+            .get_dead_letters()
+            .await
+            .unwrap();
+        if let Some(delivery) = dead_letters.first() {
+            assert_eq!(delivery.attempts, 3);
+            assert!(delivery.payload.contains(&email));
+            assert!(delivery.last_error.is_some());
+            assert_eq!(wait_for_webhooks(app, 3).await.len(), 3);
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("Webhook was never dead-lettered");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_send_webhooks_on_2fa_change_and_verification(
+    app: &mut TestApp,
+) {
+    mock_webhook_server(app, 200).await;
+    let email = sign_in(app).await;
+
+    let response = app
+        .put_account_2fa(&serde_json::json!({ "requires2FA": true }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let requests = wait_for_webhooks(app, 2).await;
+    let event = event_of_type(&requests, "user.2fa_changed");
+    assert_eq!(event["data"]["email"], email);
+    assert_eq!(event["data"]["requires2FA"], true);
+
+    // Logging in now needs a code
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let parsed_email = Email::parse(Secret::new(email.clone())).unwrap();
+    let (login_attempt_id, two_fa_code) = app
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(&parsed_email)
+        .await
+        .unwrap();
+    let response = app
+        .post_verify_2fa(&serde_json::json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id.as_ref().expose_secret(),
+            "2FACode": two_fa_code.as_ref().expose_secret()
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let requests = wait_for_webhooks(app, 3).await;
+    let event = event_of_type(&requests, "user.login_verified");
+    assert_eq!(event["data"]["email"], email);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_send_webhook_on_delete(app: &mut TestApp) {
+    mock_webhook_server(app, 200).await;
+    let email = sign_in(app).await;
+
+    let response = app
+        .delete_user(&serde_json::json!({ "email": email }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let requests = wait_for_webhooks(app, 2).await;
+    let event = event_of_type(&requests, "user.deleted");
+    assert_eq!(event["data"]["email"], email);
+    assert!(event["data"].get("requires2FA").is_none());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_changing_2fa_without_session(app: &mut TestApp) {
+    let response = app
+        .put_account_2fa(&serde_json::json!({ "requires2FA": true }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_let_two_workers_claim_the_same_delivery(app: &mut TestApp) {
+    // Due far in the future, so this app's own worker leaves them alone
+    let due_at = 1 << 50;
+    let mut first_store = app.new_webhook_store();
+    let mut second_store = app.new_webhook_store();
+    for _ in 0..10 {
+        first_store
+            .add_delivery(WebhookDelivery::new(
+                "http://localhost/webhooks".to_owned(),
+                "{}".to_owned(),
+                due_at,
+            ))
+            .await
+            .unwrap();
+    }
+
+    let (first, second) = tokio::join!(
+        first_store.claim_due_deliveries(due_at, due_at + 100, 6),
+        second_store.claim_due_deliveries(due_at, due_at + 100, 6),
+    );
+    let first: HashSet<String> = first
+        .unwrap()
+        .into_iter()
+        .map(|delivery| delivery.delivery_id)
+        .collect();
+    let second: HashSet<String> = second
+        .unwrap()
+        .into_iter()
+        .map(|delivery| delivery.delivery_id)
+        .collect();
+
+    assert!(first.is_disjoint(&second));
+    assert_eq!(first.len() + second.len(), 10);
+}
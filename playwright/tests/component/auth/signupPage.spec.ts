@@ -125,6 +125,34 @@ test.describe("Signup Page - Error Handling", () => {
     );
   });
 
+  test("Should list each password policy violation", async ({ page }) => {
+    const violations = [
+      {
+        rule: "min_length",
+        message: "Too short. Should be 8 to 128 characters.",
+      },
+      { rule: "banned_word", message: 'Contains "foo", which isn\'t allowed.' },
+    ];
+    await page.route("**/auth/signup", (route) =>
+      route.fulfill({
+        status: 400,
+        json: { error: "Password does not meet the policy", violations },
+      })
+    );
+
+    await completeForm("foo@bar.com", "foo", false);
+
+    await expect(signupPage.signupForm().error()).toBeVisible();
+    await expect(signupPage.signupForm().error()).toContainText(
+      signupContent.errorMessages.passwordPolicy
+    );
+    for (const violation of violations) {
+      await expect(signupPage.signupForm().error()).toContainText(
+        violation.message
+      );
+    }
+  });
+
   test("Should handle HTTP409 errors", async ({ page }) => {
     await page.route("**/auth/signup", (route) =>
       route.fulfill({
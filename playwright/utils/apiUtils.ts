@@ -1,7 +1,12 @@
 import { expect, APIRequestContext } from "@playwright/test";
 import { getEnv } from "./envUtils";
 
-const postmarkServerToken: string = getEnv("POSTMARK_AUTH_TOKEN");
+// With `DEV_MAILBOX_ENABLED=true` the auth service serves emails from its dev
+// mailbox
+const useMailbox: boolean = process.env.DEV_MAILBOX_ENABLED === "true";
+const postmarkServerToken: string = useMailbox
+  ? ""
+  : getEnv("POSTMARK_AUTH_TOKEN");
 
 async function fetchLatestMessageId(
   request: APIRequestContext,
@@ -67,15 +72,51 @@ async function fetchMessageTextBody(
   );
 }
 
+async function fetchLatestMailboxTextBody(
+  request: APIRequestContext,
+  recipientEmail: string,
+  maxTimeoutMs: number
+): Promise<string> {
+  const startTime = Date.now();
+  while (Date.now() - startTime < maxTimeoutMs) {
+    const messagesResponse = await request.get("/auth/dev/mailbox", {
+      params: { to: recipientEmail },
+    });
+    expect(messagesResponse.ok()).toBeTruthy();
+
+    // Newest first
+    const messages = await messagesResponse.json();
+    if (messages.length > 0) {
+      const messageResponse = await request.get(
+        `/auth/dev/mailbox/${messages[0].id}`
+      );
+      expect(messageResponse.ok()).toBeTruthy();
+      const message = await messageResponse.json();
+      return message.text_body;
+    }
+    console.log("No messages yet. Waiting 1s ...");
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+  }
+  throw new Error("Timeout: No message received after " + maxTimeoutMs + "ms.");
+}
+
 export async function retrieveTwoFaCode(
   request: APIRequestContext,
   recipientEmail: string
 ): Promise<string> {
-  const messageId = await fetchLatestMessageId(request, recipientEmail, 30000); // 30 sec timeout
-  expect(messageId).toBeDefined();
+  let textBody: string;
+  if (useMailbox) {
+    textBody = await fetchLatestMailboxTextBody(request, recipientEmail, 30000); // 30 sec timeout
+  } else {
+    const messageId = await fetchLatestMessageId(request, recipientEmail, 30000); // 30 sec timeout
+    expect(messageId).toBeDefined();
 
-  const textBody = await fetchMessageTextBody(request, messageId, 30000); // 30 sec timeout
+    textBody = await fetchMessageTextBody(request, messageId, 30000); // 30 sec timeout
+  }
   expect(textBody).toBeDefined();
 
-  return textBody;
+  // The code is on a line of its own in the email
+  const code = textBody.match(/^\s*(\d{6})\s*$/m);
+  expect(code).not.toBeNull();
+  return code![1];
 }